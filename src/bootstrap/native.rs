@@ -674,6 +674,15 @@ fn supported_sanitizers(
                 });
             }
         }
+        "aarch64-unknown-linux-gnu" => {
+            for s in &["hwasan"] {
+                result.push(SanitizerRuntime {
+                    cmake_target: format!("clang_rt.{}-aarch64", s),
+                    path: out_dir.join(&format!("build/lib/linux/libclang_rt.{}-aarch64.a", s)),
+                    name: format!("librustc-{}_rt.{}.a", channel, s),
+                });
+            }
+        }
         "aarch64-fuchsia" => {
             for s in &["asan"] {
                 result.push(SanitizerRuntime {
@@ -40,6 +40,12 @@ TYPE_KIND_OS_STRING         = 18
 TYPE_KIND_STD_VECDEQUE      = 19
 TYPE_KIND_STD_BTREESET      = 20
 TYPE_KIND_STD_BTREEMAP      = 21
+TYPE_KIND_STD_HASHMAP       = 22
+TYPE_KIND_STD_HASHSET       = 23
+TYPE_KIND_STD_RC            = 24
+TYPE_KIND_STD_ARC           = 25
+TYPE_KIND_STD_CELL          = 26
+TYPE_KIND_STD_REFCELL       = 27
 
 ENCODED_ENUM_PREFIX = "RUST$ENCODED$ENUM$"
 ENUM_DISR_FIELD_NAME = "RUST$ENUM$DISR"
@@ -69,6 +75,40 @@ STD_BTREESET_FIELD_NAMES = ["map"]
 # std::collections::BTreeMap<> related constants
 STD_BTREEMAP_FIELD_NAMES = ["root", "length"]
 
+# std::collections::HashMap<> related constants
+STD_HASHMAP_FIELD_NAMES = ["base"]
+
+# std::collections::HashSet<> related constants
+STD_HASHSET_FIELD_NAMES = ["map"]
+
+# hashbrown::HashMap<> related constants. This is the type that backs the
+# std::collections::HashMap<> above.
+HASHBROWN_HASHMAP_FIELD_NAME_TABLE = "table"
+
+# hashbrown::raw::RawTable<> related constants. Before hashbrown 0.7 the
+# elements were kept in a separate allocation pointed to by the `data` field,
+# since then they are stored right before the control bytes, in reverse order.
+RAW_TABLE_FIELD_NAME_BUCKET_MASK = "bucket_mask"
+RAW_TABLE_FIELD_NAME_CTRL = "ctrl"
+RAW_TABLE_FIELD_NAME_DATA = "data"
+RAW_TABLE_FIELD_NAME_ITEMS = "items"
+
+# The top bit of a control byte is set for empty and deleted buckets.
+RAW_TABLE_CTRL_EMPTY_OR_DELETED_BIT = 0x80
+
+# std::rc::Rc<> and std::sync::Arc<> related constants
+STD_RC_FIELD_NAME_PTR = "ptr"
+STD_RC_FIELD_NAMES = [STD_RC_FIELD_NAME_PTR, "phantom"]
+
+# std::cell::Cell<> related constants
+STD_CELL_FIELD_NAMES = ["value"]
+
+# std::cell::RefCell<> related constants
+STD_REFCELL_FIELD_NAME_BORROW = "borrow"
+STD_REFCELL_FIELD_NAME_VALUE = "value"
+STD_REFCELL_FIELD_NAMES = [STD_REFCELL_FIELD_NAME_BORROW,
+                           STD_REFCELL_FIELD_NAME_VALUE]
+
 # std::String related constants
 STD_STRING_FIELD_NAMES = ["vec"]
 
@@ -117,6 +157,14 @@ class Type(object):
                 (self.get_dwarf_type_kind() == DWARF_TYPE_CODE_UNION))
         raise NotImplementedError("Override this method")
 
+    def get_enum_values(self):
+        """
+        Implementations of this method should return the list of the values of
+        the enumerators of this type, which must have DWARF_TYPE_CODE_ENUM.
+        """
+        assert self.get_dwarf_type_kind() == DWARF_TYPE_CODE_ENUM
+        raise NotImplementedError("Override this method")
+
     def get_wrapped_value(self):
         """
         Returns the debugger-specific type-object wrapped by this object. This
@@ -183,6 +231,36 @@ class Type(object):
                 self.__conforms_to_field_layout(STD_BTREEMAP_FIELD_NAMES)):
             return TYPE_KIND_STD_BTREEMAP
 
+        # STD COLLECTION HASHMAP
+        if (unqualified_type_name.startswith("HashMap<") and
+                self.__conforms_to_field_layout(STD_HASHMAP_FIELD_NAMES)):
+            return TYPE_KIND_STD_HASHMAP
+
+        # STD COLLECTION HASHSET
+        if (unqualified_type_name.startswith("HashSet<") and
+                self.__conforms_to_field_layout(STD_HASHSET_FIELD_NAMES)):
+            return TYPE_KIND_STD_HASHSET
+
+        # STD RC
+        if (unqualified_type_name.startswith("Rc<") and
+                self.__conforms_to_field_layout(STD_RC_FIELD_NAMES)):
+            return TYPE_KIND_STD_RC
+
+        # STD ARC
+        if (unqualified_type_name.startswith("Arc<") and
+                self.__conforms_to_field_layout(STD_RC_FIELD_NAMES)):
+            return TYPE_KIND_STD_ARC
+
+        # STD CELL
+        if (unqualified_type_name.startswith("Cell<") and
+                self.__conforms_to_field_layout(STD_CELL_FIELD_NAMES)):
+            return TYPE_KIND_STD_CELL
+
+        # STD REFCELL
+        if (unqualified_type_name.startswith("RefCell<") and
+                self.__conforms_to_field_layout(STD_REFCELL_FIELD_NAMES)):
+            return TYPE_KIND_STD_REFCELL
+
         # STD STRING
         if (unqualified_type_name.startswith("String") and
             self.__conforms_to_field_layout(STD_STRING_FIELD_NAMES)):
@@ -269,6 +347,17 @@ class Value(object):
         """Returns the value of the field, array element or variant at the given index"""
         raise NotImplementedError("Override this method")
 
+    def get_child_by_name(self, name):
+        """Returns the value of the field with the given name, or None"""
+        for index, field in enumerate(self.type.get_fields()):
+            if field.name == name:
+                return self.get_child_at_index(index)
+        return None
+
+    def dereference(self):
+        """Returns the value this pointer value points to"""
+        raise NotImplementedError("Override this method")
+
     def as_integer(self):
         """
         Try to convert the wrapped value into a Python integer. This should
@@ -276,6 +365,13 @@ class Value(object):
         """
         raise NotImplementedError("Override this method")
 
+    def as_signed_integer(self):
+        """
+        Like as_integer(), but interprets integers with the top bit set as
+        negative numbers.
+        """
+        raise NotImplementedError("Override this method")
+
     def get_wrapped_value(self):
         """
         Returns the debugger-specific value-object wrapped by this object. This
@@ -312,7 +408,19 @@ class EncodedEnumInfo(object):
         if discriminant_val.type.get_dwarf_type_kind() == DWARF_TYPE_CODE_STRUCT:
             discriminant_val = discriminant_val.get_child_at_index(0)
 
-        return discriminant_val.as_integer() == 0
+        # The null variant is stored as a value that is invalid for the type of
+        # the discriminant field. That is zero for references, boxes and the
+        # NonZero integers, but e.g. `Option<bool>` uses 2 for `None`.
+        discriminant = discriminant_val.as_integer()
+        discriminant_type = discriminant_val.type
+        if discriminant_type.get_dwarf_type_kind() == DWARF_TYPE_CODE_ENUM:
+            return discriminant not in discriminant_type.get_enum_values()
+        discriminant_type_name = discriminant_type.get_unqualified_type_name()
+        if discriminant_type_name == "bool":
+            return discriminant > 1
+        if discriminant_type_name == "char":
+            return discriminant > 0x10FFFF
+        return discriminant == 0
 
     def get_non_null_variant_val(self):
         return self.__enum_val.get_child_at_index(0)
@@ -362,6 +470,94 @@ def extract_tail_head_ptr_and_cap_from_std_vecdeque(vec_val):
     return (tail, head, data_ptr, capacity)
 
 
+def extract_raw_table_from_std_hashmap(hashmap_val):
+    type_kind = hashmap_val.type.get_type_kind()
+    assert (type_kind == TYPE_KIND_STD_HASHMAP or
+            type_kind == TYPE_KIND_STD_HASHSET)
+
+    # A HashSet<T> is a HashMap<T, ()>
+    if type_kind == TYPE_KIND_STD_HASHSET:
+        hashmap_val = hashmap_val.get_child_at_index(0)
+
+    base = hashmap_val.get_child_at_index(0)
+    return base.get_child_by_name(HASHBROWN_HASHMAP_FIELD_NAME_TABLE)
+
+
+def extract_items_buckets_ctrl_and_data_from_raw_table(table_val):
+    """
+    Returns the number of elements in the table, the number of its buckets,
+    the pointer to its control bytes and the pointer to the element of the
+    first bucket, which is None if the elements are stored right before the
+    control bytes.
+    """
+    items = table_val.get_child_by_name(RAW_TABLE_FIELD_NAME_ITEMS).as_integer()
+    bucket_mask = table_val.get_child_by_name(RAW_TABLE_FIELD_NAME_BUCKET_MASK).as_integer()
+    ctrl_ptr = table_val.get_child_by_name(RAW_TABLE_FIELD_NAME_CTRL).get_child_at_index(0)
+    assert ctrl_ptr.type.get_dwarf_type_kind() == DWARF_TYPE_CODE_PTR
+
+    data = table_val.get_child_by_name(RAW_TABLE_FIELD_NAME_DATA)
+    data_ptr = None
+    if data is not None:
+        data_ptr = data.get_child_at_index(0)
+        assert data_ptr.type.get_dwarf_type_kind() == DWARF_TYPE_CODE_PTR
+
+    # Empty tables share a statically allocated group of control bytes, which
+    # must not be read as a table of bucket_mask + 1 buckets.
+    if items == 0:
+        return (0, 0, ctrl_ptr, data_ptr)
+    return (items, bucket_mask + 1, ctrl_ptr, data_ptr)
+
+
+def is_raw_table_bucket_full(ctrl_byte):
+    return (ctrl_byte & RAW_TABLE_CTRL_EMPTY_OR_DELETED_BIT) == 0
+
+
+def extract_strong_weak_and_value_from_std_rc(rc_val):
+    type_kind = rc_val.type.get_type_kind()
+    assert type_kind == TYPE_KIND_STD_RC or type_kind == TYPE_KIND_STD_ARC
+    ptr_field_index = STD_RC_FIELD_NAMES.index(STD_RC_FIELD_NAME_PTR)
+
+    # The `NonNull` pointer to the `RcBox` or `ArcInner`
+    inner_ptr = rc_val.get_child_at_index(ptr_field_index).get_child_at_index(0)
+    assert inner_ptr.type.get_dwarf_type_kind() == DWARF_TYPE_CODE_PTR
+    inner = inner_ptr.dereference()
+
+    # The counts are `Cell<usize>` for Rc and `AtomicUsize` for Arc, both of
+    # which keep their value in an `UnsafeCell<usize>`.
+    def count(index):
+        return inner.get_child_at_index(index).get_child_at_index(0).get_child_at_index(0)
+
+    strong = count(0).as_integer()
+    weak = count(1).as_integer()
+    # The strong pointers collectively hold one weak reference too
+    if strong > 0:
+        weak = weak - 1
+    return (strong, weak, inner.get_child_at_index(2))
+
+
+def extract_value_from_std_cell(cell_val):
+    assert cell_val.type.get_type_kind() == TYPE_KIND_STD_CELL
+    # The value is wrapped in an `UnsafeCell`
+    return cell_val.get_child_at_index(0).get_child_at_index(0)
+
+
+def extract_borrow_and_value_from_std_refcell(refcell_val):
+    """
+    Returns the borrow flag of the RefCell and its value. The flag is the
+    number of active shared borrows, or minus the number of active mutable
+    borrows.
+    """
+    assert refcell_val.type.get_type_kind() == TYPE_KIND_STD_REFCELL
+    borrow_field_index = STD_REFCELL_FIELD_NAMES.index(STD_REFCELL_FIELD_NAME_BORROW)
+    value_field_index = STD_REFCELL_FIELD_NAMES.index(STD_REFCELL_FIELD_NAME_VALUE)
+
+    # The flag is a `Cell<isize>`
+    borrow_cell = refcell_val.get_child_at_index(borrow_field_index)
+    borrow = borrow_cell.get_child_at_index(0).get_child_at_index(0).as_signed_integer()
+    value = refcell_val.get_child_at_index(value_field_index).get_child_at_index(0)
+    return (borrow, value)
+
+
 def extract_length_and_ptr_from_slice(slice_val):
     assert (slice_val.type.get_type_kind() == TYPE_KIND_SLICE or
             slice_val.type.get_type_kind() == TYPE_KIND_STR_SLICE)
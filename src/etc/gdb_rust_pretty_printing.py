@@ -61,6 +61,10 @@ class GdbType(rustpp.Type):
             self.fields = list(self.ty.fields())
         return self.fields
 
+    def get_enum_values(self):
+        assert self.get_dwarf_type_kind() == rustpp.DWARF_TYPE_CODE_ENUM
+        return [field.enumval for field in self.ty.fields()]
+
     def get_wrapped_value(self):
         return self.ty
 
@@ -79,12 +83,18 @@ class GdbValue(rustpp.Value):
             self.children[index] = child
         return child
 
+    def dereference(self):
+        return GdbValue(self.gdb_val.dereference())
+
     def as_integer(self):
         if self.gdb_val.type.code == gdb.TYPE_CODE_PTR:
             as_str = rustpp.compat_str(self.gdb_val).split()[0]
             return int(as_str, 0)
         return int(self.gdb_val)
 
+    def as_signed_integer(self):
+        return int(self.gdb_val)
+
     def get_wrapped_value(self):
         return self.gdb_val
 
@@ -118,6 +128,22 @@ def rust_pretty_printer_lookup_function(gdb_val):
     if type_kind == rustpp.TYPE_KIND_STD_BTREEMAP and gdb_81:
         return RustStdBTreeMapPrinter(val)
 
+    if type_kind == rustpp.TYPE_KIND_STD_HASHMAP:
+        return RustStdHashMapPrinter(val, want_values=True)
+
+    if type_kind == rustpp.TYPE_KIND_STD_HASHSET:
+        return RustStdHashMapPrinter(val, want_values=False)
+
+    if (type_kind == rustpp.TYPE_KIND_STD_RC or
+            type_kind == rustpp.TYPE_KIND_STD_ARC):
+        return RustStdRcPrinter(val)
+
+    if type_kind == rustpp.TYPE_KIND_STD_CELL:
+        return RustStdCellPrinter(val)
+
+    if type_kind == rustpp.TYPE_KIND_STD_REFCELL:
+        return RustStdRefCellPrinter(val)
+
     if type_kind == rustpp.TYPE_KIND_STD_STRING:
         return RustStdStringPrinter(val)
 
@@ -408,6 +434,99 @@ class RustStdBTreeMapPrinter(object):
                 i = i + 1
 
 
+# Yield each element of a hashbrown RawTable, in bucket order.
+def elements_of_raw_table(table_val):
+    (items, buckets, ctrl_ptr, data_ptr) = \
+        rustpp.extract_items_buckets_ctrl_and_data_from_raw_table(table_val)
+    gdb_ctrl_ptr = ctrl_ptr.get_wrapped_value()
+    if data_ptr is not None:
+        gdb_data_ptr = data_ptr.get_wrapped_value()
+    else:
+        element_type = table_val.get_wrapped_value().type.template_argument(0)
+        gdb_data_ptr = gdb_ctrl_ptr.cast(element_type.pointer())
+    found = 0
+    for index in xrange(0, buckets):
+        if found == items:
+            return
+        if not rustpp.is_raw_table_bucket_full(int((gdb_ctrl_ptr + index).dereference())):
+            continue
+        found = found + 1
+        if data_ptr is not None:
+            yield (gdb_data_ptr + index).dereference()
+        else:
+            yield (gdb_data_ptr - (index + 1)).dereference()
+
+
+class RustStdHashMapPrinter(object):
+    def __init__(self, val, want_values):
+        self.__val = val
+        self.__want_values = want_values
+
+    def display_hint(self):
+        if self.__want_values:
+            return "map"
+        else:
+            return "array"
+
+    def to_string(self):
+        table = rustpp.extract_raw_table_from_std_hashmap(self.__val)
+        (items, buckets, ctrl_ptr, data_ptr) = \
+            rustpp.extract_items_buckets_ctrl_and_data_from_raw_table(table)
+        return (self.__val.type.get_unqualified_type_name() +
+                ("(len: %i)" % items))
+
+    def children(self):
+        table = rustpp.extract_raw_table_from_std_hashmap(self.__val)
+        i = 0
+        # Both maps and sets store tuples, the elements of a HashSet<T> being
+        # (T, ()).
+        for element in elements_of_raw_table(table):
+            yield (str(i), element["__0"])
+            if self.__want_values:
+                yield (str(i), element["__1"])
+            i = i + 1
+
+
+class RustStdRcPrinter(object):
+    def __init__(self, val):
+        self.__val = val
+
+    def to_string(self):
+        (strong, weak, value) = rustpp.extract_strong_weak_and_value_from_std_rc(self.__val)
+        return (self.__val.type.get_unqualified_type_name() +
+                ("(strong: %i, weak: %i)" % (strong, weak)))
+
+    def children(self):
+        (strong, weak, value) = rustpp.extract_strong_weak_and_value_from_std_rc(self.__val)
+        yield ("value", value.get_wrapped_value())
+
+
+class RustStdCellPrinter(object):
+    def __init__(self, val):
+        self.__val = val
+
+    def to_string(self):
+        return self.__val.type.get_unqualified_type_name()
+
+    def children(self):
+        value = rustpp.extract_value_from_std_cell(self.__val)
+        yield ("value", value.get_wrapped_value())
+
+
+class RustStdRefCellPrinter(object):
+    def __init__(self, val):
+        self.__val = val
+
+    def to_string(self):
+        (borrow, value) = rustpp.extract_borrow_and_value_from_std_refcell(self.__val)
+        return (self.__val.type.get_unqualified_type_name() +
+                ("(borrow: %i)" % borrow))
+
+    def children(self):
+        (borrow, value) = rustpp.extract_borrow_and_value_from_std_refcell(self.__val)
+        yield ("value", value.get_wrapped_value())
+
+
 class RustStdStringPrinter(object):
     def __init__(self, val):
         self.__val = val
@@ -48,6 +48,10 @@ class LldbType(rustpp.Type):
             self.fields = list(self.ty.fields)
         return self.fields
 
+    def get_enum_values(self):
+        assert self.get_dwarf_type_kind() == rustpp.DWARF_TYPE_CODE_ENUM
+        return [member.GetValueAsUnsigned() for member in self.ty.GetEnumMembers()]
+
     def get_wrapped_value(self):
         return self.ty
 
@@ -68,9 +72,15 @@ class LldbValue(rustpp.Value):
             self.children[index] = child
         return child
 
+    def dereference(self):
+        return LldbValue(self.lldb_val.Dereference())
+
     def as_integer(self):
         return self.lldb_val.GetValueAsUnsigned()
 
+    def as_signed_integer(self):
+        return self.lldb_val.GetValueAsSigned()
+
     def get_wrapped_value(self):
         return self.lldb_val
 
@@ -104,6 +114,31 @@ def print_val(lldb_val, internal_dict):
     if type_kind == rustpp.TYPE_KIND_STD_VEC:
         return print_std_vec_val(val, internal_dict)
 
+    if type_kind == rustpp.TYPE_KIND_STD_VECDEQUE:
+        return print_std_vecdeque_val(val, internal_dict)
+
+    if type_kind == rustpp.TYPE_KIND_STD_BTREESET:
+        return print_std_btree_val(val.get_child_at_index(0), internal_dict, want_values=False)
+
+    if type_kind == rustpp.TYPE_KIND_STD_BTREEMAP:
+        return print_std_btree_val(val, internal_dict, want_values=True)
+
+    if type_kind == rustpp.TYPE_KIND_STD_HASHMAP:
+        return print_std_hashmap_val(val, internal_dict, want_values=True)
+
+    if type_kind == rustpp.TYPE_KIND_STD_HASHSET:
+        return print_std_hashmap_val(val, internal_dict, want_values=False)
+
+    if (type_kind == rustpp.TYPE_KIND_STD_RC or
+            type_kind == rustpp.TYPE_KIND_STD_ARC):
+        return print_std_rc_val(val, internal_dict)
+
+    if type_kind == rustpp.TYPE_KIND_STD_CELL:
+        return print_std_cell_val(val, internal_dict)
+
+    if type_kind == rustpp.TYPE_KIND_STD_REFCELL:
+        return print_std_refcell_val(val, internal_dict)
+
     if type_kind == rustpp.TYPE_KIND_STD_STRING:
         return print_std_string_val(val, internal_dict)
 
@@ -255,6 +290,88 @@ def print_std_vec_val(val, internal_dict):
                                               internal_dict)
 
 
+def print_std_vecdeque_val(val, internal_dict):
+    (tail, head, data_ptr, cap) = rustpp.extract_tail_head_ptr_and_cap_from_std_vecdeque(val)
+    if head >= tail:
+        size = head - tail
+    else:
+        size = cap + head - tail
+
+    array_name = val.get_wrapped_value().GetName()
+    raw_value = data_ptr.get_wrapped_value()
+    element_type = raw_value.GetType().GetPointeeType()
+    element_type_size = element_type.GetByteSize()
+
+    def render_element(i):
+        index = (tail + i) % cap
+        address = data_ptr.as_integer() + index * element_type_size
+        element_val = raw_value.CreateValueFromAddress(array_name + ("[%s]" % index),
+                                                       address,
+                                                       element_type)
+        return print_val(element_val, internal_dict)
+
+    return "[%s]" % ', '.join([render_element(i) for i in range(size)])
+
+
+def print_std_btree_val(map_val, internal_dict, want_values):
+    """Prints a BTreeMap, or the map inside of a BTreeSet"""
+    lldb_map = map_val.get_wrapped_value()
+    elements = []
+    if lldb_map.GetChildMemberWithName("length").GetValueAsUnsigned() > 0:
+        # The root is an Option<Root<K, V>>, which is never None here
+        root = lldb_map.GetChildMemberWithName("root")
+        type_name = root.GetType().GetName().replace("core::option::Option<", "", 1)[:-1]
+        root = root.Cast(root.GetTarget().FindFirstType(type_name))
+        height = root.GetChildMemberWithName("height").GetValueAsUnsigned()
+        for child in children_of_btree_node(root.GetChildMemberWithName("node"),
+                                            height,
+                                            want_values):
+            if want_values:
+                elements.append("%s: %s" % (print_val(child[0], internal_dict),
+                                            print_val(child[1], internal_dict)))
+            else:
+                elements.append(print_val(child, internal_dict))
+    return "{%s}" % ', '.join(elements)
+
+
+def print_std_hashmap_val(val, internal_dict, want_values):
+    table = rustpp.extract_raw_table_from_std_hashmap(val)
+    elements = []
+    # Both maps and sets store tuples, the elements of a HashSet<T> being
+    # (T, ()).
+    for element in elements_of_raw_table(table):
+        key = print_val(element.GetChildAtIndex(0), internal_dict)
+        if want_values:
+            elements.append("%s: %s" % (key, print_val(element.GetChildAtIndex(1),
+                                                       internal_dict)))
+        else:
+            elements.append(key)
+    return "{%s}" % ', '.join(elements)
+
+
+def print_std_rc_val(val, internal_dict):
+    (strong, weak, value) = rustpp.extract_strong_weak_and_value_from_std_rc(val)
+    return "%s { strong: %i, weak: %i, value: %s }" % (val.type.get_unqualified_type_name(),
+                                                       strong,
+                                                       weak,
+                                                       print_val(value.get_wrapped_value(),
+                                                                 internal_dict))
+
+
+def print_std_cell_val(val, internal_dict):
+    value = rustpp.extract_value_from_std_cell(val)
+    return "%s { value: %s }" % (val.type.get_unqualified_type_name(),
+                                 print_val(value.get_wrapped_value(), internal_dict))
+
+
+def print_std_refcell_val(val, internal_dict):
+    (borrow, value) = rustpp.extract_borrow_and_value_from_std_refcell(val)
+    return "%s { borrow: %i, value: %s }" % (val.type.get_unqualified_type_name(),
+                                             borrow,
+                                             print_val(value.get_wrapped_value(),
+                                                       internal_dict))
+
+
 def print_str_slice_val(val, internal_dict):
     (length, data_ptr) = rustpp.extract_length_and_ptr_from_slice(val)
     return read_utf8_string(data_ptr, length)
@@ -293,6 +410,71 @@ def print_array_of_values(array_name, data_ptr_val, length, internal_dict):
     return ', '.join([render_element(i) for i in range(length)])
 
 
+def children_of_btree_node(boxed_node, height, want_values):
+    """Yields each key, or each key and value, of a BoxedNode of a BTreeMap"""
+    node_ptr = boxed_node.GetChildMemberWithName("ptr").GetChildMemberWithName("pointer")
+    if height > 0:
+        type_name = node_ptr.GetType().GetPointeeType().GetName()
+        type_name = type_name.replace("LeafNode", "InternalNode", 1)
+        node_type = node_ptr.GetTarget().FindFirstType(type_name)
+        node_ptr = node_ptr.Cast(node_type.GetPointerType())
+        leaf = node_ptr.Dereference().GetChildMemberWithName("data")
+    else:
+        leaf = node_ptr.Dereference()
+
+    # The keys and values are arrays of MaybeUninit<T>, whose initialized
+    # elements are in `value.value`.
+    def initialized(element):
+        return element.GetChildMemberWithName("value").GetChildMemberWithName("value")
+
+    keys = leaf.GetChildMemberWithName("keys")
+    values = leaf.GetChildMemberWithName("vals")
+    length = leaf.GetChildMemberWithName("len").GetValueAsUnsigned()
+    for i in range(length + 1):
+        if height > 0:
+            edges = node_ptr.Dereference().GetChildMemberWithName("edges")
+            child_node = initialized(edges.GetChildAtIndex(i))
+            for child in children_of_btree_node(child_node, height - 1, want_values):
+                yield child
+        if i < length:
+            key = initialized(keys.GetChildAtIndex(i))
+            if want_values:
+                yield (key, initialized(values.GetChildAtIndex(i)))
+            else:
+                yield key
+
+
+def elements_of_raw_table(table_val):
+    """Yields each element of a hashbrown RawTable, in bucket order"""
+    (items, buckets, ctrl_ptr, data_ptr) = \
+        rustpp.extract_items_buckets_ctrl_and_data_from_raw_table(table_val)
+    if items == 0:
+        return
+
+    lldb_table = table_val.get_wrapped_value()
+    element_type = lldb_table.GetType().GetTemplateArgumentType(0)
+    element_type_size = element_type.GetByteSize()
+
+    error = lldb.SBError()
+    ctrl_address = ctrl_ptr.as_integer()
+    ctrl_bytes = bytearray(lldb_table.GetProcess().ReadMemory(ctrl_address, buckets, error))
+    if not error.Success():
+        return
+
+    found = 0
+    for index in range(buckets):
+        if found == items:
+            return
+        if not rustpp.is_raw_table_bucket_full(ctrl_bytes[index]):
+            continue
+        found = found + 1
+        if data_ptr is not None:
+            address = data_ptr.as_integer() + index * element_type_size
+        else:
+            address = ctrl_address - (index + 1) * element_type_size
+        yield lldb_table.CreateValueFromAddress("[%s]" % index, address, element_type)
+
+
 def read_utf8_string(ptr_val, byte_count):
     if byte_count == 0:
         return '""'
@@ -1184,6 +1184,10 @@ impl Clone for BorrowRef<'_> {
 /// A wrapper type for an immutably borrowed value from a `RefCell<T>`.
 ///
 /// See the [module-level documentation](index.html) for more.
+#[cfg_attr(
+    not(bootstrap),
+    must_not_suspend = "holding a Ref across suspend points can cause `BorrowError`s"
+)]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Ref<'b, T: ?Sized + 'b> {
     value: &'b T,
@@ -1474,6 +1478,10 @@ impl<'b> BorrowRefMut<'b> {
 /// A wrapper type for a mutably borrowed value from a `RefCell<T>`.
 ///
 /// See the [module-level documentation](index.html) for more.
+#[cfg_attr(
+    not(bootstrap),
+    must_not_suspend = "holding a RefMut across suspend points can cause `BorrowError`s"
+)]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct RefMut<'b, T: ?Sized + 'b> {
     value: &'b mut T,
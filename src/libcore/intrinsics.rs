@@ -56,6 +56,8 @@
 
 use crate::mem;
 
+pub mod mir;
+
 #[stable(feature = "drop_in_place", since = "1.8.0")]
 #[rustc_deprecated(
     reason = "no longer an intrinsic - use `ptr::drop_in_place` directly",
@@ -1871,6 +1873,19 @@ extern "rust-intrinsic" {
     ///
     /// Perma-unstable: do not use.
     pub fn miri_start_panic(payload: *mut u8) -> !;
+
+    /// Internal placeholder for injecting code coverage counters when the "instrument-coverage"
+    /// option is enabled. The placeholder is replaced with `llvm.instrprof.increment` during code
+    /// generation. `start_offset` and `end_offset` are the offsets, from the start of the function
+    /// body, of the code region counted by the counter at `index`.
+    #[cfg(not(bootstrap))]
+    #[lang = "count_code_region"]
+    pub fn count_code_region(
+        function_source_hash: u64,
+        index: u32,
+        start_offset: u32,
+        end_offset: u32,
+    );
 }
 
 // Some functions are defined here because they accidentally got made
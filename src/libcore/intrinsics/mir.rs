@@ -0,0 +1,191 @@
+//! Rustc internal tooling for hand-writing MIR.
+//!
+//! If for some reasons you are not writing rustc tests and have found yourself considering using
+//! this feature, turn back. This is *exceptionally* unstable. There is no attempt at all to make
+//! anything work besides those things which the rustc test suite happened to need. If you make a
+//! typo you'll probably ICE. Really, this is not the solution to your problems.
+//!
+//! The documentation for this module describes how to use this feature. If you are interested in
+//! hacking on the implementation, most of that documentation lives at
+//! `librustc_mir_build/build/custom.rs`.
+//!
+//! Typical usage will look like this:
+//!
+//! ```rust
+//! #![feature(core_intrinsics, custom_mir)]
+//!
+//! extern crate core;
+//! use core::intrinsics::mir::*;
+//!
+//! #[custom_mir]
+//! pub fn simple(x: i32) -> i32 {
+//!     mir!(
+//!         let temp1: i32;
+//!         let temp2: _;
+//!
+//!         {
+//!             temp1 = x;
+//!             Goto(exit)
+//!         }
+//!
+//!         exit = {
+//!             temp2 = Move(temp1);
+//!             RET = temp2;
+//!             Return()
+//!         }
+//!     )
+//! }
+//! ```
+//!
+//! Hopefully most of this is fairly self-explanatory. Expanding on some notable details:
+//!
+//!  - The `#[custom_mir]` attribute tells the compiler to treat the function as being custom MIR.
+//!    This attribute only works on functions - there is no way to insert custom MIR into the
+//!    middle of another function.
+//!  - Within the function, the `mir!` macro is used to declare the locals and basic blocks of the
+//!    body. The function's parameters are the MIR arguments, and `RET` is the return place.
+//!  - Locals are declared with `let`, without an initializer. The type may be left as `_` when
+//!    it can be inferred.
+//!  - The first block is the entry block and is not named. All other blocks are named and
+//!    jumped to by their name.
+//!  - Each block is a sequence of statements followed by the terminator as the block's value.
+//!
+//! # What can be written
+//!
+//! Statements:
+//!  - Assignments `place = rvalue`.
+//!  - [`StorageLive`] and [`StorageDead`].
+//!
+//! Places are locals, dereferences `*place`, field accesses `place.0` and indexing `place[local]`.
+//!
+//! Operands are [`Move`] of a place, constants, or a place by itself, which is a copy.
+//!
+//! Rvalues:
+//!  - Operands by themselves.
+//!  - Borrows `&place`, `&mut place`, and raw borrows `&raw const place`, `&raw mut place`.
+//!  - Binary and unary operations on operands, and [`Checked`] binary operations.
+//!  - Casts `operand as Type`.
+//!  - [`Len`] and [`Discriminant`] of a place.
+//!  - Tuples of operands.
+//!
+//! Terminators:
+//!  - [`Return`], [`Goto`], [`Unreachable`], [`Drop`] and [`Call`].
+//!  - `match operand { value => block, .., _ => otherwise }`, which becomes a `SwitchInt`.
+//!
+//! None of the terminators have an unwind edge.
+
+#![unstable(
+    feature = "custom_mir",
+    reason = "MIR is an implementation detail and extremely unstable",
+    issue = "none"
+)]
+#![allow(missing_docs, non_snake_case)]
+
+/// Type representing basic blocks.
+///
+/// All terminators will have this type as a return type. It helps achieve some type safety.
+#[rustc_diagnostic_item = "mir_basic_block"]
+pub struct BasicBlock;
+
+#[rustc_diagnostic_item = "mir_return"]
+pub fn Return() -> BasicBlock {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_goto"]
+pub fn Goto(_destination: BasicBlock) -> BasicBlock {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_unreachable"]
+pub fn Unreachable() -> BasicBlock {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_drop"]
+pub fn Drop<T>(_place: T, _goto: BasicBlock) -> BasicBlock {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+/// Calls `call` and stores its result in `place`, then continues at `goto`.
+#[rustc_diagnostic_item = "mir_call"]
+pub fn Call<T>(_place: T, _goto: BasicBlock, _call: T) -> BasicBlock {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_storage_live"]
+pub fn StorageLive<T>(_local: T) {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_storage_dead"]
+pub fn StorageDead<T>(_local: T) {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_move"]
+pub fn Move<T>(_place: T) -> T {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+/// A binary operation that also returns whether it overflowed, as in `Checked(a + b)`.
+#[rustc_diagnostic_item = "mir_checked"]
+pub fn Checked<T>(_binop: T) -> (T, bool) {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+/// The length of an array or slice, as in `Len(&*slice)`.
+#[rustc_diagnostic_item = "mir_len"]
+pub fn Len<T: ?Sized>(_place: &T) -> usize {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+#[rustc_diagnostic_item = "mir_discriminant"]
+pub fn Discriminant<T>(_place: T) -> isize {
+    panic!("this function is only valid inside `#[custom_mir]` bodies")
+}
+
+/// Macro for generating custom MIR.
+///
+/// See the module documentation for syntax details. This macro is not magic - it only transforms
+/// your MIR into something that is easier to parse in the compiler.
+#[rustc_macro_transparency = "transparent"]
+pub macro mir {
+    (
+        $(let $local_decl:ident $(: $local_decl_ty:ty)? ;)*
+
+        {
+            $($entry:tt)*
+        }
+
+        $(
+            $block_name:ident = {
+                $($block:tt)*
+            }
+        )*
+    ) => {{
+        // First, we declare all basic blocks.
+        $(
+            let $block_name: ::core::intrinsics::mir::BasicBlock;
+        )*
+
+        {
+            // Now all locals
+            #[allow(non_snake_case)]
+            let RET;
+            $(
+                let $local_decl $(: $local_decl_ty)? ;
+            )*
+
+            {
+                // Finally, the contents of the basic blocks
+                { $($entry)* };
+                $(
+                    $block_name = { $($block)* };
+                )*
+
+                RET
+            }
+        }
+    }}
+}
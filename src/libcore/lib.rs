@@ -98,6 +98,7 @@
 #![feature(is_sorted)]
 #![feature(lang_items)]
 #![feature(link_llvm_intrinsics)]
+#![cfg_attr(not(bootstrap), feature(must_not_suspend))]
 #![feature(never_type)]
 #![feature(nll)]
 #![feature(exhaustive_patterns)]
@@ -23,6 +23,9 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine(Symbol),
+
+    /// Lint level was set by the lint configuration file.
+    ConfigFile(Symbol),
 }
 
 pub type LevelSource = (Level, LintSource);
@@ -280,6 +283,26 @@ pub fn struct_lint_level<'s, 'd>(
                     );
                 }
             }
+            LintSource::ConfigFile(lint_config_name) => {
+                let path = sess.opts.debugging_opts.lint_config.as_ref().unwrap();
+                let msg = if lint_config_name.as_str() == name {
+                    format!(
+                        "`{}` set to `{}` by the lint configuration file `{}`",
+                        name,
+                        level.as_str(),
+                        path.display()
+                    )
+                } else {
+                    format!(
+                        "`{}` implied by `{}` set to `{}` by the lint configuration file `{}`",
+                        name,
+                        lint_config_name,
+                        level.as_str(),
+                        path.display()
+                    )
+                };
+                sess.diag_note_once(&mut err, DiagnosticMessageId::from(lint), &msg);
+            }
             LintSource::Node(lint_attr_name, src, reason) => {
                 if let Some(rationale) = reason {
                     err.note(&rationale.as_str());
@@ -75,8 +75,21 @@ bitflags! {
         const NO_SANITIZE_MEMORY  = 1 << 13;
         /// `#[no_sanitize(thread)]`: disables thread sanitizer instrumentation
         const NO_SANITIZE_THREAD  = 1 << 14;
+        /// `#[no_sanitize(cfi)]`: disables control-flow integrity checks of the
+        /// indirect calls the function makes
+        const NO_SANITIZE_CFI     = 1 << 15;
+        /// `#[no_sanitize(hwaddress)]`: disables hardware-assisted address
+        /// sanitizer instrumentation
+        const NO_SANITIZE_HWADDRESS = 1 << 16;
+        /// `#[no_sanitize(shadow_call_stack)]`: disables the shadow call stack
+        const NO_SANITIZE_SHADOW_CALL_STACK = 1 << 17;
         /// All `#[no_sanitize(...)]` attributes.
-        const NO_SANITIZE_ANY = Self::NO_SANITIZE_ADDRESS.bits | Self::NO_SANITIZE_MEMORY.bits | Self::NO_SANITIZE_THREAD.bits;
+        const NO_SANITIZE_ANY = Self::NO_SANITIZE_ADDRESS.bits
+            | Self::NO_SANITIZE_MEMORY.bits
+            | Self::NO_SANITIZE_THREAD.bits
+            | Self::NO_SANITIZE_CFI.bits
+            | Self::NO_SANITIZE_HWADDRESS.bits
+            | Self::NO_SANITIZE_SHADOW_CALL_STACK.bits;
     }
 }
 
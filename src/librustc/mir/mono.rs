@@ -52,7 +52,7 @@ impl<'tcx> MonoItem<'tcx> {
         match *self {
             MonoItem::Fn(instance) => {
                 // Estimate the size of a function based on how many statements
-                // it contains.
+                // and terminators it contains.
                 tcx.instance_def_size_estimate(instance.def)
             }
             // Conservatively estimate the size of a static declaration
@@ -290,7 +290,7 @@ impl<'tcx> CodegenUnit<'tcx> {
 
     pub fn estimate_size(&mut self, tcx: TyCtxt<'tcx>) {
         // Estimate the size of a codegen unit as (approximately) the number of MIR
-        // statements it corresponds to.
+        // statements and terminators it corresponds to.
         self.size_estimate = Some(self.items.keys().map(|mi| mi.size_estimate(tcx)).sum());
     }
 
@@ -82,6 +82,17 @@ pub struct ConstQualifs {
     pub needs_drop: bool,
 }
 
+/// Coverage information summarized from a MIR body instrumented for source code coverage (see
+/// `-Z instrument-coverage`). It is computed from the counters added by the `InstrumentCoverage`
+/// MIR pass and retrieved with the `coverageinfo` query.
+#[derive(Clone, Copy, Debug, Default, RustcEncodable, RustcDecodable, HashStable)]
+pub struct CoverageInfo {
+    /// A hash of the function's source, used by `llvm-cov` to detect stale profile data.
+    pub function_source_hash: u64,
+    /// The total number of coverage region counters added to the MIR body.
+    pub num_counters: u32,
+}
+
 /// After we borrow check a closure, we are left with various
 /// requirements that we have inferred between the free regions that
 /// appear in the closure's signature or on its field types. These
@@ -190,6 +190,27 @@ rustc_queries! {
             no_hash
         }
 
+        /// Checks whether a function can call back into the function with the given `DefId`,
+        /// directly or through other calls. Used by the MIR inliner to avoid cycles.
+        query mir_callgraph_reachable(key: (ty::Instance<'tcx>, DefId)) -> bool {
+            fatal_cycle
+            desc { |tcx|
+                "computing if `{}` (transitively) calls `{}`",
+                key.0,
+                tcx.def_path_str(key.1)
+            }
+        }
+
+        /// Obtains the functions called by the MIR of the given instance, before any inlining.
+        query mir_inliner_callees(key: ty::InstanceDef<'tcx>)
+            -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+            fatal_cycle
+            desc { |tcx|
+                "computing all local function calls in `{}`",
+                tcx.def_path_str(key.def_id())
+            }
+        }
+
         /// MIR after our optimization passes have run. This is MIR that is ready
         /// for codegen. This is also the only query that can fetch non-local MIR, at present.
         query optimized_mir(key: DefId) -> &'tcx mir::BodyAndCache<'tcx> {
@@ -222,6 +243,15 @@ rustc_queries! {
                 })
             }
         }
+
+        /// Returns coverage summary info for a function, after executing the `InstrumentCoverage`
+        /// MIR pass (assuming the `-Z instrument-coverage` option is enabled).
+        query coverageinfo(key: ty::InstanceDef<'tcx>) -> mir::CoverageInfo {
+            desc { |tcx|
+                "retrieving coverage info from MIR for `{}`",
+                tcx.def_path_str(key.def_id())
+            }
+        }
     }
 
     TypeChecking {
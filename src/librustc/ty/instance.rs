@@ -6,6 +6,7 @@ use rustc_data_structures::AtomicRef;
 use rustc_hir::def::Namespace;
 use rustc_hir::def_id::{CrateNum, DefId};
 use rustc_macros::HashStable;
+use rustc_session::config::Sanitizer;
 
 use std::fmt;
 
@@ -309,6 +310,18 @@ impl<'tcx> Instance<'tcx> {
                     debug!(" => fn pointer created for virtual call");
                     resolved.def = InstanceDef::ReifyShim(def_id);
                 }
+                // Under `-Z sanitizer=cfi`, the functions of other crates and
+                // foreign functions may lack the type metadata that the calls
+                // through the pointer are checked against, so point to a shim
+                // defined in this crate, which has it.
+                InstanceDef::Item(def_id)
+                    if tcx.sess.opts.debugging_opts.sanitizer == Some(Sanitizer::Cfi)
+                        && (!def_id.is_local() || tcx.is_foreign_item(def_id))
+                        && !tcx.fn_sig(def_id).c_variadic() =>
+                {
+                    debug!(" => fn pointer created for function without type metadata");
+                    resolved.def = InstanceDef::ReifyShim(def_id);
+                }
                 _ => {}
             }
 
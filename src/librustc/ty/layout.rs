@@ -9,7 +9,8 @@ use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_hir as hir;
 use rustc_index::bit_set::BitSet;
 use rustc_index::vec::{Idx, IndexVec};
-use rustc_session::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+use rustc_session::{DataTypeKind, DiscrInfo, DiscrKind, FieldInfo, NicheInfo};
+use rustc_session::{SizeKind, VariantInfo};
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::call::{
     ArgAbi, ArgAttribute, ArgAttributes, Conv, FnAbi, PassMode, Reg, RegKind,
@@ -1580,9 +1581,10 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
     /// layout of each type.
     #[inline(always)]
     fn record_layout_for_printing(&self, layout: TyLayout<'tcx>) {
-        // If we are running with `-Zprint-type-sizes`, maybe record layouts
-        // for dumping later.
-        if self.tcx.sess.opts.debugging_opts.print_type_sizes {
+        // If we are running with `-Zprint-type-sizes` or `-Ztype-layout-report`,
+        // maybe record layouts for dumping later.
+        let debugging_opts = &self.tcx.sess.opts.debugging_opts;
+        if debugging_opts.print_type_sizes || debugging_opts.type_layout_report.is_some() {
             self.record_layout_for_printing_outlined(layout)
         }
     }
@@ -1596,15 +1598,21 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
         }
 
         // (delay format until we actually need it)
-        let record = |kind, packed, opt_discr_size, variants| {
+        let record = |kind, packed, discr, variants| {
             let type_desc = format!("{:?}", layout.ty);
+            let niche = layout.largest_niche.as_ref().map(|niche| NicheInfo {
+                offset: niche.offset.bytes(),
+                size: niche.scalar.value.size(self).bytes(),
+                available: niche.available(self),
+            });
             self.tcx.sess.code_stats.record_type_size(
                 kind,
                 type_desc,
                 layout.align.abi,
                 layout.size,
                 packed,
-                opt_discr_size,
+                discr,
+                niche,
                 variants,
             );
         };
@@ -1683,7 +1691,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                 }
             }
 
-            Variants::Multiple { ref discr, ref discr_kind, .. } => {
+            Variants::Multiple { ref discr, ref discr_kind, discr_index, .. } => {
                 debug!(
                     "print-type-size `{:#?}` adt general variants def {}",
                     layout.ty,
@@ -1702,15 +1710,21 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                         )
                     })
                     .collect();
-                record(
-                    adt_kind.into(),
-                    adt_packed,
-                    match discr_kind {
-                        DiscriminantKind::Tag => Some(discr.value.size(self)),
-                        _ => None,
-                    },
-                    variant_infos,
-                );
+                let kind = match *discr_kind {
+                    DiscriminantKind::Tag => DiscrKind::Tag,
+                    DiscriminantKind::Niche { dataful_variant, niche_start, .. } => {
+                        DiscrKind::Niche {
+                            dataful_variant: adt_def.variants[dataful_variant].ident.to_string(),
+                            niche_start,
+                        }
+                    }
+                };
+                let discr = DiscrInfo {
+                    kind,
+                    offset: layout.fields.offset(discr_index).bytes(),
+                    size: discr.value.size(self).bytes(),
+                };
+                record(adt_kind.into(), adt_packed, Some(discr), variant_infos);
             }
         }
     }
@@ -1867,6 +1867,16 @@ pub enum AsmDialect {
     Intel,
 }
 
+/// The memory accesses inline assembly may perform.
+///
+/// E.g., `nomem` as in `asm!("lea {}, [{} + 1]", out(reg) x, in(reg) y, options(nomem))`.
+#[derive(Clone, PartialEq, RustcEncodable, RustcDecodable, Debug, Copy, HashStable_Generic)]
+pub enum AsmMemory {
+    ReadWrite,
+    ReadOnly,
+    NoMem,
+}
+
 /// Inline assembly.
 ///
 /// E.g., `"={eax}"(result)` as in `asm!("mov eax, 2" : "={eax}"(result) : : : "intel")`.
@@ -1891,6 +1901,9 @@ pub struct InlineAsm {
     pub volatile: bool,
     pub alignstack: bool,
     pub dialect: AsmDialect,
+    pub memory: AsmMemory,
+    /// Whether the flags register is left untouched, so needn't be clobbered.
+    pub preserves_flags: bool,
 }
 
 /// A parameter in a function header.
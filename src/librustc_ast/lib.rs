@@ -35,6 +35,7 @@ pub mod util {
     pub mod literal;
     pub mod map_in_place;
     pub mod parser;
+    pub mod unicode;
 }
 
 pub mod ast;
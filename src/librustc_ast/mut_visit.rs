@@ -1212,6 +1212,8 @@ pub fn noop_visit_expr<T: MutVisitor>(Expr { kind, id, span, attrs }: &mut Expr,
                 volatile: _,
                 alignstack: _,
                 dialect: _,
+                memory: _,
+                preserves_flags: _,
             } = asm.deref_mut();
             for out in outputs {
                 let InlineAsmOutput { constraint: _, expr, is_rw: _, is_indirect: _ } = out;
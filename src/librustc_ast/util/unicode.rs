@@ -0,0 +1,19 @@
+//! Unicode code points that deserve attention in source code.
+
+/// The bidirectional formatting characters of Unicode, the embeddings,
+/// overrides and isolates with the characters that end them. They change
+/// the order characters are displayed in, so that code containing them can
+/// look different from what the compiler sees.
+pub const TEXT_FLOW_CONTROL_CHARS: &[char] = &[
+    '\u{202A}', '\u{202B}', '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}', '\u{2068}', '\u{202C}',
+    '\u{2069}',
+];
+
+/// Returns `true` if `s` contains a text flow control character.
+#[inline]
+pub fn contains_text_flow_control_chars(s: &str) -> bool {
+    // The characters are all encoded as `0xE2 0x80 0xAA..=0xAE` or
+    // `0xE2 0x81 0xA6..=0xA9`, so most strings can be skipped on the
+    // lead byte alone.
+    s.as_bytes().contains(&0xE2) && s.chars().any(|c| TEXT_FLOW_CONTROL_CHARS.contains(&c))
+}
@@ -971,6 +971,8 @@ impl<'hir> LoweringContext<'_, 'hir> {
             volatile: asm.volatile,
             alignstack: asm.alignstack,
             dialect: asm.dialect,
+            memory: asm.memory,
+            preserves_flags: asm.preserves_flags,
         };
         let hir_asm = hir::InlineAsm {
             inner,
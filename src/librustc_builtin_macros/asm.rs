@@ -1,16 +1,23 @@
 // Inline assembly support.
 //
+// `asm!` accepts two syntaxes. The legacy one passes LLVM constraint strings
+// through, as in `asm!("mov $1, $0" : "=r"(x) : "r"(y))`. The other one names
+// register classes and explicit registers that rustc validates for the target
+// architecture, as in `asm!("mov {}, {}", out(reg) x, in(reg) y)`, and is
+// lowered here to the constraints of the legacy syntax.
 use State::*;
 
-use rustc_ast::ast::{self, AsmDialect};
+use rustc_ast::ast::{self, AsmDialect, AsmMemory};
 use rustc_ast::ptr::P;
 use rustc_ast::token::{self, Token};
 use rustc_ast::tokenstream::{self, TokenStream};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::{struct_span_err, DiagnosticBuilder, PResult};
 use rustc_expand::base::*;
 use rustc_parse::parser::Parser;
 use rustc_span::symbol::{kw, sym, Symbol};
 use rustc_span::Span;
+use rustc_target::asm::{InlineAsmArch, InlineAsmReg, InlineAsmRegClass};
 
 enum State {
     Asm,
@@ -41,6 +48,10 @@ pub fn expand_asm<'cx>(
     sp: Span,
     tts: TokenStream,
 ) -> Box<dyn MacResult + 'cx> {
+    if !is_legacy_syntax(&tts) {
+        return expand_typed_asm(cx, sp, tts);
+    }
+
     let mut inline_asm = match parse_inline_asm(cx, sp, tts) {
         Ok(Some(inline_asm)) => inline_asm,
         Ok(None) => return DummyResult::any(sp),
@@ -64,6 +75,20 @@ pub fn expand_asm<'cx>(
     }))
 }
 
+/// Whether `tts` use the legacy syntax, whose template is followed by a colon
+/// rather than by a comma. A template on its own is taken to use the legacy
+/// syntax, as existing code relies on that.
+fn is_legacy_syntax(tts: &TokenStream) -> bool {
+    let separator = tts.trees().find_map(|tt| match tt {
+        tokenstream::TokenTree::Token(Token { kind, .. }) => match kind {
+            token::Comma | token::Colon | token::ModSep => Some(kind),
+            _ => None,
+        },
+        _ => None,
+    });
+    separator != Some(token::Comma)
+}
+
 fn parse_asm_str<'a>(p: &mut Parser<'a>) -> PResult<'a, Symbol> {
     match p.parse_str_lit() {
         Ok(str_lit) => Ok(str_lit.symbol_unescaped),
@@ -296,5 +321,705 @@ fn parse_inline_asm<'a>(
         volatile,
         alignstack,
         dialect,
+        memory: AsmMemory::ReadWrite,
+        preserves_flags: false,
     }))
 }
+
+/// The register of an operand of the typed syntax: any register of a class, or
+/// an explicit register.
+enum AsmReg {
+    Class(InlineAsmRegClass),
+    Explicit(InlineAsmReg),
+}
+
+impl AsmReg {
+    fn llvm_constraint(&self) -> String {
+        match self {
+            AsmReg::Class(class) => class.llvm_constraint().to_string(),
+            AsmReg::Explicit(reg) => reg.llvm_constraint(),
+        }
+    }
+}
+
+enum AsmOperand {
+    In {
+        reg: AsmReg,
+        expr: P<ast::Expr>,
+    },
+    /// An `out` or `lateout` operand, whose value is discarded if `expr` is `None`.
+    Out {
+        reg: AsmReg,
+        late: bool,
+        expr: Option<P<ast::Expr>>,
+    },
+    InOut {
+        reg: AsmReg,
+        late: bool,
+        expr: P<ast::Expr>,
+    },
+    /// An `inout` or `inlateout` operand of the form `in_expr => out_expr`.
+    SplitInOut {
+        reg: AsmReg,
+        late: bool,
+        in_expr: P<ast::Expr>,
+        out_expr: Option<P<ast::Expr>>,
+    },
+}
+
+impl AsmOperand {
+    fn reg(&self) -> &AsmReg {
+        match self {
+            AsmOperand::In { reg, .. }
+            | AsmOperand::Out { reg, .. }
+            | AsmOperand::InOut { reg, .. }
+            | AsmOperand::SplitInOut { reg, .. } => reg,
+        }
+    }
+
+    /// Whether the operand reads its register, and whether it writes it.
+    fn direction(&self) -> (bool, bool) {
+        match self {
+            AsmOperand::In { .. } => (true, false),
+            AsmOperand::Out { .. } => (false, true),
+            AsmOperand::InOut { .. } | AsmOperand::SplitInOut { .. } => (true, true),
+        }
+    }
+
+    /// Whether the operand stores its output in a place.
+    fn has_output(&self) -> bool {
+        match self {
+            AsmOperand::In { .. } => false,
+            AsmOperand::Out { expr, .. } => expr.is_some(),
+            AsmOperand::InOut { .. } => true,
+            AsmOperand::SplitInOut { out_expr, .. } => out_expr.is_some(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AsmOptions {
+    pure: bool,
+    nomem: bool,
+    readonly: bool,
+    preserves_flags: bool,
+    nostack: bool,
+    att_syntax: bool,
+}
+
+struct AsmArgs {
+    templates: Vec<P<ast::Expr>>,
+    operands: Vec<(AsmOperand, Span)>,
+    named_args: FxHashMap<Symbol, usize>,
+    reg_args: FxHashSet<usize>,
+    options: AsmOptions,
+    options_span: Option<Span>,
+}
+
+fn expand_typed_asm<'cx>(
+    cx: &'cx mut ExtCtxt<'_>,
+    sp: Span,
+    tts: TokenStream,
+) -> Box<dyn MacResult + 'cx> {
+    let arch = cx
+        .parse_sess
+        .config
+        .iter()
+        .find_map(|&(name, value)| if name == sym::target_arch { value } else { None })
+        .and_then(|arch| arch.as_str().parse::<InlineAsmArch>().ok());
+    let arch = match arch {
+        Some(arch) => arch,
+        None => {
+            let msg = "inline assembly with register operands is not supported on this target";
+            cx.span_err(sp, msg);
+            return DummyResult::any(sp);
+        }
+    };
+
+    let args = match parse_typed_args(cx, tts, arch) {
+        Ok(args) => args,
+        Err(mut err) => {
+            err.emit();
+            return DummyResult::any(sp);
+        }
+    };
+    match lower_typed_asm(cx, sp, args, arch) {
+        Some(expr) => MacEager::expr(expr),
+        None => DummyResult::any(sp),
+    }
+}
+
+fn parse_typed_args<'a>(
+    cx: &mut ExtCtxt<'a>,
+    tts: TokenStream,
+    arch: InlineAsmArch,
+) -> PResult<'a, AsmArgs> {
+    let mut p = cx.new_parser_from_tts(tts);
+
+    let first_template = p.parse_expr()?;
+    let mut args = AsmArgs {
+        templates: vec![first_template],
+        operands: vec![],
+        named_args: FxHashMap::default(),
+        reg_args: FxHashSet::default(),
+        options: AsmOptions::default(),
+        options_span: None,
+    };
+
+    let mut allow_templates = true;
+    while p.token != token::Eof {
+        p.expect(&token::Comma)?;
+        // Accept trailing commas.
+        if p.token == token::Eof {
+            break;
+        }
+
+        if p.eat_keyword(sym::options) {
+            parse_options(&mut p, &mut args)?;
+            allow_templates = false;
+            continue;
+        }
+
+        let span_start = p.token.span;
+
+        let name = if p.token.is_ident() && p.look_ahead(1, |t| *t == token::Eq) {
+            let (ident, _) = p.token.ident().unwrap();
+            p.bump();
+            p.expect(&token::Eq)?;
+            allow_templates = false;
+            Some(ident.name)
+        } else {
+            None
+        };
+
+        let mut explicit_reg = false;
+        let operand = if p.eat_keyword(kw::In) {
+            let reg = parse_reg(&mut p, &mut explicit_reg, arch)?;
+            let expr = p.parse_expr()?;
+            AsmOperand::In { reg, expr }
+        } else if p.eat_keyword(sym::out) {
+            let reg = parse_reg(&mut p, &mut explicit_reg, arch)?;
+            let expr = parse_output_expr(&mut p)?;
+            AsmOperand::Out { reg, late: false, expr }
+        } else if p.eat_keyword(sym::lateout) {
+            let reg = parse_reg(&mut p, &mut explicit_reg, arch)?;
+            let expr = parse_output_expr(&mut p)?;
+            AsmOperand::Out { reg, late: true, expr }
+        } else if p.eat_keyword(sym::inout) {
+            let reg = parse_reg(&mut p, &mut explicit_reg, arch)?;
+            parse_inout(&mut p, reg, false)?
+        } else if p.eat_keyword(sym::inlateout) {
+            let reg = parse_reg(&mut p, &mut explicit_reg, arch)?;
+            parse_inout(&mut p, reg, true)?
+        } else if allow_templates {
+            let template = p.parse_expr()?;
+            // If it can't possibly expand to a string, it is more likely to be
+            // a mistyped operand than a template.
+            match template.kind {
+                ast::ExprKind::Lit(ast::Lit { kind: ast::LitKind::Str(..), .. })
+                | ast::ExprKind::MacCall(..) => {}
+                _ => {
+                    let msg = "expected operand, options, or additional template string";
+                    return Err(cx.struct_span_err(template.span, msg));
+                }
+            }
+            args.templates.push(template);
+            continue;
+        } else {
+            return Err(p.struct_span_err(p.token.span, "expected operand or options"));
+        };
+
+        allow_templates = false;
+        let span = span_start.to(p.prev_token.span);
+        let slot = args.operands.len();
+
+        // As in `format!`, named operands follow positional ones. Explicit
+        // register operands cannot be used in the template, so they go last.
+        if explicit_reg {
+            if name.is_some() {
+                cx.struct_span_err(span, "explicit register arguments cannot have names").emit();
+            }
+            args.reg_args.insert(slot);
+        } else if let Some(name) = name {
+            if let Some(&prev) = args.named_args.get(&name) {
+                cx.struct_span_err(span, &format!("duplicate argument named `{}`", name))
+                    .span_label(args.operands[prev].1, "previously here")
+                    .span_label(span, "duplicate argument")
+                    .emit();
+                continue;
+            }
+            if !args.reg_args.is_empty() {
+                let msg = "named arguments cannot follow explicit register arguments";
+                let mut err = cx.struct_span_err(span, msg);
+                err.span_label(span, "named argument");
+                for &slot in &args.reg_args {
+                    err.span_label(args.operands[slot].1, "explicit register argument");
+                }
+                err.emit();
+            }
+            args.named_args.insert(name, slot);
+        } else if !args.named_args.is_empty() || !args.reg_args.is_empty() {
+            let msg = "positional arguments cannot follow named arguments \
+                       or explicit register arguments";
+            let mut err = cx.struct_span_err(span, msg);
+            err.span_label(span, "positional argument");
+            for &slot in args.named_args.values() {
+                err.span_label(args.operands[slot].1, "named argument");
+            }
+            for &slot in &args.reg_args {
+                err.span_label(args.operands[slot].1, "explicit register argument");
+            }
+            err.emit();
+        }
+        args.operands.push((operand, span));
+    }
+
+    let options = &args.options;
+    if let Some(options_span) = args.options_span {
+        if options.nomem && options.readonly {
+            let msg = "the `nomem` and `readonly` options are mutually exclusive";
+            cx.struct_span_err(options_span, msg).emit();
+        }
+        if options.pure && !options.nomem && !options.readonly {
+            let msg = "the `pure` option must be combined with either `nomem` or `readonly`";
+            cx.struct_span_err(options_span, msg).emit();
+        }
+        if options.pure && !args.operands.iter().any(|(operand, _)| operand.has_output()) {
+            let msg = "asm with the `pure` option must have at least one output";
+            cx.struct_span_err(options_span, msg).emit();
+        }
+        if options.att_syntax && !arch.has_att_syntax() {
+            let msg = "the `att_syntax` option is only supported on x86";
+            cx.struct_span_err(options_span, msg).emit();
+        }
+    }
+
+    Ok(args)
+}
+
+fn parse_options<'a>(p: &mut Parser<'a>, args: &mut AsmArgs) -> PResult<'a, ()> {
+    let span_start = p.prev_token.span;
+
+    p.expect(&token::OpenDelim(token::Paren))?;
+
+    while !p.eat(&token::CloseDelim(token::Paren)) {
+        let options = &mut args.options;
+        if p.eat_keyword(sym::pure) {
+            options.pure = true;
+        } else if p.eat_keyword(sym::nomem) {
+            options.nomem = true;
+        } else if p.eat_keyword(sym::readonly) {
+            options.readonly = true;
+        } else if p.eat_keyword(sym::preserves_flags) {
+            options.preserves_flags = true;
+        } else if p.eat_keyword(sym::nostack) {
+            options.nostack = true;
+        } else if p.eat_keyword(sym::att_syntax) {
+            options.att_syntax = true;
+        } else {
+            let msg = "expected one of `att_syntax`, `nomem`, `nostack`, `preserves_flags`, \
+                       `pure` or `readonly`";
+            return Err(p.struct_span_err(p.token.span, msg));
+        }
+
+        // Accept trailing commas.
+        if p.eat(&token::CloseDelim(token::Paren)) {
+            break;
+        }
+        p.expect(&token::Comma)?;
+    }
+
+    let new_span = span_start.to(p.prev_token.span);
+    args.options_span = Some(args.options_span.map_or(new_span, |span| span.to(new_span)));
+
+    Ok(())
+}
+
+fn parse_reg<'a>(
+    p: &mut Parser<'a>,
+    explicit_reg: &mut bool,
+    arch: InlineAsmArch,
+) -> PResult<'a, AsmReg> {
+    p.expect(&token::OpenDelim(token::Paren))?;
+    let span = p.token.span;
+    let reg = match p.token.kind {
+        token::Ident(name, false) => {
+            p.bump();
+            InlineAsmRegClass::parse(arch, &name.as_str()).map(AsmReg::Class).map_err(|msg| {
+                p.struct_span_err(span, &format!("invalid register class `{}`: {}", name, msg))
+            })?
+        }
+        token::Literal(token::Lit { kind: token::LitKind::Str, symbol, suffix: _ }) => {
+            p.bump();
+            *explicit_reg = true;
+            InlineAsmReg::parse(arch, &symbol.as_str()).map(AsmReg::Explicit).map_err(|msg| {
+                p.struct_span_err(span, &format!("invalid register `{}`: {}", symbol, msg))
+            })?
+        }
+        _ => {
+            let msg = "expected register class or explicit register";
+            return Err(p.struct_span_err(span, msg));
+        }
+    };
+    p.expect(&token::CloseDelim(token::Paren))?;
+    Ok(reg)
+}
+
+/// Parses the expression of an output, or `_` to discard the output.
+fn parse_output_expr<'a>(p: &mut Parser<'a>) -> PResult<'a, Option<P<ast::Expr>>> {
+    if p.eat_keyword(kw::Underscore) { Ok(None) } else { Ok(Some(p.parse_expr()?)) }
+}
+
+fn parse_inout<'a>(p: &mut Parser<'a>, reg: AsmReg, late: bool) -> PResult<'a, AsmOperand> {
+    let expr = p.parse_expr()?;
+    if p.eat(&token::FatArrow) {
+        let out_expr = parse_output_expr(p)?;
+        Ok(AsmOperand::SplitInOut { reg, late, in_expr: expr, out_expr })
+    } else {
+        Ok(AsmOperand::InOut { reg, late, expr })
+    }
+}
+
+/// Where an operand ended up among the legacy outputs and inputs.
+#[derive(Copy, Clone)]
+enum Slot {
+    Output(usize),
+    Input(usize),
+    /// The operand is only a clobber, or is invalid.
+    None,
+}
+
+/// Lowers the typed syntax to the legacy one, returning `None` if errors were
+/// reported.
+fn lower_typed_asm(
+    cx: &mut ExtCtxt<'_>,
+    sp: Span,
+    args: AsmArgs,
+    arch: InlineAsmArch,
+) -> Option<P<ast::Expr>> {
+    let span = cx.with_def_site_ctxt(sp);
+    let mut errored = false;
+
+    let spans: Vec<_> = args.operands.iter().map(|&(_, span)| span).collect();
+    let classes: Vec<_> = args
+        .operands
+        .iter()
+        .map(|(operand, _)| match operand.reg() {
+            AsmReg::Class(class) => Some(*class),
+            AsmReg::Explicit(_) => None,
+        })
+        .collect();
+
+    // An explicit register can only be read by one operand and written by one.
+    let mut used_regs: FxHashMap<(InlineAsmReg, bool), Span> = FxHashMap::default();
+    for &(ref operand, op_span) in &args.operands {
+        if let AsmReg::Explicit(reg) = operand.reg() {
+            let (is_input, is_output) = operand.direction();
+            for &(is_use, is_output_use) in &[(is_input, false), (is_output, true)] {
+                if !is_use {
+                    continue;
+                }
+                let key = (reg.clone(), is_output_use);
+                if let Some(&prev) = used_regs.get(&key) {
+                    let kind = if is_output_use { "output" } else { "input" };
+                    let msg = format!("register `{}` is used by more than one {}", reg, kind);
+                    cx.struct_span_err(op_span, &msg)
+                        .span_label(prev, "first use")
+                        .span_label(op_span, "second use")
+                        .emit();
+                    errored = true;
+                } else {
+                    used_regs.insert(key, op_span);
+                }
+            }
+        }
+    }
+
+    // LLVM numbers outputs before inputs, so the operands are numbered once
+    // they are all placed.
+    let mut outputs = vec![];
+    let mut inputs = vec![];
+    let mut clobbers = vec![];
+    let mut stmts = vec![];
+    let mut slots = vec![];
+    for (index, (operand, op_span)) in args.operands.into_iter().enumerate() {
+        let constraint = operand.reg().llvm_constraint();
+        let output = |late: bool, expr, is_rw| ast::InlineAsmOutput {
+            // Unless it is late, the output may be written before all inputs
+            // are read, so it cannot share a register with them.
+            constraint: Symbol::intern(&format!("={}{}", if late { "" } else { "&" }, constraint)),
+            expr,
+            is_rw,
+            is_indirect: false,
+        };
+        let slot = match operand {
+            AsmOperand::In { expr, .. } => {
+                inputs.push((Symbol::intern(&constraint), expr));
+                Slot::Input(inputs.len() - 1)
+            }
+            AsmOperand::Out { late, expr: Some(expr), .. } => {
+                outputs.push(output(late, expr, false));
+                Slot::Output(outputs.len() - 1)
+            }
+            AsmOperand::Out { reg: AsmReg::Explicit(reg), expr: None, .. } => {
+                // Discarded outputs are lowered to clobbers, which LLVM doesn't
+                // allow to overlap with inputs.
+                if let Some(&input_span) = used_regs.get(&(reg.clone(), false)) {
+                    let msg = format!("register `{}` is both an input and a discarded output", reg);
+                    cx.struct_span_err(op_span, &msg)
+                        .span_label(input_span, "input")
+                        .help(&format!("use `inout(\"{}\") <input> => _` instead", reg))
+                        .emit();
+                    errored = true;
+                }
+                clobbers.push(Symbol::intern(reg.name()));
+                Slot::None
+            }
+            AsmOperand::Out { reg: AsmReg::Class(_), expr: None, .. } => {
+                cx.struct_span_err(op_span, "discarded outputs need an explicit register")
+                    .help("use a variable of the type of the output instead of `_`")
+                    .emit();
+                errored = true;
+                Slot::None
+            }
+            AsmOperand::InOut { late, expr, .. } => {
+                outputs.push(output(late, expr, true));
+                Slot::Output(outputs.len() - 1)
+            }
+            AsmOperand::SplitInOut { late, in_expr, out_expr: Some(out_expr), .. } => {
+                let output_index = outputs.len();
+                outputs.push(output(late, out_expr, false));
+                inputs.push((Symbol::intern(&output_index.to_string()), in_expr));
+                Slot::Output(output_index)
+            }
+            AsmOperand::SplitInOut { late, in_expr, out_expr: None, .. } => {
+                // The output needs a place to go, so write it to a copy of the input.
+                let name = Symbol::intern(&format!("__asm_discarded_output_{}", index));
+                let ident = ast::Ident::new(name, span);
+                stmts.push(cx.stmt_let(span, true, ident, in_expr));
+                outputs.push(output(late, cx.expr_ident(span, ident), true));
+                Slot::Output(outputs.len() - 1)
+            }
+        };
+        slots.push(slot);
+    }
+    let llvm_index = |slot| match slot {
+        Slot::Output(index) => Some(index),
+        Slot::Input(index) => Some(outputs.len() + index),
+        Slot::None => None,
+    };
+
+    let mut template = String::new();
+    let mut used = vec![false; slots.len()];
+    let mut next_implicit = 0;
+    for (i, template_expr) in args.templates.into_iter().enumerate() {
+        if i != 0 {
+            template.push('\n');
+        }
+        let template_span = template_expr.span;
+        let msg = "asm template must be a string literal";
+        let (template_str, _) = expr_to_string(cx, template_expr, msg)?;
+        let template_str = template_str.as_str();
+
+        let mut chars = template_str.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                // `$` introduces operands in LLVM templates.
+                '$' => template.push_str("$$"),
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    template.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    template.push('}');
+                }
+                '}' => {
+                    let msg = "invalid asm template string: unmatched `}` found";
+                    cx.struct_span_err(template_span, msg)
+                        .note("if you intended to print `}`, you can escape it using `}}`")
+                        .emit();
+                    errored = true;
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    let mut closed = false;
+                    for c in &mut chars {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        placeholder.push(c);
+                    }
+                    if !closed {
+                        let msg = "invalid asm template string: expected `}`, found end of string";
+                        cx.struct_span_err(template_span, msg)
+                            .note("if you intended to print `{`, you can escape it using `{{`")
+                            .emit();
+                        errored = true;
+                        break;
+                    }
+
+                    let (arg, modifier) = match placeholder.find(':') {
+                        Some(colon) => (&placeholder[..colon], Some(&placeholder[colon + 1..])),
+                        None => (&placeholder[..], None),
+                    };
+                    let index = if arg.is_empty() {
+                        next_implicit += 1;
+                        next_implicit - 1
+                    } else if let Ok(index) = arg.parse::<usize>() {
+                        index
+                    } else if let Some(&index) = args.named_args.get(&Symbol::intern(arg)) {
+                        index
+                    } else {
+                        let msg = format!("there is no argument named `{}`", arg);
+                        cx.struct_span_err(template_span, &msg).emit();
+                        errored = true;
+                        continue;
+                    };
+
+                    if index >= slots.len() {
+                        let msg = format!("invalid reference to argument at index {}", index);
+                        let note = match slots.len() {
+                            1 => "there is 1 argument".to_string(),
+                            count => format!("there are {} arguments", count),
+                        };
+                        cx.struct_span_err(template_span, &msg).note(&note).emit();
+                        errored = true;
+                        continue;
+                    }
+                    used[index] = true;
+                    let class = match classes[index] {
+                        Some(class) => class,
+                        None => {
+                            let msg = "explicit register arguments cannot be used in the asm \
+                                       template";
+                            cx.struct_span_err(template_span, msg)
+                                .span_label(spans[index], "explicit register argument")
+                                .emit();
+                            errored = true;
+                            continue;
+                        }
+                    };
+
+                    let llvm_modifier = match modifier {
+                        None => None,
+                        Some(modifier) => {
+                            let mut modifier_chars = modifier.chars();
+                            let valid_modifiers = class.valid_modifiers(arch);
+                            match (modifier_chars.next(), modifier_chars.next()) {
+                                (Some(modifier), None) if valid_modifiers.contains(&modifier) => {
+                                    class.llvm_modifier(modifier)
+                                }
+                                _ => {
+                                    let msg = "invalid asm template modifier for this register \
+                                               class";
+                                    let mut err = cx.struct_span_err(template_span, msg);
+                                    err.span_label(spans[index], "argument");
+                                    if valid_modifiers.is_empty() {
+                                        err.note(&format!(
+                                            "the `{}` register class does not support template \
+                                             modifiers",
+                                            class
+                                        ));
+                                    } else {
+                                        let valid: Vec<_> = valid_modifiers
+                                            .iter()
+                                            .map(|m| format!("`{}`", m))
+                                            .collect();
+                                        err.note(&format!(
+                                            "the `{}` register class supports the following \
+                                             template modifiers: {}",
+                                            class,
+                                            valid.join(", ")
+                                        ));
+                                    }
+                                    err.emit();
+                                    errored = true;
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+
+                    if let Some(llvm_index) = llvm_index(slots[index]) {
+                        match llvm_modifier {
+                            Some(modifier) => {
+                                template.push_str(&format!("${{{}:{}}}", llvm_index, modifier))
+                            }
+                            None => template.push_str(&format!("${{{}}}", llvm_index)),
+                        }
+                    }
+                }
+                c => template.push(c),
+            }
+        }
+    }
+
+    for (index, &used) in used.iter().enumerate() {
+        if used || classes[index].is_none() {
+            continue;
+        }
+        let msg = if args.named_args.values().any(|&named| named == index) {
+            "named argument never used"
+        } else {
+            "argument never used"
+        };
+        cx.struct_span_err(spans[index], msg)
+            .span_label(spans[index], msg)
+            .help(&format!(
+                "if this argument is intentionally unused, consider using it in an asm comment: \
+                 `\"/* {{{}}} */\"`",
+                index
+            ))
+            .emit();
+        errored = true;
+    }
+
+    if errored {
+        return None;
+    }
+
+    let options = args.options;
+    let memory = if options.nomem {
+        AsmMemory::NoMem
+    } else if options.readonly {
+        AsmMemory::ReadOnly
+    } else {
+        AsmMemory::ReadWrite
+    };
+    let dialect = if arch.has_att_syntax() && !options.att_syntax {
+        AsmDialect::Intel
+    } else {
+        AsmDialect::Att
+    };
+    let inline_asm = ast::InlineAsm {
+        asm: Symbol::intern(&template),
+        asm_str_style: ast::StrStyle::Cooked,
+        outputs,
+        inputs,
+        clobbers,
+        // Unless it is `pure`, the assembly has side effects, which must not be
+        // optimized out along with its outputs.
+        volatile: !options.pure,
+        // Unless the assembly doesn't touch the stack, the stack must be aligned
+        // as calls expect it to be.
+        alignstack: !options.nostack,
+        dialect,
+        memory,
+        preserves_flags: options.preserves_flags,
+    };
+
+    let expr = P(ast::Expr {
+        id: ast::DUMMY_NODE_ID,
+        kind: ast::ExprKind::InlineAsm(P(inline_asm)),
+        span,
+        attrs: ast::AttrVec::new(),
+    });
+    if stmts.is_empty() {
+        Some(expr)
+    } else {
+        stmts.push(cx.stmt_expr(expr));
+        Some(cx.expr_block(cx.block(span, stmts)))
+    }
+}
@@ -4,6 +4,7 @@ use crate::llvm;
 use crate::type_of::LayoutLlvmExt;
 use crate::value::Value;
 
+use rustc_ast::ast::AsmMemory;
 use rustc_codegen_ssa::mir::operand::OperandValue;
 use rustc_codegen_ssa::mir::place::PlaceRef;
 use rustc_codegen_ssa::traits::*;
@@ -53,6 +54,7 @@ impl AsmBuilderMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         // Default per-arch clobbers
         // Basically what clang does
         let arch_clobbers = match &self.sess().target.target.arch[..] {
+            "x86" | "x86_64" if ia.preserves_flags => vec![],
             "x86" | "x86_64" => vec!["~{dirflag}", "~{fpsr}", "~{flags}"],
             "mips" | "mips64" => vec!["~{$1}"],
             _ => Vec::new(),
@@ -95,6 +97,16 @@ impl AsmBuilderMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         }
         let r = r.unwrap();
 
+        match ia.memory {
+            AsmMemory::ReadWrite => {}
+            AsmMemory::ReadOnly => {
+                llvm::Attribute::ReadOnly.apply_callsite(llvm::AttributePlace::Function, r)
+            }
+            AsmMemory::NoMem => {
+                llvm::Attribute::ReadNone.apply_callsite(llvm::AttributePlace::Function, r)
+            }
+        }
+
         // Again, based on how many outputs we have
         let outputs = ia.outputs.iter().zip(&outputs).filter(|&(ref o, _)| !o.is_indirect);
         for (i, (_, &place)) in outputs.enumerate() {
@@ -66,7 +66,18 @@ pub fn sanitize(cx: &CodegenCx<'ll, '_>, codegen_fn_flags: CodegenFnAttrFlags, l
                     llvm::Attribute::SanitizeThread.apply_llfn(Function, llfn);
                 }
             }
-            Sanitizer::Leak => {}
+            Sanitizer::HwAddress => {
+                if !codegen_fn_flags.contains(CodegenFnAttrFlags::NO_SANITIZE_HWADDRESS) {
+                    llvm::Attribute::SanitizeHWAddress.apply_llfn(Function, llfn);
+                }
+            }
+            Sanitizer::ShadowCallStack => {
+                if !codegen_fn_flags.contains(CodegenFnAttrFlags::NO_SANITIZE_SHADOW_CALL_STACK) {
+                    llvm::Attribute::ShadowCallStack.apply_llfn(Function, llfn);
+                }
+            }
+            // CFI checks are emitted along with the indirect calls they guard.
+            Sanitizer::Cfi | Sanitizer::Leak => {}
         }
     }
 }
@@ -194,12 +205,21 @@ pub fn llvm_target_features(sess: &Session) -> impl Iterator<Item = &str> {
         .target_feature
         .split(',')
         .filter(|f| !RUSTC_SPECIFIC_FEATURES.iter().any(|s| f.contains(s)));
+    let sanitizer: &[&str] = match sess.opts.debugging_opts.sanitizer {
+        // Instrumented code finds the tag of a global in the top byte of its address, which only
+        // survives if the code that takes the address keeps it.
+        Some(Sanitizer::HwAddress) => &["+tagged-globals"],
+        // The shadow call stack is addressed through `x18`, which nothing else may use.
+        Some(Sanitizer::ShadowCallStack) => &["+reserve-x18"],
+        _ => &[],
+    };
     sess.target
         .target
         .options
         .features
         .split(',')
         .chain(cmdline)
+        .chain(sanitizer.iter().cloned())
         .filter(|l| !l.is_empty())
         .map(translate_obsolete_target_features)
 }
@@ -0,0 +1,52 @@
+//! Reads the size of the symbols of the compiled modules from their object files, for
+//! `-Z code-size-report`.
+
+use crate::llvm::{self, False, ObjectFile};
+use rustc_codegen_ssa::back::code_size::SymbolSizes;
+use rustc_codegen_ssa::CompiledModule;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_fs_util::path_to_c_string;
+use rustc_session::Session;
+use std::ffi::CStr;
+use std::path::Path;
+
+/// Returns the size of the symbols defined by the object file of each module. The sizes come
+/// from the symbol table, which only records them for ELF targets.
+pub fn symbol_sizes(sess: &Session, modules: &[CompiledModule]) -> SymbolSizes {
+    let mut symbol_sizes = SymbolSizes::default();
+    for module in modules {
+        let path = match module.object {
+            Some(ref path) => path,
+            None => continue,
+        };
+        match read_symbol_sizes(path) {
+            Some(sizes) => {
+                symbol_sizes.insert(module.name.clone(), sizes);
+            }
+            None => sess.warn(&format!(
+                "could not read the symbols of `{}` for the code size report",
+                path.display()
+            )),
+        }
+    }
+    symbol_sizes
+}
+
+fn read_symbol_sizes(path: &Path) -> Option<FxHashMap<String, u64>> {
+    unsafe {
+        let path = path_to_c_string(path);
+        let mb = llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(path.as_ptr())?;
+        let of = ObjectFile::new(mb)?;
+        let si = llvm::mk_symbol_iter(of.llof);
+        let mut sizes = FxHashMap::default();
+        while llvm::LLVMIsSymbolIteratorAtEnd(of.llof, si.llsi) == False {
+            let size = llvm::LLVMGetSymbolSize(si.llsi);
+            if size > 0 {
+                let name = CStr::from_ptr(llvm::LLVMGetSymbolName(si.llsi));
+                sizes.insert(name.to_string_lossy().into_owned(), size);
+            }
+            llvm::LLVMMoveToNextSymbol(si.llsi);
+        }
+        Some(sizes)
+    }
+}
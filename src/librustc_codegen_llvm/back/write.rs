@@ -72,11 +72,21 @@ pub fn write_output_file(
     pm: &llvm::PassManager<'ll>,
     m: &'ll llvm::Module,
     output: &Path,
+    dwo_output: Option<&Path>,
     file_type: llvm::FileType,
 ) -> Result<(), FatalError> {
     unsafe {
         let output_c = path_to_c_string(output);
-        let result = llvm::LLVMRustWriteOutputFile(target, pm, m, output_c.as_ptr(), file_type);
+        let dwo_output_c = dwo_output.map(path_to_c_string);
+        let dwo_output_ptr = dwo_output_c.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+        let result = llvm::LLVMRustWriteOutputFile(
+            target,
+            pm,
+            m,
+            output_c.as_ptr(),
+            dwo_output_ptr,
+            file_type,
+        );
         result.into_result().map_err(|()| {
             let msg = format!("could not write output to {}", output.display());
             llvm_err(handler, &msg)
@@ -340,6 +350,13 @@ fn get_pgo_use_path(config: &ModuleConfig) -> Option<CString> {
         .map(|path_buf| CString::new(path_buf.to_string_lossy().as_bytes()).unwrap())
 }
 
+fn get_pgo_sample_use_path(config: &ModuleConfig) -> Option<CString> {
+    config
+        .pgo_sample_use
+        .as_ref()
+        .map(|path_buf| CString::new(path_buf.to_string_lossy().as_bytes()).unwrap())
+}
+
 pub(crate) fn should_use_new_llvm_pass_manager(config: &ModuleConfig) -> bool {
     // We only support the new pass manager starting with LLVM 9.
     if llvm_util::get_major_version() < 9 {
@@ -362,6 +379,7 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
     let using_thin_buffers = opt_stage == llvm::OptStage::PreLinkThinLTO || config.bitcode_needed();
     let pgo_gen_path = get_pgo_gen_path(config);
     let pgo_use_path = get_pgo_use_path(config);
+    let pgo_sample_use_path = get_pgo_sample_use_path(config);
     let is_lto = opt_stage == llvm::OptStage::ThinLTO || opt_stage == llvm::OptStage::FatLTO;
     // Sanitizer instrumentation is only inserted during the pre-link optimization stage.
     let sanitizer_options = if !is_lto {
@@ -369,6 +387,7 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
             sanitize_memory: *s == Sanitizer::Memory,
             sanitize_thread: *s == Sanitizer::Thread,
             sanitize_address: *s == Sanitizer::Address,
+            sanitize_hwaddress: *s == Sanitizer::HwAddress,
             sanitize_recover: config.sanitizer_recover.contains(s),
             sanitize_memory_track_origins: config.sanitizer_memory_track_origins as c_int,
         })
@@ -405,6 +424,7 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
         sanitizer_options.as_ref(),
         pgo_gen_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         pgo_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+        pgo_sample_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         llvm_selfprofiler,
         selfprofile_before_pass_callback,
         selfprofile_after_pass_callback,
@@ -588,7 +608,10 @@ unsafe fn add_sanitizer_passes(config: &ModuleConfig, passes: &mut Vec<&'static
         Sanitizer::Thread => {
             passes.push(llvm::LLVMRustCreateThreadSanitizerPass());
         }
-        Sanitizer::Leak => {}
+        Sanitizer::HwAddress => {
+            passes.push(llvm::LLVMRustCreateHWAddressSanitizerPass(recover));
+        }
+        Sanitizer::Cfi | Sanitizer::Leak | Sanitizer::ShadowCallStack => {}
     }
 }
 
@@ -745,7 +768,15 @@ pub(crate) unsafe fn codegen(
             // module to produce the asm output
             let llmod = if config.emit_obj { llvm::LLVMCloneModule(llmod) } else { llmod };
             with_codegen(tm, llmod, config.no_builtins, |cpm| {
-                write_output_file(diag_handler, tm, cpm, llmod, &path, llvm::FileType::AssemblyFile)
+                write_output_file(
+                    diag_handler,
+                    tm,
+                    cpm,
+                    llmod,
+                    &path,
+                    None,
+                    llvm::FileType::AssemblyFile,
+                )
             })?;
         }
 
@@ -754,6 +785,11 @@ pub(crate) unsafe fn codegen(
                 let _timer = cgcx
                     .prof
                     .generic_activity_with_arg("LLVM_module_codegen_emit_obj", &module.name[..]);
+                let dwo_out = if config.emit_dwarf_object {
+                    Some(cgcx.output_filenames.split_dwarf_path(module_name))
+                } else {
+                    None
+                };
                 with_codegen(tm, llmod, config.no_builtins, |cpm| {
                     write_output_file(
                         diag_handler,
@@ -761,6 +797,7 @@ pub(crate) unsafe fn codegen(
                         cpm,
                         llmod,
                         &obj_out,
+                        dwo_out.as_deref(),
                         llvm::FileType::ObjectFile,
                     )
                 })?;
@@ -799,6 +836,7 @@ pub(crate) unsafe fn codegen(
         config.emit_obj,
         config.emit_bc,
         config.emit_bc_compressed,
+        config.emit_dwarf_object,
         &cgcx.output_filenames,
     ))
 }
@@ -873,6 +911,7 @@ pub unsafe fn with_llvm_pmb(
     let inline_threshold = config.inline_threshold;
     let pgo_gen_path = get_pgo_gen_path(config);
     let pgo_use_path = get_pgo_use_path(config);
+    let pgo_sample_use_path = get_pgo_sample_use_path(config);
 
     llvm::LLVMRustConfigurePassManagerBuilder(
         builder,
@@ -883,6 +922,7 @@ pub unsafe fn with_llvm_pmb(
         prepare_for_thin_lto,
         pgo_gen_path.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
         pgo_use_path.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+        pgo_sample_use_path.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
     );
 
     llvm::LLVMPassManagerBuilderSetSizeLevel(builder, opt_size as u32);
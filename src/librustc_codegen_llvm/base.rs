@@ -144,6 +144,9 @@ pub fn compile_codegen_unit(
                 }
             }
 
+            // Emit the coverage map, which is kept alive through llvm.used
+            cx.coverageinfo_finalize();
+
             // Create the llvm.used variable
             // This variable has type [N x i8*] and is stored in the llvm.metadata section
             if !cx.used_statics().borrow().is_empty() {
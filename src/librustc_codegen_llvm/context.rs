@@ -1,5 +1,6 @@
 use crate::attributes;
 use crate::callee::get_fn;
+use crate::coverageinfo;
 use crate::debuginfo;
 use crate::llvm;
 use crate::llvm_util;
@@ -19,7 +20,7 @@ use rustc_data_structures::base_n;
 use rustc_data_structures::const_cstr;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::small_c_str::SmallCStr;
-use rustc_session::config::{self, CFGuard, DebugInfo};
+use rustc_session::config::{self, CFGuard, DebugInfo, Sanitizer};
 use rustc_session::Session;
 use rustc_span::source_map::{Span, DUMMY_SP};
 use rustc_span::symbol::Symbol;
@@ -80,6 +81,7 @@ pub struct CodegenCx<'ll, 'tcx> {
     pub isize_ty: &'ll Type,
 
     pub dbg_cx: Option<debuginfo::CrateDebugContext<'ll, 'tcx>>,
+    pub coverage_cx: Option<coverageinfo::CrateCoverageContext<'ll, 'tcx>>,
 
     eh_personality: Cell<Option<&'ll Value>>,
     pub rust_try_fn: Cell<Option<&'ll Value>>,
@@ -298,6 +300,12 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
             None
         };
 
+        let coverage_cx = if tcx.sess.opts.debugging_opts.instrument_coverage {
+            Some(coverageinfo::CrateCoverageContext::new())
+        } else {
+            None
+        };
+
         let isize_ty = Type::ix_llcx(llcx, tcx.data_layout.pointer_size.bits());
 
         CodegenCx {
@@ -320,6 +328,7 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
             pointee_infos: Default::default(),
             isize_ty,
             dbg_cx,
+            coverage_cx,
             eh_personality: Cell::new(None),
             rust_try_fn: Cell::new(None),
             intrinsics: Default::default(),
@@ -796,6 +805,14 @@ impl CodegenCx<'b, 'tcx> {
         ifn!("llvm.va_end", fn(i8p) -> void);
         ifn!("llvm.va_copy", fn(i8p, i8p) -> void);
 
+        if self.sess().opts.debugging_opts.instrument_coverage {
+            ifn!("llvm.instrprof.increment", fn(i8p, t_i64, t_i32, t_i32) -> void);
+        }
+
+        if self.sess().opts.debugging_opts.sanitizer == Some(Sanitizer::Cfi) {
+            ifn!("llvm.type.test", fn(i8p, self.type_metadata()) -> i1);
+        }
+
         if self.sess().opts.debuginfo != DebugInfo::None {
             ifn!("llvm.dbg.declare", fn(self.type_metadata(), self.type_metadata()) -> void);
             ifn!("llvm.dbg.value", fn(self.type_metadata(), t_i64, self.type_metadata()) -> void);
@@ -0,0 +1,148 @@
+//! Generates the coverage map of a codegen unit, in the format `llvm-cov` reads. See
+//! <https://llvm.org/docs/CoverageMappingFormat.html> for a description of the format.
+
+use crate::common::CodegenCx;
+use crate::llvm;
+use crate::llvm::coverageinfo::{Counter, CounterMappingRegion};
+use libc::c_uint;
+use log::debug;
+use rustc::bug;
+use rustc_codegen_ssa::traits::*;
+use rustc_data_structures::fx::FxIndexSet;
+use std::ffi::CString;
+
+/// Writes the coverage map of the functions in the codegen unit, as a `__llvm_coverage_mapping`
+/// global in the coverage map section:
+///
+/// - a header with the number of functions, the sizes of the two parts of the encoded data
+///   below, and the version of the format;
+/// - a record for each function: the hash of its PGO name, the size of its encoded regions and
+///   the hash of its source;
+/// - the encoded list of the filenames referenced by the regions, followed by the encoded regions
+///   of each function, padded to a multiple of 8 bytes.
+pub(super) fn write_coverage_map(cx: &CodegenCx<'ll, 'tcx>) {
+    let mut function_coverage_map = cx.coverage_context().take_function_coverage_map();
+    if function_coverage_map.is_empty() {
+        return;
+    }
+
+    // Sort by symbol name so the map does not depend on the iteration order of the hash map.
+    let mut functions: Vec<_> = function_coverage_map
+        .drain()
+        .map(|(instance, coverage)| (cx.tcx.symbol_name(instance).name.to_string(), coverage))
+        .collect();
+    functions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let source_map = cx.sess().source_map();
+    let mut filenames = FxIndexSet::default();
+    let mut function_records = Vec::with_capacity(functions.len());
+    let mut coverage_mappings = Vec::new();
+    for (mangled_fn_name, function_coverage) in functions {
+        // The regions refer to the filenames of the codegen unit through a per-function table.
+        let mut virtual_file_mapping = Vec::new();
+        let mut mapping_regions = Vec::new();
+        for (counter, lo, hi) in function_coverage.counter_regions() {
+            let start = source_map.lookup_char_pos(lo);
+            let end = source_map.lookup_char_pos(hi);
+            let (filename_index, _) = filenames.insert_full(start.file.name.to_string());
+            let filename_index = filename_index as c_uint;
+            let file_id = match virtual_file_mapping.iter().position(|&i| i == filename_index) {
+                Some(file_id) => file_id,
+                None => {
+                    virtual_file_mapping.push(filename_index);
+                    virtual_file_mapping.len() - 1
+                }
+            };
+            // `llvm-cov` columns are 1-based, and `hi` is already one past the region.
+            mapping_regions.push(CounterMappingRegion::code_region(
+                Counter::counter_value_reference(counter),
+                file_id as u32,
+                start.line as u32,
+                start.col.0 as u32 + 1,
+                end.line as u32,
+                end.col.0 as u32 + 1,
+            ));
+        }
+
+        let coverage_mapping = llvm::build_byte_buffer(|buffer| unsafe {
+            llvm::LLVMRustCoverageWriteMappingToBuffer(
+                virtual_file_mapping.as_ptr(),
+                virtual_file_mapping.len() as c_uint,
+                mapping_regions.as_mut_ptr(),
+                mapping_regions.len() as c_uint,
+                buffer,
+            );
+        });
+        debug!(
+            "coverage map of {}: {} regions in {} bytes",
+            mangled_fn_name,
+            mapping_regions.len(),
+            coverage_mapping.len()
+        );
+
+        let mangled_fn_name = CString::new(mangled_fn_name).unwrap();
+        let name_hash = unsafe { llvm::LLVMRustCoverageComputeHash(mangled_fn_name.as_ptr()) };
+        function_records.push(cx.const_struct(
+            &[
+                cx.const_u64(name_hash),
+                cx.const_u32(coverage_mapping.len() as u32),
+                cx.const_u64(function_coverage.source_hash),
+            ],
+            /*packed=*/ true,
+        ));
+        coverage_mappings.extend(coverage_mapping);
+    }
+
+    let filenames: Vec<_> = filenames.into_iter().map(|name| CString::new(name).unwrap()).collect();
+    let filename_ptrs: Vec<_> = filenames.iter().map(|name| name.as_ptr()).collect();
+    let mut data = llvm::build_byte_buffer(|buffer| unsafe {
+        llvm::LLVMRustCoverageWriteFilenamesSectionToBuffer(
+            filename_ptrs.as_ptr(),
+            filename_ptrs.len(),
+            buffer,
+        );
+    });
+    let filenames_size = data.len();
+    data.extend(coverage_mappings);
+    // The padding counts as part of the coverage mappings.
+    data.resize((data.len() + 7) / 8 * 8, 0);
+    let coverage_size = data.len() - filenames_size;
+
+    let header = cx.const_struct(
+        &[
+            cx.const_u32(function_records.len() as u32),
+            cx.const_u32(filenames_size as u32),
+            cx.const_u32(coverage_size as u32),
+            cx.const_u32(unsafe { llvm::LLVMRustCoverageMappingVersion() }),
+        ],
+        /*packed=*/ false,
+    );
+    let function_records_ty = cx.val_ty(function_records[0]);
+    let covmap = cx.const_struct(
+        &[header, cx.const_array(function_records_ty, &function_records), cx.const_bytes(&data)],
+        /*packed=*/ false,
+    );
+
+    let var_name = llvm::build_string(|s| unsafe {
+        llvm::LLVMRustCoverageWriteMappingVarNameToString(s);
+    })
+    .expect("coverage mapping variable name is not valid UTF-8");
+    let section_name = llvm::build_string(|s| unsafe {
+        llvm::LLVMRustCoverageWriteSectionNameToString(cx.llmod, s);
+    })
+    .expect("coverage mapping section name is not valid UTF-8");
+    let section_name = CString::new(section_name).unwrap();
+
+    let llglobal = cx
+        .define_global(&var_name, cx.val_ty(covmap))
+        .unwrap_or_else(|| bug!("symbol `{}` is already defined", var_name));
+    unsafe {
+        llvm::LLVMSetInitializer(llglobal, covmap);
+        llvm::LLVMSetGlobalConstant(llglobal, llvm::True);
+        llvm::LLVMRustSetLinkage(llglobal, llvm::Linkage::PrivateLinkage);
+        llvm::LLVMSetSection(llglobal, section_name.as_ptr());
+        llvm::LLVMSetAlignment(llglobal, 8);
+    }
+    // Nothing refers to the coverage map, so keep it alive explicitly.
+    cx.used_statics.borrow_mut().push(llglobal);
+}
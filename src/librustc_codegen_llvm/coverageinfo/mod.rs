@@ -0,0 +1,132 @@
+//! Source-based code coverage, for `-Z instrument-coverage`.
+//!
+//! The `InstrumentCoverage` MIR pass adds calls to the `count_code_region` intrinsic to each
+//! function. Every call is lowered to an `llvm.instrprof.increment` of one of the function's
+//! counters, and the source region that the counter counts is recorded here. Once all the
+//! functions of the codegen unit are generated, `mapgen` writes their regions into the LLVM
+//! coverage map, which `llvm-cov` combines with the counter values from the profiler runtime.
+
+use crate::builder::Builder;
+use crate::common::CodegenCx;
+use crate::llvm;
+use log::debug;
+use rustc::bug;
+use rustc::ty::Instance;
+use rustc_codegen_ssa::traits::*;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_span::BytePos;
+use std::cell::RefCell;
+use std::ffi::CString;
+
+mod mapgen;
+
+/// A context object for maintaining all state needed by the coverageinfo module.
+pub struct CrateCoverageContext<'ll, 'tcx> {
+    /// The coverage of each instrumented function in the codegen unit.
+    function_coverage_map: RefCell<FxHashMap<Instance<'tcx>, FunctionCoverage>>,
+    /// The global holding the PGO name of each instrumented function, which identifies the
+    /// function's counters in `llvm.instrprof.increment`.
+    pgo_func_name_vars: RefCell<FxHashMap<Instance<'tcx>, &'ll llvm::Value>>,
+}
+
+impl<'ll, 'tcx> CrateCoverageContext<'ll, 'tcx> {
+    pub fn new() -> Self {
+        Self { function_coverage_map: Default::default(), pgo_func_name_vars: Default::default() }
+    }
+
+    fn take_function_coverage_map(&self) -> FxHashMap<Instance<'tcx>, FunctionCoverage> {
+        self.function_coverage_map.replace(FxHashMap::default())
+    }
+}
+
+/// The counters of a function and the source regions they count.
+struct FunctionCoverage {
+    source_hash: u64,
+    /// The source map range counted by each counter. Counters that were optimized out have no
+    /// region, and are not part of the function's coverage map.
+    regions: Vec<Option<(BytePos, BytePos)>>,
+}
+
+impl FunctionCoverage {
+    fn new(source_hash: u64, num_counters: u32) -> Self {
+        Self { source_hash, regions: vec![None; num_counters as usize] }
+    }
+
+    /// Returns the counters that have a region, along with their region.
+    fn counter_regions(&self) -> impl Iterator<Item = (u32, BytePos, BytePos)> + '_ {
+        self.regions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, region)| region.map(|(lo, hi)| (index as u32, lo, hi)))
+    }
+}
+
+impl CodegenCx<'ll, 'tcx> {
+    /// Writes the coverage map of the functions in this codegen unit, if it has any.
+    pub fn coverageinfo_finalize(&self) {
+        if self.coverage_cx.is_some() {
+            mapgen::write_coverage_map(self);
+        }
+    }
+
+    fn coverage_context(&self) -> &CrateCoverageContext<'ll, 'tcx> {
+        self.coverage_cx.as_ref().unwrap()
+    }
+}
+
+impl Builder<'a, 'll, 'tcx> {
+    /// Increments counter `index` of `instance`, the function being built, and records that it
+    /// counts the source region from `start_offset` to `end_offset`.
+    ///
+    /// The offsets are relative to the start of the function body, since the function may come
+    /// from another crate, whose source map positions mean nothing in this one. They are added to
+    /// the span of the instance's MIR body, which is already translated into this crate's source
+    /// map.
+    pub fn count_code_region(
+        &mut self,
+        instance: Instance<'tcx>,
+        function_source_hash: &'ll llvm::Value,
+        index: &'ll llvm::Value,
+        start_offset: &'ll llvm::Value,
+        end_offset: &'ll llvm::Value,
+    ) -> &'ll llvm::Value {
+        let tcx = self.tcx;
+        let coverageinfo = tcx.coverageinfo(instance.def);
+        let const_u32 = |value| match self.const_to_opt_u128(value, false) {
+            Some(value) => value as u32,
+            None => bug!("non-constant argument to `count_code_region` in {}", instance),
+        };
+        let counter = const_u32(index);
+        let body_start = tcx.instance_mir(instance.def).span.lo();
+        let region = (
+            body_start + BytePos(const_u32(start_offset)),
+            body_start + BytePos(const_u32(end_offset)),
+        );
+        debug!("count_code_region({}, counter={}, region={:?})", instance, counter, region);
+
+        self.coverage_context()
+            .function_coverage_map
+            .borrow_mut()
+            .entry(instance)
+            .or_insert_with(|| {
+                FunctionCoverage::new(coverageinfo.function_source_hash, coverageinfo.num_counters)
+            })
+            .regions[counter as usize] = Some(region);
+
+        let llfn = self.llfn();
+        let fn_name_var = *self
+            .coverage_context()
+            .pgo_func_name_vars
+            .borrow_mut()
+            .entry(instance)
+            .or_insert_with(|| {
+                let mangled_fn_name = CString::new(&*tcx.symbol_name(instance).name.as_str())
+                    .expect("symbol names do not contain NUL bytes");
+                let mangled_fn_name = mangled_fn_name.as_ptr();
+                unsafe { llvm::LLVMRustCoverageCreatePGOFuncNameVar(llfn, mangled_fn_name) }
+            });
+        let num_counters = self.const_u32(coverageinfo.num_counters);
+        let llfn = self.get_intrinsic("llvm.instrprof.increment");
+        self.call(llfn, &[fn_name_var, function_source_hash, num_counters, index], None)
+    }
+}
@@ -934,6 +934,8 @@ pub fn compile_unit_metadata(
             split_name.as_ptr().cast(),
             split_name.len(),
             kind,
+            // Sample profiles are matched against the line tables, which this makes more precise.
+            tcx.sess.opts.cg.profile_sample_use.is_some(),
         );
 
         if tcx.sess.opts.debugging_opts.profile {
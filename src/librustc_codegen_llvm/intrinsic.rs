@@ -7,6 +7,7 @@ use crate::type_::Type;
 use crate::type_of::LayoutLlvmExt;
 use crate::va_arg::emit_va_arg;
 use crate::value::Value;
+use libc::c_uint;
 use rustc::ty::layout::{self, FnAbiExt, HasTyCtxt, LayoutOf, Primitive};
 use rustc::ty::{self, Ty};
 use rustc::{bug, span_bug};
@@ -87,6 +88,7 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         args: &[OperandRef<'tcx, &'ll Value>],
         llresult: &'ll Value,
         span: Span,
+        caller_instance: ty::Instance<'tcx>,
     ) {
         let tcx = self.tcx;
         let callee_ty = instance.monomorphic_ty(tcx);
@@ -123,6 +125,22 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
                 let expect = self.get_intrinsic(&("llvm.expect.i1"));
                 self.call(expect, &[args[0].immediate(), self.const_bool(false)], None)
             }
+            "count_code_region" => {
+                // Only emitted by the `InstrumentCoverage` MIR pass, with `-Z instrument-coverage`.
+                // The generic and `#[inline]` functions of a crate built with it keep their
+                // counters when they are codegened in a crate built without it, which does not
+                // collect coverage, so the counters do nothing there.
+                if !tcx.sess.opts.debugging_opts.instrument_coverage {
+                    return;
+                }
+                self.count_code_region(
+                    caller_instance,
+                    args[0].immediate(),
+                    args[1].immediate(),
+                    args[2].immediate(),
+                    args[3].immediate(),
+                )
+            }
             "try" => {
                 try_intrinsic(
                     self,
@@ -787,6 +805,19 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn type_test(&mut self, pointer: &'ll Value, typeid: &str) -> &'ll Value {
+        let intrinsic = self.cx().get_intrinsic("llvm.type.test");
+        let pointer = self.pointercast(pointer, self.type_i8p());
+        let typeid = unsafe {
+            llvm::LLVMMDStringInContext(
+                self.cx.llcx,
+                typeid.as_ptr().cast(),
+                typeid.len() as c_uint,
+            )
+        };
+        self.call(intrinsic, &[pointer, typeid], None)
+    }
+
     fn va_start(&mut self, va_list: &'ll Value) -> &'ll Value {
         let intrinsic = self.cx().get_intrinsic("llvm.va_start");
         self.call(intrinsic, &[va_list], None)
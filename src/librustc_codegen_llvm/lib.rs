@@ -22,6 +22,7 @@ use rustc::middle::cstore::{EncodedMetadata, MetadataLoaderDyn};
 use rustc::ty::{self, TyCtxt};
 use rustc::util::common::ErrorReported;
 use rustc_ast::expand::allocator::AllocatorKind;
+use rustc_codegen_ssa::back::code_size::write_code_size_report;
 use rustc_codegen_ssa::back::lto::{LtoModuleCodegen, SerializedModule, ThinModule};
 use rustc_codegen_ssa::back::write::{CodegenContext, FatLTOInput, ModuleConfig};
 use rustc_codegen_ssa::traits::*;
@@ -41,6 +42,7 @@ use std::sync::Arc;
 mod back {
     pub mod archive;
     pub mod bytecode;
+    pub mod code_size;
     pub mod lto;
     mod profiling;
     pub mod write;
@@ -56,6 +58,7 @@ mod callee;
 mod common;
 mod consts;
 mod context;
+mod coverageinfo;
 mod debuginfo;
 mod declare;
 mod intrinsic;
@@ -276,6 +279,18 @@ impl CodegenBackend for LlvmCodegenBackend {
             rustc_codegen_ssa::back::write::dump_incremental_data(&codegen_results);
         }
 
+        if let Some(ref path) = sess.opts.debugging_opts.code_size_report {
+            let symbol_sizes = back::code_size::symbol_sizes(sess, &codegen_results.modules);
+            let items = &codegen_results.code_size_items;
+            if let Err(err) = write_code_size_report(items, &symbol_sizes, path) {
+                sess.err(&format!(
+                    "failed to write code size report to `{}`: {}",
+                    path.display(),
+                    err
+                ));
+            }
+        }
+
         sess.time("serialize_work_products", move || {
             rustc_incremental::save_work_product_index(sess, &dep_graph, work_products)
         });
@@ -1,6 +1,7 @@
 #![allow(non_camel_case_types)]
 #![allow(non_upper_case_globals)]
 
+use super::coverageinfo::CounterMappingRegion;
 use super::debuginfo::{
     DIArray, DIBasicType, DIBuilder, DICompositeType, DIDerivedType, DIDescriptor, DIEnumerator,
     DIFile, DIFlags, DIGlobalVariableExpression, DILexicalBlock, DINameSpace, DISPFlags, DIScope,
@@ -124,6 +125,9 @@ pub enum Attribute {
     NonLazyBind = 23,
     OptimizeNone = 24,
     ReturnsTwice = 25,
+    ReadNone = 26,
+    SanitizeHWAddress = 27,
+    ShadowCallStack = 28,
 }
 
 /// LLVMIntPredicate
@@ -438,6 +442,7 @@ pub struct SanitizerOptions {
     pub sanitize_memory: bool,
     pub sanitize_thread: bool,
     pub sanitize_address: bool,
+    pub sanitize_hwaddress: bool,
     pub sanitize_recover: bool,
     pub sanitize_memory_track_origins: c_int,
 }
@@ -592,6 +597,8 @@ extern "C" {
 }
 #[repr(C)]
 pub struct SectionIterator<'a>(InvariantOpaque<'a>);
+#[repr(C)]
+pub struct SymbolIterator<'a>(InvariantOpaque<'a>);
 extern "C" {
     pub type Pass;
 }
@@ -713,6 +720,92 @@ pub mod debuginfo {
     }
 }
 
+pub mod coverageinfo {
+    use libc::c_uint;
+
+    /// Must match the layout of `LLVMRustCounterKind`.
+    #[derive(Copy, Clone, Debug)]
+    #[repr(C)]
+    #[allow(dead_code)] // Only `CounterValueReference` is used so far.
+    pub enum CounterKind {
+        Zero = 0,
+        CounterValueReference = 1,
+        Expression = 2,
+    }
+
+    /// A reference to an instrumentation counter or an expression of counters. Must match the
+    /// layout of `llvm::coverage::Counter`.
+    #[derive(Copy, Clone, Debug)]
+    #[repr(C)]
+    pub struct Counter {
+        pub kind: CounterKind,
+        pub id: c_uint,
+    }
+
+    impl Counter {
+        pub fn counter_value_reference(counter_id: u32) -> Self {
+            Self { kind: CounterKind::CounterValueReference, id: counter_id }
+        }
+    }
+
+    /// Must match the layout of `llvm::coverage::CounterMappingRegion::RegionKind`.
+    #[derive(Copy, Clone, Debug)]
+    #[repr(C)]
+    #[allow(dead_code)] // Only `CodeRegion` is used so far.
+    pub enum RegionKind {
+        /// A region that associates some code with a counter.
+        CodeRegion = 0,
+        /// An expansion region, for the code expanded from a macro.
+        ExpansionRegion = 1,
+        /// A region with no code, like a disabled `#[cfg]` block.
+        SkippedRegion = 2,
+        /// A gap region, covering the whitespace between two regions.
+        GapRegion = 3,
+    }
+
+    /// A source region and the counter that counts it. Must match the layout of
+    /// `llvm::coverage::CounterMappingRegion`.
+    ///
+    /// Lines and columns are 1-based, and the end column is the column after the last character
+    /// of the region.
+    #[derive(Copy, Clone, Debug)]
+    #[repr(C)]
+    pub struct CounterMappingRegion {
+        pub counter: Counter,
+        /// The index of the region's file in the function's list of files.
+        pub file_id: c_uint,
+        /// The file the region was expanded into, for expansion regions.
+        pub expanded_file_id: c_uint,
+        pub start_line: c_uint,
+        pub start_col: c_uint,
+        pub end_line: c_uint,
+        pub end_col: c_uint,
+        pub kind: RegionKind,
+    }
+
+    impl CounterMappingRegion {
+        pub fn code_region(
+            counter: Counter,
+            file_id: u32,
+            start_line: u32,
+            start_col: u32,
+            end_line: u32,
+            end_col: u32,
+        ) -> Self {
+            Self {
+                counter,
+                file_id,
+                expanded_file_id: 0,
+                start_line,
+                start_col,
+                end_line,
+                end_col,
+                kind: RegionKind::CodeRegion,
+            }
+        }
+    }
+}
+
 extern "C" {
     pub type ModuleBuffer;
 }
@@ -866,6 +959,12 @@ extern "C" {
     pub fn LLVMGetAlignment(Global: &Value) -> c_uint;
     pub fn LLVMSetAlignment(Global: &Value, Bytes: c_uint);
     pub fn LLVMSetDLLStorageClass(V: &Value, C: DLLStorageClass);
+    pub fn LLVMRustAddTypeMetadata(
+        Global: &Value,
+        Offset: u64,
+        TypeId: *const c_char,
+        TypeIdLen: size_t,
+    );
 
     // Operations on global variables
     pub fn LLVMIsAGlobalVariable(GlobalVar: &Value) -> Option<&Value>;
@@ -1566,6 +1665,19 @@ extern "C" {
     /// Returns the current section contents as a string buffer.
     pub fn LLVMGetSectionContents(SI: &SectionIterator<'_>) -> *const c_char;
 
+    /// Enumerates the symbols in an object file.
+    pub fn LLVMGetSymbols(ObjFile: &'a ObjectFile) -> &'a mut SymbolIterator<'a>;
+    /// Destroys a symbol iterator.
+    pub fn LLVMDisposeSymbolIterator(SI: &'a mut SymbolIterator<'a>);
+    /// Returns `true` if the symbol iterator is at the end of the symbol list.
+    pub fn LLVMIsSymbolIteratorAtEnd(ObjFile: &'a ObjectFile, SI: &SymbolIterator<'a>) -> Bool;
+    /// Moves the symbol iterator to point to the next symbol.
+    pub fn LLVMMoveToNextSymbol(SI: &SymbolIterator<'_>);
+    /// Returns the name of the current symbol.
+    pub fn LLVMGetSymbolName(SI: &SymbolIterator<'_>) -> *const c_char;
+    /// Returns the size of the current symbol, as recorded in the symbol table.
+    pub fn LLVMGetSymbolSize(SI: &SymbolIterator<'_>) -> u64;
+
     /// Reads the given file and returns it as a memory buffer. Use
     /// LLVMDisposeMemoryBuffer() to get rid of it.
     pub fn LLVMRustCreateMemoryBufferWithContentsOfFile(
@@ -1632,6 +1744,7 @@ extern "C" {
         SplitName: *const c_char,
         SplitNameLen: size_t,
         kind: DebugEmissionKind,
+        DebugInfoForProfiling: bool,
     ) -> &'a DIDescriptor;
 
     pub fn LLVMRustDIBuilderCreateFile(
@@ -1915,6 +2028,7 @@ extern "C" {
         Recover: bool,
     ) -> &'static mut Pass;
     pub fn LLVMRustCreateThreadSanitizerPass() -> &'static mut Pass;
+    pub fn LLVMRustCreateHWAddressSanitizerPass(Recover: bool) -> &'static mut Pass;
     pub fn LLVMRustAddPass(PM: &PassManager<'_>, Pass: &'static mut Pass);
     pub fn LLVMRustAddLastExtensionPasses(
         PMB: &PassManagerBuilder,
@@ -1961,6 +2075,7 @@ extern "C" {
         PrepareForThinLTO: bool,
         PGOGenPath: *const c_char,
         PGOUsePath: *const c_char,
+        PGOSampleUsePath: *const c_char,
     );
     pub fn LLVMRustAddLibraryInfo(
         PM: &PassManager<'a>,
@@ -1973,6 +2088,7 @@ extern "C" {
         PM: &PassManager<'a>,
         M: &'a Module,
         Output: *const c_char,
+        DwoOutput: *const c_char,
         FileType: FileType,
     ) -> LLVMRustResult;
     pub fn LLVMRustOptimizeWithNewPassManager(
@@ -1991,6 +2107,7 @@ extern "C" {
         SanitizerOptions: Option<&SanitizerOptions>,
         PGOGenPath: *const c_char,
         PGOUsePath: *const c_char,
+        PGOSampleUsePath: *const c_char,
         llvm_selfprofiler: *mut c_void,
         begin_callback: SelfProfileBeforePassCallback,
         end_callback: SelfProfileAfterPassCallback,
@@ -2028,6 +2145,28 @@ extern "C" {
     #[allow(improper_ctypes)]
     pub fn LLVMRustWriteTwineToString(T: &Twine, s: &RustString);
 
+    pub fn LLVMRustCoverageCreatePGOFuncNameVar(F: &'a Value, FuncName: *const c_char) -> &'a Value;
+    pub fn LLVMRustCoverageComputeHash(Name: *const c_char) -> u64;
+    pub fn LLVMRustCoverageMappingVersion() -> u32;
+    #[allow(improper_ctypes)]
+    pub fn LLVMRustCoverageWriteFilenamesSectionToBuffer(
+        Filenames: *const *const c_char,
+        FilenamesLen: size_t,
+        BufferOut: &RustString,
+    );
+    #[allow(improper_ctypes)]
+    pub fn LLVMRustCoverageWriteMappingToBuffer(
+        VirtualFileMappingIDs: *const c_uint,
+        NumVirtualFileMappingIDs: c_uint,
+        MappingRegions: *mut CounterMappingRegion,
+        NumMappingRegions: c_uint,
+        BufferOut: &RustString,
+    );
+    #[allow(improper_ctypes)]
+    pub fn LLVMRustCoverageWriteSectionNameToString(M: &Module, Str: &RustString);
+    #[allow(improper_ctypes)]
+    pub fn LLVMRustCoverageWriteMappingVarNameToString(Str: &RustString);
+
     pub fn LLVMContextSetDiagnosticHandler(
         C: &Context,
         Handler: DiagnosticHandler,
@@ -189,6 +189,24 @@ pub fn mk_section_iter(llof: &ffi::ObjectFile) -> SectionIter<'_> {
     unsafe { SectionIter { llsi: LLVMGetSections(llof) } }
 }
 
+// Memory-managed interface to symbol iterators.
+
+pub struct SymbolIter<'a> {
+    pub llsi: &'a mut SymbolIterator<'a>,
+}
+
+impl Drop for SymbolIter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeSymbolIterator(&mut *(self.llsi as *mut _));
+        }
+    }
+}
+
+pub fn mk_symbol_iter(llof: &ffi::ObjectFile) -> SymbolIter<'_> {
+    unsafe { SymbolIter { llsi: LLVMGetSymbols(llof) } }
+}
+
 /// Safe wrapper around `LLVMGetParam`, because segfaults are no fun.
 pub fn get_param(llfn: &Value, index: c_uint) -> &Value {
     unsafe {
@@ -225,6 +243,12 @@ pub fn build_string(f: impl FnOnce(&RustString)) -> Result<String, FromUtf8Error
     String::from_utf8(sr.bytes.into_inner())
 }
 
+pub fn build_byte_buffer(f: impl FnOnce(&RustString)) -> Vec<u8> {
+    let sr = RustString { bytes: RefCell::new(Vec::new()) };
+    f(&sr);
+    sr.bytes.into_inner()
+}
+
 pub fn twine_to_string(tr: &Twine) -> String {
     unsafe {
         build_string(|s| LLVMRustWriteTwineToString(tr, s)).expect("got a non-UTF8 Twine from LLVM")
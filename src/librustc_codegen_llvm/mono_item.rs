@@ -8,8 +8,10 @@ use log::debug;
 use rustc::mir::mono::{Linkage, Visibility};
 use rustc::ty::layout::{FnAbiExt, LayoutOf};
 use rustc::ty::{Instance, TypeFoldable};
+use rustc_codegen_ssa::common::typeid_for_fn_abi;
 use rustc_codegen_ssa::traits::*;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_session::config::Sanitizer;
 
 pub use rustc::mir::mono::MonoItem;
 
@@ -79,6 +81,15 @@ impl PreDefineMethods<'tcx> for CodegenCx<'ll, 'tcx> {
 
         attributes::from_fn_attrs(self, lldecl, instance, &fn_abi);
 
+        // Mark the function as a valid target of the indirect calls that are checked against its
+        // type identifier.
+        if self.tcx.sess.opts.debugging_opts.sanitizer == Some(Sanitizer::Cfi) {
+            let typeid = typeid_for_fn_abi(&fn_abi);
+            unsafe {
+                llvm::LLVMRustAddTypeMetadata(lldecl, 0, typeid.as_ptr().cast(), typeid.len());
+            }
+        }
+
         self.instances.borrow_mut().insert(instance, lldecl);
     }
 }
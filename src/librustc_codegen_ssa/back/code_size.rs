@@ -0,0 +1,176 @@
+//! The `-Z code-size-report` report, which attributes the machine code emitted for the crate to
+//! the functions, generic or not, that it was instantiated from.
+//!
+//! The mono items of each codegen unit are collected before codegen, while the type context is
+//! still around. Once the codegen units are compiled, the backend reads the size of each symbol
+//! from their object files, and the report matches the two up by symbol name.
+
+use rustc::mir::mono::{CodegenUnit, MonoItem};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_serialize::json::as_pretty_json;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A function in a codegen unit, as collected for `-Z code-size-report`.
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct CodeSizeItem {
+    pub cgu_name: String,
+    pub symbol_name: String,
+    /// The instance, with its generic arguments.
+    pub instance: String,
+    /// The path of the definition that the instance was instantiated from.
+    pub source: String,
+    /// The crate of that definition.
+    pub source_crate: String,
+}
+
+/// Collects the functions of `codegen_units`.
+pub fn collect_code_size_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[Arc<CodegenUnit<'tcx>>],
+) -> Vec<CodeSizeItem> {
+    let mut items = vec![];
+    for cgu in codegen_units {
+        for (mono_item, _) in cgu.items_in_deterministic_order(tcx) {
+            let instance = match mono_item {
+                MonoItem::Fn(instance) => instance,
+                MonoItem::Static(_) | MonoItem::GlobalAsm(_) => continue,
+            };
+            let def_id = instance.def_id();
+            items.push(CodeSizeItem {
+                cgu_name: cgu.name().to_string(),
+                symbol_name: mono_item.symbol_name(tcx).name.to_string(),
+                instance: instance.to_string(),
+                source: tcx.def_path_str(def_id),
+                source_crate: tcx.crate_name(def_id.krate).to_string(),
+            });
+        }
+    }
+    items
+}
+
+/// The size of the symbols defined by each module, by module name and symbol name.
+pub type SymbolSizes = FxHashMap<String, FxHashMap<String, u64>>;
+
+#[derive(RustcEncodable)]
+struct Report<'a> {
+    items: Vec<ItemReport<'a>>,
+    functions: Vec<FunctionReport<'a>>,
+    crates: Vec<CrateReport<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct ItemReport<'a> {
+    cgu: &'a str,
+    symbol: &'a str,
+    instance: &'a str,
+    source: &'a str,
+    source_crate: &'a str,
+    /// `None` if the function was inlined into all its callers, or otherwise optimized away.
+    size: Option<u64>,
+}
+
+#[derive(RustcEncodable)]
+struct FunctionReport<'a> {
+    source: &'a str,
+    source_crate: &'a str,
+    /// The number of distinct instances of the function.
+    instantiations: usize,
+    /// The number of copies of those instances, across codegen units.
+    copies: usize,
+    size: u64,
+}
+
+#[derive(RustcEncodable)]
+struct CrateReport<'a> {
+    source_crate: &'a str,
+    functions: usize,
+    copies: usize,
+    size: u64,
+}
+
+/// Writes the report for `items` to `path`, as JSON. Everything is sorted by size, largest
+/// first, so the functions that contribute the most code come first.
+pub fn write_code_size_report(
+    items: &[CodeSizeItem],
+    symbol_sizes: &SymbolSizes,
+    path: &Path,
+) -> io::Result<()> {
+    let size_of = |item: &CodeSizeItem| {
+        // After LTO, the functions are no longer in the module of their codegen unit.
+        let lookup = |sizes: &FxHashMap<String, u64>| {
+            // Mach-O prefixes the names of all symbols with an underscore.
+            sizes.get(&item.symbol_name).or_else(|| sizes.get(&format!("_{}", item.symbol_name)))
+        };
+        symbol_sizes
+            .get(&item.cgu_name)
+            .and_then(lookup)
+            .or_else(|| symbol_sizes.values().find_map(lookup))
+            .copied()
+    };
+
+    let mut item_reports: Vec<_> = items
+        .iter()
+        .map(|item| ItemReport {
+            cgu: &item.cgu_name,
+            symbol: &item.symbol_name,
+            instance: &item.instance,
+            source: &item.source,
+            source_crate: &item.source_crate,
+            size: size_of(item),
+        })
+        .collect();
+    item_reports.sort_by(|a, b| {
+        b.size.cmp(&a.size).then_with(|| (a.symbol, a.cgu).cmp(&(b.symbol, b.cgu)))
+    });
+
+    let mut functions: FxHashMap<(&str, &str), (Vec<&str>, usize, u64)> = FxHashMap::default();
+    for item in &item_reports {
+        let (instances, copies, size) =
+            functions.entry((item.source_crate, item.source)).or_default();
+        if !instances.contains(&item.instance) {
+            instances.push(item.instance);
+        }
+        *copies += 1;
+        *size += item.size.unwrap_or(0);
+    }
+    let mut function_reports: Vec<_> = functions
+        .into_iter()
+        .map(|((source_crate, source), (instances, copies, size))| FunctionReport {
+            source,
+            source_crate,
+            instantiations: instances.len(),
+            copies,
+            size,
+        })
+        .collect();
+    function_reports.sort_by(|a, b| {
+        let key = |f: &FunctionReport<'_>| (f.source_crate, f.source);
+        b.size.cmp(&a.size).then_with(|| key(a).cmp(&key(b)))
+    });
+
+    let mut crates: FxHashMap<&str, (usize, usize, u64)> = FxHashMap::default();
+    for function in &function_reports {
+        let (functions, copies, size) = crates.entry(function.source_crate).or_default();
+        *functions += 1;
+        *copies += function.copies;
+        *size += function.size;
+    }
+    let mut crate_reports: Vec<_> = crates
+        .into_iter()
+        .map(|(source_crate, (functions, copies, size))| CrateReport {
+            source_crate,
+            functions,
+            copies,
+            size,
+        })
+        .collect();
+    crate_reports
+        .sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.source_crate.cmp(b.source_crate)));
+
+    let report = Report { items: item_reports, functions: function_reports, crates: crate_reports };
+    fs::write(path, format!("{}\n", as_pretty_json(&report)))
+}
@@ -4,7 +4,8 @@ use rustc_data_structures::fx::FxHashSet;
 use rustc_fs_util::fix_windows_verbatim_for_gcc;
 use rustc_hir::def_id::CrateNum;
 use rustc_session::config::{
-    self, CFGuard, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer,
+    self, CFGuard, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer, SplitDebuginfo,
+    Strip,
 };
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
@@ -108,6 +109,9 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
                         tmpdir.path(),
                         target_cpu,
                     );
+                    if sess.split_debuginfo() == SplitDebuginfo::Packed {
+                        package_split_dwarf(sess, &out_filename);
+                    }
                 }
             }
             if sess.opts.json_artifact_notifications {
@@ -134,6 +138,11 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
             {
                 remove(sess, obj);
             }
+            if sess.split_debuginfo() == SplitDebuginfo::Packed && !outputs_need_split_dwarf(sess) {
+                for obj in codegen_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref()) {
+                    remove(sess, obj);
+                }
+            }
             if let Some(ref metadata_module) = codegen_results.metadata_module {
                 if let Some(ref obj) = metadata_module.object {
                     remove(sess, obj);
@@ -758,6 +767,73 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
     }
 }
 
+/// Whether the `.dwo` files of the crate are still needed once it is linked, because one of its
+/// outputs is linked into other crates, whose `.dwp` files are packaged from them.
+fn outputs_need_split_dwarf(sess: &Session) -> bool {
+    sess.crate_types.borrow().iter().any(|&crate_type| match crate_type {
+        config::CrateType::Rlib | config::CrateType::Staticlib => true,
+        config::CrateType::Executable
+        | config::CrateType::Dylib
+        | config::CrateType::Cdylib
+        | config::CrateType::ProcMacro => false,
+    })
+}
+
+/// Packages the split DWARF of a linked executable or dynamic library into a `.dwp` file next to
+/// it, where debuggers look for it. `llvm-dwp` finds the `.dwo` files of all the crates linked
+/// into the output, upstream crates included, through the skeleton units in the output.
+fn package_split_dwarf(sess: &Session, out_filename: &Path) {
+    let mut dwp_filename = out_filename.as_os_str().to_owned();
+    dwp_filename.push(".dwp");
+
+    // Prefer the `llvm-dwp` bundled with the compiler, like the linker does for `rust-lld`.
+    let mut new_path = sess.host_filesearch(PathKind::All).get_tools_search_paths();
+    if let Some(path) = env::var_os("PATH") {
+        new_path.extend(env::split_paths(&path));
+    }
+    let mut cmd = Command::new("llvm-dwp");
+    cmd.env("PATH", env::join_paths(new_path).unwrap());
+    cmd.arg("-e").arg(out_filename).arg("-o").arg(&dwp_filename);
+    info!("{:?}", &cmd);
+
+    match sess.time("run_llvm_dwp", || cmd.output()) {
+        Ok(prog) if !prog.status.success() => {
+            let mut output = prog.stderr.clone();
+            output.extend_from_slice(&prog.stdout);
+            let msg = format!("packaging split DWARF with `llvm-dwp` failed: {}", prog.status);
+            sess.struct_err(&msg)
+                .note(&format!("{:?}", &cmd))
+                .note(&String::from_utf8_lossy(&output))
+                .emit();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let mut err = if e.kind() == io::ErrorKind::NotFound {
+                sess.struct_err("`llvm-dwp` not found")
+            } else {
+                sess.struct_err("could not exec `llvm-dwp`")
+            };
+            err.note(&e.to_string());
+            err.help("`-C split-debuginfo=unpacked` does not need `llvm-dwp`");
+            err.emit();
+        }
+    }
+}
+
+/// What the linker should strip from the output. Besides `-C strip`, building without debuginfo
+/// under `-Z strip-debuginfo-if-disabled` strips the debuginfo of the upstream crates, like libstd.
+fn strip_value(sess: &Session) -> Strip {
+    match sess.opts.cg.strip {
+        Strip::None
+            if sess.opts.debuginfo == DebugInfo::None
+                && sess.opts.debugging_opts.strip_debuginfo_if_disabled == Some(true) =>
+        {
+            Strip::Debuginfo
+        }
+        strip => strip,
+    }
+}
+
 fn link_sanitizer_runtime(sess: &Session, crate_type: config::CrateType, linker: &mut dyn Linker) {
     let sanitizer = match &sess.opts.debugging_opts.sanitizer {
         Some(s) => s,
@@ -773,6 +849,10 @@ fn link_sanitizer_runtime(sess: &Session, crate_type: config::CrateType, linker:
         Sanitizer::Leak => "lsan",
         Sanitizer::Memory => "msan",
         Sanitizer::Thread => "tsan",
+        Sanitizer::HwAddress => "hwasan",
+        // A failed CFI check traps without help from a runtime, and the platforms that support the
+        // shadow call stack set it up themselves.
+        Sanitizer::Cfi | Sanitizer::ShadowCallStack => return,
     };
 
     let default_sysroot = filesearch::get_or_default_sysroot();
@@ -793,7 +873,10 @@ fn link_sanitizer_runtime(sess: &Session, crate_type: config::CrateType, linker:
             linker.args(&["-Wl,-rpath".into(), "-Xlinker".into(), rpath.into()]);
             linker.link_dylib(Symbol::intern(&libname));
         }
-        "x86_64-unknown-linux-gnu" | "x86_64-fuchsia" | "aarch64-fuchsia" => {
+        "x86_64-unknown-linux-gnu"
+        | "x86_64-fuchsia"
+        | "aarch64-fuchsia"
+        | "aarch64-unknown-linux-gnu" => {
             let filename = format!("librustc{}_rt.{}.a", channel, name);
             let path = default_tlib.join(&filename);
             linker.link_whole_rlib(&path);
@@ -1339,8 +1422,8 @@ fn link_args<'a, B: ArchiveBuilder<'a>>(
     // Pass optimization flags down to the linker.
     cmd.optimize();
 
-    // Pass debuginfo flags down to the linker.
-    cmd.debuginfo();
+    // Pass debuginfo and stripping flags down to the linker.
+    cmd.debuginfo(strip_value(sess));
 
     // We want to, by default, prevent the compiler from accidentally leaking in
     // any system libraries, so we may explicitly ask linkers to not link to any
@@ -1391,7 +1474,7 @@ fn link_args<'a, B: ArchiveBuilder<'a>>(
         cmd.build_static_executable();
     }
 
-    if sess.opts.cg.profile_generate.enabled() {
+    if sess.opts.cg.profile_generate.enabled() || sess.opts.debugging_opts.instrument_coverage {
         cmd.pgo_gen();
     }
 
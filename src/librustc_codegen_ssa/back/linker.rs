@@ -13,7 +13,7 @@ use rustc::ty::TyCtxt;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_serialize::{json, Encoder};
-use rustc_session::config::{self, CrateType, DebugInfo, LinkerPluginLto, Lto, OptLevel};
+use rustc_session::config::{self, CrateType, DebugInfo, LinkerPluginLto, Lto, OptLevel, Strip};
 use rustc_session::Session;
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::{LinkerFlavor, LldFlavor};
@@ -107,7 +107,7 @@ pub trait Linker {
     fn optimize(&mut self);
     fn pgo_gen(&mut self);
     fn control_flow_guard(&mut self);
-    fn debuginfo(&mut self);
+    fn debuginfo(&mut self, strip: Strip);
     fn no_default_libraries(&mut self);
     fn build_dylib(&mut self, out_filename: &Path);
     fn build_static_executable(&mut self);
@@ -365,15 +365,18 @@ impl<'a> Linker for GccLinker<'a> {
         self.sess.warn("Windows Control Flow Guard is not supported by this linker.");
     }
 
-    fn debuginfo(&mut self) {
-        if let DebugInfo::None = self.sess.opts.debuginfo {
-            // If we are building without debuginfo enabled and we were called with
-            // `-Zstrip-debuginfo-if-disabled=yes`, tell the linker to strip any debuginfo
-            // found when linking to get rid of symbols from libstd.
-            if let Some(true) = self.sess.opts.debugging_opts.strip_debuginfo_if_disabled {
+    fn debuginfo(&mut self, strip: Strip) {
+        // The shorthands are used because the macOS linker does not support
+        // `--strip-debug` and `--strip-all`.
+        match strip {
+            Strip::None => {}
+            Strip::Debuginfo => {
                 self.linker_arg("-S");
             }
-        };
+            Strip::Symbols => {
+                self.linker_arg("-s");
+            }
+        }
     }
 
     fn no_default_libraries(&mut self) {
@@ -669,7 +672,14 @@ impl<'a> Linker for MsvcLinker<'a> {
         self.cmd.arg("/guard:cf");
     }
 
-    fn debuginfo(&mut self) {
+    fn debuginfo(&mut self, strip: Strip) {
+        // The symbols of the output only ever end up in the PDB file, so
+        // stripping them means not generating one.
+        if strip != Strip::None {
+            self.cmd.arg("/DEBUG:NONE");
+            return;
+        }
+
         // This will cause the Microsoft linker to generate a PDB file
         // from the CodeView line tables in the object files.
         self.cmd.arg("/DEBUG");
@@ -875,9 +885,12 @@ impl<'a> Linker for EmLinker<'a> {
         self.sess.warn("Windows Control Flow Guard is not supported by this linker.");
     }
 
-    fn debuginfo(&mut self) {
-        // Preserve names or generate source maps depending on debug info
-        self.cmd.arg(match self.sess.opts.debuginfo {
+    fn debuginfo(&mut self, strip: Strip) {
+        // Preserve names or generate source maps depending on debug info,
+        // unless they are stripped anyway.
+        let debuginfo =
+            if strip == Strip::None { self.sess.opts.debuginfo } else { DebugInfo::None };
+        self.cmd.arg(match debuginfo {
             DebugInfo::None => "-g0",
             DebugInfo::Limited => "-g3",
             DebugInfo::Full => "-g4",
@@ -1069,7 +1082,17 @@ impl<'a> Linker for WasmLd<'a> {
 
     fn pgo_gen(&mut self) {}
 
-    fn debuginfo(&mut self) {}
+    fn debuginfo(&mut self, strip: Strip) {
+        match strip {
+            Strip::None => {}
+            Strip::Debuginfo => {
+                self.cmd.arg("--strip-debug");
+            }
+            Strip::Symbols => {
+                self.cmd.arg("--strip-all");
+            }
+        }
+    }
 
     fn control_flow_guard(&mut self) {
         self.sess.warn("Windows Control Flow Guard is not supported by this linker.");
@@ -1174,7 +1197,7 @@ impl<'a> Linker for PtxLinker<'a> {
         self.cmd.arg("-L").arg(path);
     }
 
-    fn debuginfo(&mut self) {
+    fn debuginfo(&mut self, _strip: Strip) {
         self.cmd.arg("--debug");
     }
 
@@ -1,4 +1,5 @@
 pub mod archive;
+pub mod code_size;
 pub mod command;
 pub mod link;
 pub mod linker;
@@ -1,3 +1,4 @@
+use super::code_size::CodeSizeItem;
 use super::command::Command;
 use super::link::{self, get_linker, remove};
 use super::linker::LinkerInfo;
@@ -31,7 +32,7 @@ use rustc_incremental::{
 };
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{
-    self, Lto, OutputFilenames, OutputType, Passes, Sanitizer, SwitchWithOptPath,
+    self, Lto, OutputFilenames, OutputType, Passes, Sanitizer, SplitDebuginfo, SwitchWithOptPath,
 };
 use rustc_session::Session;
 use rustc_span::hygiene::ExpnId;
@@ -72,6 +73,7 @@ pub struct ModuleConfig {
 
     pub pgo_gen: SwitchWithOptPath,
     pub pgo_use: Option<PathBuf>,
+    pub pgo_sample_use: Option<PathBuf>,
 
     pub sanitizer: Option<Sanitizer>,
     pub sanitizer_recover: Vec<Sanitizer>,
@@ -85,6 +87,8 @@ pub struct ModuleConfig {
     pub emit_ir: bool,
     pub emit_asm: bool,
     pub emit_obj: bool,
+    // Write the DWARF of the object file to a separate `.dwo` file.
+    pub emit_dwarf_object: bool,
     // Miscellaneous flags.  These are mostly copied from command-line
     // options.
     pub verify_llvm_ir: bool,
@@ -113,6 +117,7 @@ impl ModuleConfig {
 
             pgo_gen: SwitchWithOptPath::Disabled,
             pgo_use: None,
+            pgo_sample_use: None,
 
             sanitizer: None,
             sanitizer_recover: Default::default(),
@@ -125,6 +130,7 @@ impl ModuleConfig {
             emit_ir: false,
             emit_asm: false,
             emit_obj: false,
+            emit_dwarf_object: false,
             obj_is_bitcode: false,
             embed_bitcode: EmbedBitcode::None,
             no_integrated_as: false,
@@ -371,8 +377,14 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         modules_config.passes.push("insert-gcov-profiling".to_owned())
     }
 
+    if sess.opts.debugging_opts.instrument_coverage {
+        // Lowers the `llvm.instrprof.increment` calls emitted for coverage counters.
+        modules_config.passes.push("instrprof".to_owned())
+    }
+
     modules_config.pgo_gen = sess.opts.cg.profile_generate.clone();
     modules_config.pgo_use = sess.opts.cg.profile_use.clone();
+    modules_config.pgo_sample_use = sess.opts.cg.profile_sample_use.clone();
     modules_config.sanitizer = sess.opts.debugging_opts.sanitizer.clone();
     modules_config.sanitizer_recover = sess.opts.debugging_opts.sanitizer_recover.clone();
     modules_config.sanitizer_memory_track_origins =
@@ -440,6 +452,13 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
     metadata_config.set_flags(sess, no_builtins);
     allocator_config.set_flags(sess, no_builtins);
 
+    // Only the regular modules have debuginfo to split out, and only LLVM's own object writer can
+    // split it.
+    modules_config.emit_dwarf_object = modules_config.emit_obj
+        && !modules_config.obj_is_bitcode
+        && !modules_config.no_integrated_as
+        && sess.split_debuginfo() != SplitDebuginfo::Off;
+
     // Exclude metadata and allocator modules from time_passes output, since
     // they throw off the "LLVM passes" measurement.
     metadata_config.time_module = false;
@@ -471,6 +490,7 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         windows_subsystem,
         linker_info,
         crate_info,
+        code_size_items: Vec::new(),
 
         coordinator_send,
         codegen_worker_receive,
@@ -504,6 +524,9 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
         if let Some(ref path) = module.bytecode_compressed {
             files.push((WorkProductFileKind::BytecodeCompressed, path.clone()));
         }
+        if let Some(ref path) = module.dwarf_object {
+            files.push((WorkProductFileKind::DwarfObject, path.clone()));
+        }
 
         if let Some((id, product)) =
             copy_cgu_workproducts_to_incr_comp_cache_dir(sess, &module.name, &files)
@@ -841,6 +864,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
     let mut object = None;
     let mut bytecode = None;
     let mut bytecode_compressed = None;
+    let mut dwarf_object = None;
     for (kind, saved_file) in &module.source.saved_files {
         let obj_out = match kind {
             WorkProductFileKind::Object => {
@@ -861,6 +885,11 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
                 bytecode_compressed = Some(path.clone());
                 path
             }
+            WorkProductFileKind::DwarfObject => {
+                let path = cgcx.output_filenames.split_dwarf_path(Some(&module.name));
+                dwarf_object = Some(path.clone());
+                path
+            }
         };
         let source_file = in_incr_comp_dir(&incr_comp_session_dir, &saved_file);
         debug!(
@@ -883,6 +912,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
     assert_eq!(object.is_some(), module_config.emit_obj);
     assert_eq!(bytecode.is_some(), module_config.emit_bc);
     assert_eq!(bytecode_compressed.is_some(), module_config.emit_bc_compressed);
+    assert_eq!(dwarf_object.is_some(), module_config.emit_dwarf_object);
 
     Ok(WorkItemResult::Compiled(CompiledModule {
         name: module.name,
@@ -890,6 +920,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         object,
         bytecode,
         bytecode_compressed,
+        dwarf_object,
     }))
 }
 
@@ -1734,6 +1765,7 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub windows_subsystem: Option<String>,
     pub linker_info: LinkerInfo,
     pub crate_info: CrateInfo,
+    pub code_size_items: Vec<CodeSizeItem>,
     pub coordinator_send: Sender<Box<dyn Any + Send>>,
     pub codegen_worker_receive: Receiver<Message<B>>,
     pub shared_emitter_main: SharedEmitterMain,
@@ -1780,6 +1812,7 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
                 windows_subsystem: self.windows_subsystem,
                 linker_info: self.linker_info,
                 crate_info: self.crate_info,
+                code_size_items: self.code_size_items,
 
                 modules: compiled_modules.modules,
                 allocator_module: compiled_modules.allocator_module,
@@ -13,6 +13,7 @@
 //!   but one `llvm::Type` corresponds to many `Ty`s; for instance, `tup(int, int,
 //!   int)` and `rec(x=int, y=int, z=int)` will have the same `llvm::Type`.
 
+use crate::back::code_size::collect_code_size_items;
 use crate::back::write::{
     start_async_codegen, submit_codegened_module_to_llvm, submit_post_lto_module_to_llvm,
     submit_pre_lto_module_to_llvm, OngoingCodegen,
@@ -545,7 +546,11 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         }
     }
 
-    let ongoing_codegen = start_async_codegen(backend.clone(), tcx, metadata, codegen_units.len());
+    let mut ongoing_codegen =
+        start_async_codegen(backend.clone(), tcx, metadata, codegen_units.len());
+    if tcx.sess.opts.debugging_opts.code_size_report.is_some() {
+        ongoing_codegen.code_size_items = collect_code_size_items(tcx, &codegen_units);
+    }
     let ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
 
     // Codegen an allocator shim, if necessary.
@@ -4,6 +4,11 @@ use rustc::ty::{Ty, TyCtxt};
 use rustc_errors::struct_span_err;
 use rustc_session::Session;
 use rustc_span::Span;
+use rustc_target::abi::call::{FnAbi, PassMode};
+use rustc_target::abi::{Abi, Primitive, Scalar};
+
+use std::fmt::Write;
+use std::iter;
 
 use crate::base;
 use crate::traits::*;
@@ -197,3 +202,49 @@ pub fn shift_mask_val<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
 pub fn span_invalid_monomorphization_error(a: &Session, b: Span, c: &str) {
     struct_span_err!(a, b, E0511, "{}", c).emit();
 }
+
+/// The type identifier that `-Z sanitizer=cfi` checks the targets of indirect calls against.
+///
+/// Functions are told apart by how their arguments and return value are passed rather than by
+/// their Rust types, as a virtual call passes the receiver as a pointer to `dyn Trait` where the
+/// method it calls takes a pointer to the concrete type.
+pub fn typeid_for_fn_abi(fn_abi: &FnAbi<'_, Ty<'_>>) -> String {
+    fn encode_scalar(typeid: &mut String, scalar: &Scalar) {
+        match scalar.value {
+            Primitive::Int(int, signed) => {
+                let sign = if signed { 'i' } else { 'u' };
+                write!(typeid, "{}{}", sign, int.size().bits()).unwrap();
+            }
+            Primitive::F32 => typeid.push_str("f32"),
+            Primitive::F64 => typeid.push_str("f64"),
+            Primitive::Pointer => typeid.push('p'),
+        }
+    }
+
+    let mut typeid = format!("typeid.{:?}", fn_abi.conv);
+    if fn_abi.c_variadic {
+        typeid.push_str(".variadic");
+    }
+    for arg in iter::once(&fn_abi.ret).chain(fn_abi.args.iter()) {
+        typeid.push('.');
+        match (&arg.mode, &arg.layout.abi) {
+            (PassMode::Ignore, _) => typeid.push('z'),
+            (PassMode::Indirect(_, None), _) => typeid.push('r'),
+            (PassMode::Indirect(_, Some(_)), _) => typeid.push_str("rr"),
+            (PassMode::Cast(_), _) => write!(typeid, "c{}", arg.layout.size.bytes()).unwrap(),
+            (_, Abi::Scalar(scalar)) => encode_scalar(&mut typeid, scalar),
+            (_, Abi::ScalarPair(a, b)) => {
+                encode_scalar(&mut typeid, a);
+                encode_scalar(&mut typeid, b);
+            }
+            (_, Abi::Vector { element, count }) => {
+                write!(typeid, "v{}", count).unwrap();
+                encode_scalar(&mut typeid, element);
+            }
+            (_, Abi::Uninhabited) | (_, Abi::Aggregate { .. }) => {
+                write!(typeid, "a{}", arg.layout.size.bytes()).unwrap()
+            }
+        }
+    }
+    typeid
+}
@@ -62,6 +62,7 @@ impl<M> ModuleCodegen<M> {
         emit_obj: bool,
         emit_bc: bool,
         emit_bc_compressed: bool,
+        emit_dwarf_object: bool,
         outputs: &OutputFilenames,
     ) -> CompiledModule {
         let object = emit_obj.then(|| outputs.temp_path(OutputType::Object, Some(&self.name)));
@@ -71,6 +72,7 @@ impl<M> ModuleCodegen<M> {
                 .temp_path(OutputType::Bitcode, Some(&self.name))
                 .with_extension(RLIB_BYTECODE_EXTENSION)
         });
+        let dwarf_object = emit_dwarf_object.then(|| outputs.split_dwarf_path(Some(&self.name)));
 
         CompiledModule {
             name: self.name.clone(),
@@ -78,6 +80,7 @@ impl<M> ModuleCodegen<M> {
             object,
             bytecode,
             bytecode_compressed,
+            dwarf_object,
         }
     }
 }
@@ -89,6 +92,8 @@ pub struct CompiledModule {
     pub object: Option<PathBuf>,
     pub bytecode: Option<PathBuf>,
     pub bytecode_compressed: Option<PathBuf>,
+    /// The split DWARF of the module, for `-C split-debuginfo`.
+    pub dwarf_object: Option<PathBuf>,
 }
 
 pub struct CachedModuleCodegen {
@@ -148,6 +153,8 @@ pub struct CodegenResults {
     pub windows_subsystem: Option<String>,
     pub linker_info: back::linker::LinkerInfo,
     pub crate_info: CrateInfo,
+    /// The functions to attribute the code size to, for `-Z code-size-report`.
+    pub code_size_items: Vec<back::code_size::CodeSizeItem>,
 }
 
 pub fn provide(providers: &mut Providers<'_>) {
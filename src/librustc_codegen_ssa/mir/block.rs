@@ -9,12 +9,14 @@ use crate::meth;
 use crate::traits::*;
 use crate::MemFlags;
 
+use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc::middle::lang_items;
 use rustc::mir;
 use rustc::mir::AssertKind;
 use rustc::ty::layout::{self, FnAbiExt, HasTyCtxt, LayoutOf};
 use rustc::ty::{self, Instance, Ty, TypeFoldable};
 use rustc_index::vec::Idx;
+use rustc_session::config::Sanitizer;
 use rustc_span::{source_map::Span, symbol::Symbol};
 use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
 use rustc_target::spec::abi::Abi;
@@ -145,6 +147,36 @@ impl<'a, 'tcx> TerminatorCodegenHelper<'tcx> {
         }
     }
 
+    /// Under `-Z sanitizer=cfi`, check that the target `fn_ptr` of a call
+    /// through a function pointer is a function of `fn_abi`, and abort the
+    /// program if it is not.
+    fn check_indirect_call<Bx: BuilderMethods<'a, 'tcx>>(
+        &self,
+        fx: &mut FunctionCx<'a, 'tcx, Bx>,
+        bx: &mut Bx,
+        fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+        fn_ptr: Bx::Value,
+    ) {
+        let tcx = bx.tcx();
+        if tcx.sess.opts.debugging_opts.sanitizer != Some(Sanitizer::Cfi) {
+            return;
+        }
+        let flags = tcx.codegen_fn_attrs(fx.instance.def_id()).flags;
+        if flags.contains(CodegenFnAttrFlags::NO_SANITIZE_CFI) {
+            return;
+        }
+
+        let typeid = common::typeid_for_fn_abi(fn_abi);
+        let is_valid = bx.type_test(fn_ptr, &typeid);
+        let mut valid_bx = bx.build_sibling_block("cfi_valid");
+        let mut invalid_bx = bx.build_sibling_block("cfi_invalid");
+        bx.cond_br(is_valid, valid_bx.llbb(), invalid_bx.llbb());
+        invalid_bx.abort();
+        invalid_bx.unreachable();
+        fx.set_debug_loc(&mut valid_bx, self.terminator.source_info);
+        *bx = valid_bx;
+    }
+
     // Generate sideeffect intrinsic if jumping to any of the targets can form
     // a loop.
     fn maybe_sideeffect<Bx: BuilderMethods<'a, 'tcx>>(
@@ -686,6 +718,7 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 &args,
                 dest,
                 terminator.source_info.span,
+                self.instance,
             );
 
             if let ReturnDest::IndirectOperand(dst, _) = ret_dest {
@@ -798,7 +831,13 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         }
 
         let fn_ptr = match (llfn, instance) {
-            (Some(llfn), _) => llfn,
+            (Some(llfn), None) => {
+                helper.check_indirect_call(self, &mut bx, &fn_abi, llfn);
+                llfn
+            }
+            // Calls through vtables are not checked, as the vtable may have
+            // been created by a crate compiled without the sanitizer.
+            (Some(llfn), Some(_)) => llfn,
             (None, Some(instance)) => bx.get_fn_addr(instance),
             _ => span_bug!(span, "no llfn for call"),
         };
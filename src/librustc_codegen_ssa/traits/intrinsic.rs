@@ -15,12 +15,16 @@ pub trait IntrinsicCallMethods<'tcx>: BackendTypes {
         args: &[OperandRef<'tcx, Self::Value>],
         llresult: Self::Value,
         span: Span,
+        caller_instance: ty::Instance<'tcx>,
     );
 
     fn abort(&mut self);
     fn assume(&mut self, val: Self::Value);
     fn expect(&mut self, cond: Self::Value, expected: bool) -> Self::Value;
     fn sideeffect(&mut self);
+    /// Tests whether `pointer` points to a function with the type identifier `typeid`, as
+    /// `-Z sanitizer=cfi` does before an indirect call.
+    fn type_test(&mut self, pointer: Self::Value, typeid: &str) -> Self::Value;
     /// Trait method used to inject `va_start` on the "spoofed" `VaListImpl` in
     /// Rust defined C-variadic functions.
     fn va_start(&mut self, val: Self::Value) -> Self::Value;
@@ -402,6 +402,16 @@ pub fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if let Some(ref path) = sess.opts.debugging_opts.type_layout_report {
+                if let Err(err) = sess.code_stats.write_type_layout_report(path) {
+                    sess.err(&format!(
+                        "failed to write type layout report to `{}`: {}",
+                        path.display(),
+                        err
+                    ));
+                }
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
@@ -851,9 +861,9 @@ Available lint options:
         lints
     }
 
-    fn sort_lint_groups(
-        lints: Vec<(&'static str, Vec<LintId>, bool)>,
-    ) -> Vec<(&'static str, Vec<LintId>)> {
+    fn sort_lint_groups<'a>(
+        lints: Vec<(&'a str, Vec<LintId>, bool)>,
+    ) -> Vec<(&'a str, Vec<LintId>)> {
         let mut lints: Vec<_> = lints.into_iter().map(|(x, y, _)| (x, y)).collect();
         lints.sort_by_key(|l| l.0);
         lints
@@ -912,7 +922,7 @@ Available lint options:
     println!("    {}  {}", padded("----"), "---------");
     println!("    {}  {}", padded("warnings"), "all lints that are set to issue warnings");
 
-    let print_lint_groups = |lints: Vec<(&'static str, Vec<LintId>)>| {
+    let print_lint_groups = |lints: Vec<(&str, Vec<LintId>)>| {
         for (name, to) in lints {
             let name = name.to_lowercase().replace("_", "-");
             let desc = to
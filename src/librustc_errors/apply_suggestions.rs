@@ -0,0 +1,272 @@
+//! Applies the suggestions of diagnostics to the source files, for
+//! `-Z apply-suggestions`.
+//!
+//! The `SuggestionApplier` wraps the emitter of the session: diagnostics are
+//! reported as usual, and the suggestions of those whose code, a lint name or
+//! an error code, was selected are collected. Only suggestions that are
+//! `MachineApplicable` and have a single substitution are applied, since when
+//! there are several substitutions, choosing one of them is up to the user.
+//!
+//! Suggestions are applied in the order their diagnostics were emitted. A
+//! suggestion that overlaps a suggestion applied before it is skipped as a
+//! whole, rather than applying only some of its parts, and running the
+//! compiler again applies it if it still makes sense. The same suggestion made
+//! twice, as happens for code that is expanded several times, is applied once.
+//!
+//! The files are rewritten when the emitter is dropped, together with the
+//! handler that owns it, so that each file is read and written once. A file
+//! edited since it was compiled is left alone, with a warning.
+
+use crate::emitter::Emitter;
+use crate::{
+    pluralize, Applicability, CodeSuggestion, Diagnostic, DiagnosticId, DiagnosticSummaryEntry,
+    Level,
+};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::{self, Lrc};
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Pos};
+
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::str;
+
+#[cfg(test)]
+mod tests;
+
+/// A part of a suggestion, with its range in the file as it is on disk,
+/// before line endings are normalized.
+#[derive(Clone, Debug, PartialEq)]
+struct Replacement {
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl Replacement {
+    fn overlaps(&self, other: &Replacement) -> bool {
+        // Two insertions at the same position overlap, as the order they are
+        // to be inserted in is unknown.
+        self.range.start < other.range.end && other.range.start < self.range.end
+            || self.range.start == other.range.start
+    }
+}
+
+pub struct SuggestionApplier {
+    emitter: Box<dyn Emitter + sync::Send>,
+    sm: Lrc<SourceMap>,
+    /// The lint names and error codes to apply the suggestions of, which
+    /// apply to every diagnostic if they contain `all`.
+    codes: Vec<String>,
+    /// The directory to write patched copies of the files to, rather than
+    /// rewriting the files.
+    output_dir: Option<PathBuf>,
+    /// The suggestions for each file, in the order they were made.
+    suggestions: FxIndexMap<PathBuf, Vec<Vec<Replacement>>>,
+}
+
+impl SuggestionApplier {
+    pub fn new(
+        emitter: Box<dyn Emitter + sync::Send>,
+        source_map: Lrc<SourceMap>,
+        codes: Vec<String>,
+        output_dir: Option<PathBuf>,
+    ) -> SuggestionApplier {
+        SuggestionApplier {
+            emitter,
+            sm: source_map,
+            codes,
+            output_dir,
+            suggestions: Default::default(),
+        }
+    }
+
+    fn is_selected(&self, code: &Option<DiagnosticId>) -> bool {
+        self.codes.iter().any(|selected| match code {
+            _ if selected == "all" => true,
+            Some(DiagnosticId::Error(code)) | Some(DiagnosticId::Lint(code)) => selected == code,
+            None => false,
+        })
+    }
+
+    /// Returns the file that `suggestion` applies to and its replacements,
+    /// if it can be applied.
+    fn replacements(&self, suggestion: &CodeSuggestion) -> Option<(PathBuf, Vec<Replacement>)> {
+        if suggestion.applicability != Applicability::MachineApplicable {
+            return None;
+        }
+        let substitution = match &suggestion.substitutions[..] {
+            [substitution] => substitution,
+            _ => return None,
+        };
+
+        let mut path = None;
+        let mut replacements = Vec::new();
+        for part in &substitution.parts {
+            if part.span.is_dummy() {
+                return None;
+            }
+            let file = self.sm.lookup_byte_offset(part.span.lo()).sf;
+            // Only rewrite the files of the crate being compiled, and only
+            // apply suggestions that stay within one file.
+            let part_path = match &file.name {
+                FileName::Real(part_path) if !file.is_imported() => part_path,
+                _ => return None,
+            };
+            if !file.contains(part.span.hi()) || *path.get_or_insert(part_path) != part_path {
+                return None;
+            }
+            // The original positions account for the byte order mark and the
+            // `\r\n` line endings removed from the source.
+            let start = file.original_relative_byte_pos(part.span.lo()).to_usize();
+            let end = file.original_relative_byte_pos(part.span.hi()).to_usize();
+            replacements.push(Replacement { range: start..end, snippet: part.snippet.clone() });
+        }
+        path.map(|path| (path.clone(), replacements))
+    }
+
+    /// Applies the suggestions for `path`, returning the number of
+    /// suggestions that were applied and the number that were skipped.
+    fn apply(&self, path: &Path, suggestions: Vec<Vec<Replacement>>) -> io::Result<(usize, usize)> {
+        let changed = || io::Error::new(io::ErrorKind::InvalidData, "the file has changed");
+        // The suggestions are positions in the source that was compiled, so
+        // a file edited since then is left alone.
+        let source = fs::read(path)?;
+        let file = self.sm.get_source_file(&FileName::Real(path.to_owned()));
+        match (file, str::from_utf8(&source)) {
+            (Some(file), Ok(src)) if file.is_source(src) => {}
+            _ => return Err(changed()),
+        }
+        let (patched, applied, skipped) =
+            apply_replacements(&source, suggestions).ok_or_else(changed)?;
+        if applied == 0 {
+            return Ok((applied, skipped));
+        }
+
+        let output = match &self.output_dir {
+            Some(dir) => {
+                // Mirror the path of the file in the directory, leaving out the
+                // root of absolute paths and the `..` components, which would
+                // lead out of the directory and overwrite the sources.
+                let relative: PathBuf = path
+                    .components()
+                    .filter(|component| match component {
+                        Component::Normal(_) => true,
+                        _ => false,
+                    })
+                    .collect();
+                let output = dir.join(relative);
+                if let Some(parent) = output.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                output
+            }
+            None => path.to_owned(),
+        };
+        fs::write(output, patched)?;
+        Ok((applied, skipped))
+    }
+}
+
+/// Applies `suggestions` to `source`, skipping the duplicates and the
+/// suggestions that overlap the ones before them. Returns the patched source,
+/// the number of suggestions that were applied and the number of suggestions
+/// that were skipped, or `None` if a suggestion is out of the bounds of the
+/// source.
+fn apply_replacements(
+    source: &[u8],
+    suggestions: Vec<Vec<Replacement>>,
+) -> Option<(Vec<u8>, usize, usize)> {
+    let mut accepted: Vec<Replacement> = Vec::new();
+    let (mut applied, mut skipped) = (0, 0);
+    for replacements in suggestions {
+        if replacements.iter().all(|replacement| accepted.contains(replacement)) {
+            continue;
+        }
+        if replacements.iter().any(|replacement| accepted.iter().any(|a| a.overlaps(replacement))) {
+            skipped += 1;
+            continue;
+        }
+        accepted.extend(replacements);
+        applied += 1;
+    }
+
+    accepted.sort_by_key(|replacement| replacement.range.start);
+    let mut patched = Vec::with_capacity(source.len());
+    let mut pos = 0;
+    for replacement in accepted {
+        patched.extend_from_slice(source.get(pos..replacement.range.start)?);
+        patched.extend_from_slice(replacement.snippet.as_bytes());
+        pos = replacement.range.end;
+    }
+    patched.extend_from_slice(source.get(pos..)?);
+    Some((patched, applied, skipped))
+}
+
+impl Emitter for SuggestionApplier {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        self.emitter.emit_diagnostic(diag);
+
+        if !self.is_selected(&diag.code) {
+            return;
+        }
+        for suggestion in &diag.suggestions {
+            if let Some((path, replacements)) = self.replacements(suggestion) {
+                self.suggestions.entry(path).or_default().push(replacements);
+            }
+        }
+    }
+
+    fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
+        self.emitter.emit_artifact_notification(path, artifact_type);
+    }
+
+    fn emit_diagnostic_summary(&mut self, entries: &[DiagnosticSummaryEntry]) {
+        self.emitter.emit_diagnostic_summary(entries);
+    }
+
+    fn should_show_explain(&self) -> bool {
+        self.emitter.should_show_explain()
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+}
+
+impl Drop for SuggestionApplier {
+    fn drop(&mut self) {
+        for (path, suggestions) in std::mem::take(&mut self.suggestions) {
+            let diag = match self.apply(&path, suggestions) {
+                Ok((0, _)) => continue,
+                Ok((applied, skipped)) => {
+                    let mut diag = Diagnostic::new(
+                        Level::Note,
+                        &format!(
+                            "applied {} suggestion{} to `{}`",
+                            applied,
+                            pluralize!(applied),
+                            path.display()
+                        ),
+                    );
+                    if skipped > 0 {
+                        diag.note(&format!(
+                            "skipped {} suggestion{} overlapping those applied; \
+                             compile again to apply them",
+                            skipped,
+                            pluralize!(skipped)
+                        ));
+                    }
+                    diag
+                }
+                Err(e) => Diagnostic::new(
+                    Level::Warning,
+                    &format!("failed to apply suggestions to `{}`: {}", path.display(), e),
+                ),
+            };
+            self.emitter.emit_diagnostic(&diag);
+        }
+    }
+}
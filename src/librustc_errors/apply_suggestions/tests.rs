@@ -0,0 +1,80 @@
+use super::*;
+
+use crate::emitter::EmitterWriter;
+use rustc_span::source_map::FilePathMapping;
+
+fn replacement(range: Range<usize>, snippet: &str) -> Replacement {
+    Replacement { range, snippet: snippet.to_owned() }
+}
+
+fn apply(source: &str, suggestions: Vec<Vec<Replacement>>) -> (String, usize, usize) {
+    let (patched, applied, skipped) = apply_replacements(source.as_bytes(), suggestions).unwrap();
+    (String::from_utf8(patched).unwrap(), applied, skipped)
+}
+
+#[test]
+fn replacements() {
+    let source = "let mut x = (1);";
+    let suggestions =
+        vec![vec![replacement(12..13, ""), replacement(14..15, "")], vec![replacement(4..8, "")]];
+    assert_eq!(apply(source, suggestions), ("let x = 1;".to_owned(), 2, 0));
+}
+
+#[test]
+fn insertions() {
+    let source = "fn foo() {}";
+    let suggestions = vec![vec![replacement(0..0, "pub ")], vec![replacement(3..6, "bar")]];
+    assert_eq!(apply(source, suggestions), ("pub fn bar() {}".to_owned(), 2, 0));
+}
+
+#[test]
+fn duplicates() {
+    let source = "let mut x = 1;";
+    let suggestions = vec![vec![replacement(4..8, "")], vec![replacement(4..8, "")]];
+    assert_eq!(apply(source, suggestions), ("let x = 1;".to_owned(), 1, 0));
+}
+
+#[test]
+fn overlapping() {
+    let source = "let mut x = 1;";
+    let suggestions = vec![
+        vec![replacement(4..8, "")],
+        // Skipped as a whole, although its second part does not overlap
+        vec![replacement(6..9, "y"), replacement(12..13, "2")],
+        // Inserting at the same position as another suggestion
+        vec![replacement(4..4, "ref ")],
+        vec![replacement(8..9, "y")],
+    ];
+    assert_eq!(apply(source, suggestions), ("let y = 1;".to_owned(), 2, 2));
+}
+
+#[test]
+fn out_of_bounds() {
+    let suggestions = vec![vec![replacement(4..20, "")]];
+    assert!(apply_replacements(b"let x = 1;", suggestions).is_none());
+}
+
+#[test]
+fn changed_file() {
+    let dir = std::env::temp_dir().join(format!("apply-suggestions-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("lib.rs");
+    fs::write(&path, "let mut x = 1;\r\n").unwrap();
+
+    let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+    sm.new_source_file(path.clone().into(), "let mut x = 1;\r\n".to_owned());
+    let emitter = EmitterWriter::new(Box::new(io::sink()), None, false, false, false, None, false);
+    let applier = SuggestionApplier::new(Box::new(emitter), sm, vec!["all".to_owned()], None);
+    let suggestions = || vec![vec![replacement(4..8, "")]];
+
+    // The file was edited after it was compiled.
+    fs::write(&path, "let mut y = 1;\r\n").unwrap();
+    assert!(applier.apply(&path, suggestions()).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "let mut y = 1;\r\n");
+
+    fs::write(&path, "let mut x = 1;\r\n").unwrap();
+    assert_eq!(applier.apply(&path, suggestions()).unwrap(), (1, 0));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "let x = 1;\r\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -10,12 +10,13 @@
 use Destination::*;
 
 use rustc_span::source_map::SourceMap;
-use rustc_span::{MultiSpan, SourceFile, Span};
+use rustc_span::{FileName, MultiSpan, SourceFile, Span};
 
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
 use crate::{
-    pluralize, CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic, SuggestionStyle,
+    pluralize, CodeSuggestion, Diagnostic, DiagnosticId, DiagnosticSummaryEntry, Level,
+    SubDiagnostic, SuggestionStyle,
 };
 
 use log::*;
@@ -24,6 +25,7 @@ use rustc_data_structures::sync::Lrc;
 use rustc_span::hygiene::{ExpnKind, MacroKind};
 use std::borrow::Cow;
 use std::cmp::{max, min, Reverse};
+use std::env;
 use std::io;
 use std::io::prelude::*;
 use std::iter;
@@ -31,6 +33,9 @@ use std::path::Path;
 use termcolor::{Ansi, BufferWriter, ColorChoice, ColorSpec, StandardStream};
 use termcolor::{Buffer, Color, WriteColor};
 
+#[cfg(test)]
+mod tests;
+
 /// Describes the way the content of the `rendered` field of the json output is generated
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HumanReadableErrorType {
@@ -188,6 +193,54 @@ pub trait Emitter {
     /// other formats can, and will, simply ignore it.
     fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
 
+    /// Emits the number of errors and warnings of each error code and lint,
+    /// with the location of the first of them, for `-Z diagnostic-summary`.
+    fn emit_diagnostic_summary(&mut self, entries: &[DiagnosticSummaryEntry]) {
+        let errors: usize =
+            entries.iter().filter(|entry| entry.level == Level::Error).map(|e| e.count).sum();
+        let warnings: usize =
+            entries.iter().filter(|entry| entry.level == Level::Warning).map(|e| e.count).sum();
+        let mut lines = vec![format!(
+            "{} error{} and {} warning{} were reported",
+            errors,
+            pluralize!(errors),
+            warnings,
+            pluralize!(warnings)
+        )];
+
+        let labels: Vec<_> = entries
+            .iter()
+            .map(|entry| match &entry.code {
+                Some(DiagnosticId::Error(code)) | Some(DiagnosticId::Lint(code)) => {
+                    format!("{}[{}]", entry.level, code)
+                }
+                None => entry.level.to_string(),
+            })
+            .collect();
+        let count_width = entries.iter().map(|e| e.count.to_string().len()).max().unwrap_or(0);
+        let label_width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        for (entry, label) in entries.iter().zip(labels) {
+            let location = match (entry.first_span, self.source_map()) {
+                (Some(span), Some(sm)) if !span.is_dummy() => {
+                    let loc = sm.lookup_char_pos(span.lo());
+                    format!("first at {}:{}:{}", loc.file.name, loc.line, loc.col.0 + 1)
+                }
+                _ => String::new(),
+            };
+            let line = format!(
+                "    {:>count_width$} {:label_width$} {}",
+                entry.count,
+                label,
+                location,
+                count_width = count_width,
+                label_width = label_width
+            );
+            lines.push(line.trim_end().to_string());
+        }
+
+        self.emit_diagnostic(&Diagnostic::new(Level::Note, &lines.join("\n")));
+    }
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
@@ -551,6 +604,48 @@ impl ColorConfig {
     }
 }
 
+/// Whether stderr is a terminal that is known to support OSC 8 hyperlinks.
+/// Other terminals may print the escape sequences, and a terminal cannot be
+/// asked whether it supports them, so they are recognized by the environment
+/// variables they set.
+pub fn terminal_supports_hyperlinks() -> bool {
+    if !atty::is(atty::Stream::Stderr) {
+        return false;
+    }
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    if let Ok(version) = var("VTE_VERSION").parse::<u32>() {
+        // GNOME Terminal and the other terminals built on VTE 0.50 or later
+        return version >= 5000;
+    }
+    match &var("TERM_PROGRAM")[..] {
+        "iTerm.app" | "WezTerm" | "vscode" => return true,
+        _ => {}
+    }
+    var("TERM") == "xterm-kitty"
+        || ["WT_SESSION", "KONSOLE_VERSION", "DOMTERM"]
+            .iter()
+            .any(|&name| env::var_os(name).is_some())
+}
+
+/// Percent-encodes `path` as the path of a URL, with `/` as the separator.
+fn encode_url_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut encoded = String::new();
+    // The path of a URL starts with `/`, even for Windows paths like `C:/`
+    if !path.starts_with('/') {
+        encoded.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
 /// Handles the writing of `HumanReadableErrorType::Default` and `HumanReadableErrorType::Short`
 pub struct EmitterWriter {
     dst: Destination,
@@ -561,6 +656,9 @@ pub struct EmitterWriter {
     terminal_width: Option<usize>,
 
     macro_backtrace: bool,
+    /// The URL of the hyperlinks to locations, if locations and error codes
+    /// are hyperlinks.
+    terminal_url_template: Option<String>,
 }
 
 #[derive(Debug)]
@@ -588,6 +686,7 @@ impl EmitterWriter {
             ui_testing: false,
             terminal_width,
             macro_backtrace,
+            terminal_url_template: None,
         }
     }
 
@@ -608,6 +707,7 @@ impl EmitterWriter {
             ui_testing: false,
             terminal_width,
             macro_backtrace,
+            terminal_url_template: None,
         }
     }
 
@@ -616,6 +716,44 @@ impl EmitterWriter {
         self
     }
 
+    /// Makes the locations and the error codes of diagnostics OSC 8
+    /// hyperlinks if `terminal_urls` is true. The URL of a location is
+    /// `template`, `file://{path}` by default, with `{path}`, `{line}` and
+    /// `{col}` replaced by the absolute path of the file and the position.
+    pub fn terminal_urls(mut self, terminal_urls: bool, template: Option<String>) -> Self {
+        self.terminal_url_template = if terminal_urls {
+            Some(template.unwrap_or_else(|| "file://{path}".to_owned()))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Returns the link to `line` and `col` of `file`, where `line` is already offset to the
+    /// line of the file on disk for doctests.
+    fn location_url(&self, file: &SourceFile, line: usize, col: usize) -> Option<String> {
+        let template = self.terminal_url_template.as_ref()?;
+        // Link to the file on disk, even if its name has been remapped
+        let path = match file.unmapped_path.as_ref().unwrap_or(&file.name) {
+            FileName::Real(path) | FileName::DocTest(path, _) if path.is_absolute() => path.clone(),
+            FileName::Real(path) | FileName::DocTest(path, _) => {
+                env::current_dir().ok()?.join(path)
+            }
+            _ => return None,
+        };
+        Some(
+            template
+                .replace("{path}", &encode_url_path(&path))
+                .replace("{line}", &line.to_string())
+                .replace("{col}", &col.to_string()),
+        )
+    }
+
+    fn error_code_url(&self, code: &str) -> Option<String> {
+        self.terminal_url_template.as_ref()?;
+        Some(format!("https://doc.rust-lang.org/error-index.html#{}", code))
+    }
+
     fn maybe_anonymized(&self, line_num: usize) -> String {
         if self.ui_testing { ANONYMIZED_LINE_NUM.to_string() } else { line_num.to_string() }
     }
@@ -1242,7 +1380,7 @@ impl EmitterWriter {
             // only render error codes, not lint codes
             if let Some(DiagnosticId::Error(ref code)) = *code {
                 buffer.append(0, "[", Style::Level(*level));
-                buffer.append(0, &code, Style::Level(*level));
+                buffer.append_hyperlink(0, &code, Style::Level(*level), self.error_code_url(code));
                 buffer.append(0, "]", Style::Level(*level));
             }
             if *level != Level::FailureNote && !level_str.is_empty() {
@@ -1292,30 +1430,25 @@ impl EmitterWriter {
                     // remember where we are in the output buffer for easy reference
                     let buffer_msg_line_offset = buffer.num_lines();
 
+                    let line = sm.doctest_offset_line(&loc.file.name, loc.line);
                     buffer.prepend(buffer_msg_line_offset, "--> ", Style::LineNumber);
-                    buffer.append(
+                    buffer.append_hyperlink(
                         buffer_msg_line_offset,
-                        &format!(
-                            "{}:{}:{}",
-                            loc.file.name,
-                            sm.doctest_offset_line(&loc.file.name, loc.line),
-                            loc.col.0 + 1,
-                        ),
+                        &format!("{}:{}:{}", loc.file.name, line, loc.col.0 + 1),
                         Style::LineAndColumn,
+                        self.location_url(&loc.file, line, loc.col.0 + 1),
                     );
                     for _ in 0..max_line_num_len {
                         buffer.prepend(buffer_msg_line_offset, " ", Style::NoStyle);
                     }
                 } else {
-                    buffer.prepend(
+                    let line = sm.doctest_offset_line(&loc.file.name, loc.line);
+                    buffer.prepend(0, ": ", Style::LineAndColumn);
+                    buffer.prepend_hyperlink(
                         0,
-                        &format!(
-                            "{}:{}:{}: ",
-                            loc.file.name,
-                            sm.doctest_offset_line(&loc.file.name, loc.line),
-                            loc.col.0 + 1,
-                        ),
+                        &format!("{}:{}:{}", loc.file.name, line, loc.col.0 + 1),
                         Style::LineAndColumn,
+                        self.location_url(&loc.file, line, loc.col.0 + 1),
                     );
                 }
             } else if !self.short_message {
@@ -1327,22 +1460,27 @@ impl EmitterWriter {
 
                 // Then, the secondary file indicator
                 buffer.prepend(buffer_msg_line_offset + 1, "::: ", Style::LineNumber);
-                let loc = if let Some(first_line) = annotated_file.lines.first() {
-                    let col = if let Some(first_annotation) = first_line.annotations.first() {
-                        format!(":{}", first_annotation.start_col + 1)
+                let (loc, url) = if let Some(first_line) = annotated_file.lines.first() {
+                    let start_col = first_line.annotations.first().map(|a| a.start_col + 1);
+                    let col = if let Some(start_col) = start_col {
+                        format!(":{}", start_col)
                     } else {
                         String::new()
                     };
-                    format!(
-                        "{}:{}{}",
-                        annotated_file.file.name,
-                        sm.doctest_offset_line(&annotated_file.file.name, first_line.line_index),
-                        col
-                    )
+                    let line =
+                        sm.doctest_offset_line(&annotated_file.file.name, first_line.line_index);
+                    let loc = format!("{}:{}{}", annotated_file.file.name, line, col);
+                    let url = self.location_url(&annotated_file.file, line, start_col.unwrap_or(1));
+                    (loc, url)
                 } else {
-                    annotated_file.file.name.to_string()
+                    (annotated_file.file.name.to_string(), None)
                 };
-                buffer.append(buffer_msg_line_offset + 1, &loc, Style::LineAndColumn);
+                buffer.append_hyperlink(
+                    buffer_msg_line_offset + 1,
+                    &loc,
+                    Style::LineAndColumn,
+                    url,
+                );
                 for _ in 0..max_line_num_len {
                     buffer.prepend(buffer_msg_line_offset + 1, " ", Style::NoStyle);
                 }
@@ -2009,7 +2147,11 @@ fn emit_to_destination(
     for (pos, line) in rendered_buffer.iter().enumerate() {
         for part in line {
             dst.apply_style(lvl.clone(), part.style)?;
-            write!(dst, "{}", part.text)?;
+            match &part.hyperlink {
+                // An OSC 8 hyperlink, which the terminal shows as its text
+                Some(url) => write!(dst, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, part.text)?,
+                None => write!(dst, "{}", part.text)?,
+            }
             dst.reset()?;
         }
         if !short_message && (!lvl.is_failure_note() || pos != rendered_buffer.len() - 1) {
@@ -0,0 +1,148 @@
+use super::*;
+
+use crate::registry::Registry;
+use crate::{Handler, HandlerFlags};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_globals(f: impl FnOnce()) {
+    let globals = rustc_span::Globals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::GLOBALS.set(&globals, || rustc_span::GLOBALS.set(&globals, f))
+}
+
+/// Emits an error with an error code in `file_name`, with hyperlinks if
+/// there is a URL template, and returns the output.
+fn test_output(file_name: FileName, template: Option<&str>, short_message: bool) -> String {
+    let mut output = String::new();
+    with_default_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        let code = "fn main() {\n    let x: u8 = \"\";\n}\n";
+        sm.new_source_file(file_name, code.to_owned());
+
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let dst = Box::new(Shared { data: data.clone() });
+        let emitter = EmitterWriter::new(dst, Some(sm), short_message, false, false, None, false)
+            .terminal_urls(template.is_some(), template.map(str::to_owned));
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+
+        let span = Span::with_root_ctxt(BytePos(28), BytePos(30));
+        let code = DiagnosticId::Error("E0308".to_owned());
+        handler.struct_span_err_with_code(span, "mismatched types", code).emit();
+
+        output = String::from_utf8(data.lock().unwrap().clone()).unwrap();
+    });
+    output
+}
+
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+fn main_rs() -> FileName {
+    Path::new("src/main.rs").to_owned().into()
+}
+
+fn main_rs_path() -> String {
+    encode_url_path(&env::current_dir().unwrap().join("src/main.rs"))
+}
+
+#[test]
+fn no_hyperlinks() {
+    let output = test_output(main_rs(), None, false);
+    assert!(output.starts_with("error[E0308]: mismatched types\n"));
+    assert!(output.contains(" --> src/main.rs:2:17\n"));
+    assert!(!output.contains('\x1b'));
+}
+
+#[test]
+fn hyperlinks() {
+    let output = test_output(main_rs(), Some("file://{path}"), false);
+    let code = hyperlink("https://doc.rust-lang.org/error-index.html#E0308", "E0308");
+    assert!(output.starts_with(&format!("error[{}]: mismatched types\n", code)));
+    let location = hyperlink(&format!("file://{}", main_rs_path()), "src/main.rs:2:17");
+    assert!(output.contains(&format!(" --> {}\n", location)));
+}
+
+#[test]
+fn url_template() {
+    let template = Some("editor://open?file={path}&line={line}&col={col}");
+    let output = test_output(main_rs(), template, true);
+    let url = format!("editor://open?file={}&line=2&col=17", main_rs_path());
+    assert!(output.starts_with(&format!("{}: error[", hyperlink(&url, "src/main.rs:2:17"))));
+}
+
+#[test]
+fn doctest_url() {
+    // A doctest starting at line 10 of `src/main.rs`.
+    let file_name = FileName::DocTest(PathBuf::from("src/main.rs"), 10);
+    let template = Some("editor://open?file={path}&line={line}&col={col}");
+    let output = test_output(file_name, template, false);
+    let url = format!("editor://open?file={}&line=12&col=17", main_rs_path());
+    assert!(output.contains(&format!(" --> {}\n", hyperlink(&url, "src/main.rs:12:17"))));
+}
+
+#[test]
+fn url_path() {
+    assert_eq!(encode_url_path(&PathBuf::from("/src/main.rs")), "/src/main.rs");
+    assert_eq!(encode_url_path(&PathBuf::from("/my src/ü.rs")), "/my%20src/%C3%BC.rs");
+    assert_eq!(encode_url_path(&PathBuf::from("C:\\src\\main.rs")), "/C:/src/main.rs");
+}
+
+#[test]
+fn diagnostic_summary() {
+    let mut output = String::new();
+    with_default_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        let code = "fn main() {\n    let x: u8 = \"\";\n    let y: u8 = \"\";\n}\n";
+        sm.new_source_file(Path::new("src/main.rs").to_owned().into(), code.to_owned());
+
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let dst = Box::new(Shared { data: data.clone() });
+        let emitter = EmitterWriter::new(dst, Some(sm), false, false, false, None, false);
+        let flags = HandlerFlags {
+            can_emit_warnings: true,
+            diagnostic_summary: true,
+            ..Default::default()
+        };
+        let handler = Handler::with_emitter_and_flags(Box::new(emitter), flags);
+
+        let lint = DiagnosticId::Lint("unused_variables".to_owned());
+        handler
+            .struct_span_warn(Span::with_root_ctxt(BytePos(20), BytePos(21)), "unused")
+            .code(lint)
+            .emit();
+        for &(lo, hi) in &[(28, 30), (48, 50)] {
+            let span = Span::with_root_ctxt(BytePos(lo), BytePos(hi));
+            let code = DiagnosticId::Error("E0308".to_owned());
+            handler.struct_span_err_with_code(span, "mismatched types", code).emit();
+        }
+        handler.print_error_count(&Registry::new(&[]));
+
+        output = String::from_utf8(data.lock().unwrap().clone()).unwrap();
+    });
+    let summary = format!(
+        "note: 2 errors and 1 warning were reported\n\
+         \x20   2 {:25} first at src/main.rs:2:17\n\
+         \x20   1 {:25} first at src/main.rs:2:9\n\n\
+         error: aborting due to 2 previous errors\n",
+        "error[E0308]", "warning[unused_variables]"
+    );
+    assert!(output.contains(&summary), "{}", output);
+}
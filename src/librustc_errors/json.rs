@@ -13,7 +13,7 @@ use rustc_span::source_map::{FilePathMapping, SourceMap};
 
 use crate::emitter::{Emitter, HumanReadableErrorType};
 use crate::registry::Registry;
-use crate::{Applicability, DiagnosticId};
+use crate::{Applicability, DiagnosticId, DiagnosticSummaryEntry};
 use crate::{CodeSuggestion, SubDiagnostic};
 
 use rustc_data_structures::sync::Lrc;
@@ -124,6 +124,33 @@ impl Emitter for JsonEmitter {
         }
     }
 
+    fn emit_diagnostic_summary(&mut self, entries: &[DiagnosticSummaryEntry]) {
+        let summary = entries
+            .iter()
+            .map(|entry| SummaryEntry {
+                level: entry.level.to_str(),
+                code: entry.code.clone().map(|code| match code {
+                    DiagnosticId::Error(code) | DiagnosticId::Lint(code) => code,
+                }),
+                count: entry.count,
+                first_span: entry
+                    .first_span
+                    .filter(|span| !span.is_dummy())
+                    .map(|span| DiagnosticSpan::from_span_etc(span, true, None, None, self)),
+            })
+            .collect();
+        let data = Summary { summary };
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        }
+        .and_then(|_| self.dst.flush());
+        if let Err(e) = result {
+            panic!("failed to print diagnostic summary: {:?}", e);
+        }
+    }
+
     fn source_map(&self) -> Option<&Lrc<SourceMap>> {
         Some(&self.sm)
     }
@@ -219,6 +246,24 @@ struct ArtifactNotification<'a> {
     emit: &'a str,
 }
 
+#[derive(RustcEncodable)]
+struct Summary {
+    /// The errors and warnings of each error code and lint.
+    summary: Vec<SummaryEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct SummaryEntry {
+    /// "error" or "warning".
+    level: &'static str,
+    /// The error code or the lint, if any.
+    code: Option<String>,
+    /// The number of errors or warnings.
+    count: usize,
+    /// The primary span of the first of them, if any.
+    first_span: Option<DiagnosticSpan>,
+}
+
 impl Diagnostic {
     fn from_errors_diagnostic(diag: &crate::Diagnostic, je: &JsonEmitter) -> Diagnostic {
         let sugg = diag.suggestions.iter().map(|sugg| Diagnostic {
@@ -19,6 +19,7 @@ use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{Loc, MultiSpan, Span};
+use translation::Translator;
 
 use std::borrow::Cow;
 use std::panic;
@@ -28,14 +29,17 @@ use std::{error, fmt};
 use termcolor::{Color, ColorSpec};
 
 pub mod annotate_snippet_emitter_writer;
+pub mod apply_suggestions;
 mod diagnostic;
 mod diagnostic_builder;
 pub mod emitter;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
+pub mod translation;
 pub use snippet::Style;
 
 pub type PResult<'a, T> = Result<T, DiagnosticBuilder<'a>>;
@@ -278,6 +282,7 @@ pub use diagnostic_builder::DiagnosticBuilder;
 pub struct Handler {
     flags: HandlerFlags,
     inner: Lock<HandlerInner>,
+    translator: Translator,
 }
 
 /// This inner struct exists to keep it all behind a single lock;
@@ -312,6 +317,25 @@ struct HandlerInner {
     /// The stashed diagnostics count towards the total error count.
     /// When `.abort_if_errors()` is called, these are also emitted.
     stashed_diagnostics: FxIndexMap<(Span, StashKey), Diagnostic>,
+
+    /// The errors and warnings that have been emitted for each error code or
+    /// lint, in the order they were first emitted, for `-Z diagnostic-summary`.
+    /// Errors and warnings of the same code are counted apart.
+    diagnostic_summary: FxIndexMap<(bool, Option<DiagnosticId>), DiagnosticSummaryEntry>,
+}
+
+/// The errors or the warnings of one error code or lint, as reported at the
+/// end of the compilation with `-Z diagnostic-summary`.
+#[derive(Clone, Debug)]
+pub struct DiagnosticSummaryEntry {
+    /// Either `Error` or `Warning`.
+    pub level: Level,
+    /// The error code or the lint, or `None` for the diagnostics without one.
+    pub code: Option<DiagnosticId>,
+    /// The number of diagnostics that were emitted.
+    pub count: usize,
+    /// The primary span of the first diagnostic, if it has one.
+    pub first_span: Option<Span>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -344,6 +368,10 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, the errors and warnings of each error code and lint are
+    /// counted and reported at the end of the compilation.
+    /// (rustc: see `-Z diagnostic-summary`)
+    pub diagnostic_summary: bool,
 }
 
 impl Drop for HandlerInner {
@@ -420,10 +448,24 @@ impl Handler {
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                diagnostic_summary: Default::default(),
             }),
+            translator: Translator::default(),
         }
     }
 
+    /// Looks up the messages passed to `translate` with `translator`.
+    pub fn with_translator(mut self, translator: Translator) -> Self {
+        self.translator = translator;
+        self
+    }
+
+    /// Returns the message `id` of the catalogs of diagnostic messages, in
+    /// the language chosen with `-Z translate-lang`, with `args` interpolated.
+    pub fn translate(&self, id: &str, args: &[(&str, String)]) -> String {
+        self.translator.translate(id, args)
+    }
+
     // This is here to not allow mutation of flags;
     // as of this writing it's only used in tests in librustc.
     pub fn can_emit_warnings(&self) -> bool {
@@ -746,6 +788,9 @@ impl HandlerInner {
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
             }
+            if self.flags.diagnostic_summary {
+                self.add_to_summary(diagnostic);
+            }
         }
         if diagnostic.is_error() {
             self.bump_err_count();
@@ -760,8 +805,40 @@ impl HandlerInner {
         self.flags.treat_err_as_bug.map(|c| self.err_count() >= c).unwrap_or(false)
     }
 
+    fn add_to_summary(&mut self, diagnostic: &Diagnostic) {
+        let level = match diagnostic.level {
+            _ if diagnostic.is_error() => Level::Error,
+            Level::Warning => Level::Warning,
+            _ => return,
+        };
+        let code = diagnostic.code.clone();
+        let entry = self
+            .diagnostic_summary
+            .entry((level == Level::Error, code.clone()))
+            .or_insert_with(|| DiagnosticSummaryEntry {
+                level,
+                code,
+                count: 0,
+                first_span: diagnostic.span.primary_span(),
+            });
+        entry.count += 1;
+    }
+
+    /// Emits the summary of the errors and warnings of `-Z diagnostic-summary`:
+    /// the errors come first, then the codes reported the most often.
+    fn emit_diagnostic_summary(&mut self) {
+        if self.diagnostic_summary.is_empty() {
+            return;
+        }
+        let mut entries: Vec<_> =
+            std::mem::take(&mut self.diagnostic_summary).into_iter().map(|(_, e)| e).collect();
+        entries.sort_by_key(|entry| (entry.level != Level::Error, std::cmp::Reverse(entry.count)));
+        self.emitter.emit_diagnostic_summary(&entries);
+    }
+
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_summary();
 
         let s = match self.deduplicated_err_count {
             0 => return,
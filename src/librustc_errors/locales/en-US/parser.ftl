@@ -0,0 +1,51 @@
+# Diagnostics of the lexer and the parser.
+
+parser-bare-cr-in-doc-comment = bare CR not allowed in doc-comment
+
+parser-bare-cr-in-block-doc-comment = bare CR not allowed in block doc-comment
+
+parser-unterminated-block-comment = unterminated block comment
+
+parser-unterminated-block-doc-comment = unterminated block doc-comment
+
+parser-cannot-be-raw-identifier = `{ $ident }` cannot be a raw identifier
+
+parser-underscore-literal-suffix = underscore literal suffix is not allowed
+    .warn = this was previously accepted by the compiler but is being phased out; it will become a hard error in a future release!
+    .note = see issue #42326 <https://github.com/rust-lang/rust/issues/42326> for more information
+
+parser-lifetime-starts-with-number = lifetimes cannot start with a number
+
+parser-unknown-start-of-token = unknown start of token: { $char }
+
+parser-unterminated-char = unterminated character literal
+
+parser-unterminated-byte = unterminated byte constant
+
+parser-unterminated-string = unterminated double quote string
+
+parser-unterminated-byte-string = unterminated double quote byte string
+
+parser-invalid-raw-string-delimiter = found invalid character; only `#` is allowed in raw string delimitation: { $char }
+
+parser-unterminated-raw-string = unterminated raw string
+    .label = unterminated raw string
+    .note = this raw string should be terminated with `"{ $hashes }`
+
+parser-too-many-raw-string-hashes = too many `#` symbols: raw strings may be delimited by up to 65535 `#` symbols
+
+parser-no-digits = no valid digits found for number
+
+parser-empty-exponent = expected at least one digit in exponent
+
+parser-hexadecimal-float = hexadecimal float literal is not supported
+
+parser-octal-float = octal float literal is not supported
+
+parser-binary-float = binary float literal is not supported
+
+parser-invalid-digit = invalid digit for a base { $base } literal
+
+parser-text-direction-codepoint-in-comment = unicode codepoint changing visible direction of text present in comment
+
+parser-text-direction-codepoint-in-literal = unicode codepoint changing visible direction of text present in literal
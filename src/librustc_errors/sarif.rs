@@ -0,0 +1,349 @@
+//! An emitter for errors in the Static Analysis Results Interchange Format
+//! (SARIF), version 2.1.0.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic, a SARIF
+//! log is a single document: diagnostics are collected as they are emitted and
+//! the log is written when the emitter is dropped, together with the handler
+//! that owns it.
+//!
+//! Diagnostics map to SARIF as follows:
+//!
+//! * the code of a diagnostic (an error code or a lint name) is the rule of
+//!   the result, and the rules are described once in the `tool` of the run;
+//! * the primary spans are the locations of the result, the labels of the
+//!   spans being the messages of the locations;
+//! * the secondary spans and the notes and helps attached to the diagnostic
+//!   are related locations of the result;
+//! * the suggestions are fixes of the result.
+//!
+//! SARIF does not allow `null` for most properties, so the log is built as a
+//! `Json` value, leaving out the properties we have no value for, rather than
+//! with `RustcEncodable` structs.
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{CodeSuggestion, DiagnosticId, DiagnosticSummaryEntry, Level, SubDiagnostic};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, Object};
+use rustc_span::{FileName, MultiSpan, Span};
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str =
+    "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0-rtm.5.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    /// The codes of the rules of the run, in the order of `rules`.
+    rule_indices: FxHashMap<String, usize>,
+    rules: Vec<Json>,
+    results: Vec<Json>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), registry, source_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            rule_indices: Default::default(),
+            rules: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the rule for `code` in `rules`, describing the
+    /// rule first if this is its first result.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let (id, is_lint) = rule_id(code);
+        if let Some(&index) = self.rule_indices.get(id) {
+            return index;
+        }
+
+        let mut rule = vec![("id", Json::String(id.clone()))];
+        if !is_lint {
+            let explanation = self
+                .registry
+                .as_ref()
+                .and_then(|registry| registry.try_find_description(id).ok())
+                .and_then(|explanation| explanation);
+            if let Some(explanation) = explanation {
+                rule.push(("fullDescription", markdown_message(explanation.trim())));
+            }
+            let help_uri = format!("https://doc.rust-lang.org/error-index.html#{}", id);
+            rule.push(("helpUri", Json::String(help_uri)));
+        }
+
+        let index = self.rules.len();
+        self.rules.push(object(rule));
+        self.rule_indices.insert(id.clone(), index);
+        index
+    }
+
+    fn result(&mut self, diag: &crate::Diagnostic) -> Json {
+        let mut result = vec![
+            ("level", Json::String(level(diag.level).to_owned())),
+            ("message", message(diag.message())),
+        ];
+
+        if let Some(code) = &diag.code {
+            let rule_index = self.rule_index(code);
+            result.push(("ruleId", Json::String(rule_id(code).0.clone())));
+            result.push(("ruleIndex", Json::U64(rule_index as u64)));
+        }
+
+        let locations = self.locations(&diag.span, true);
+        if !locations.is_empty() {
+            result.push(("locations", Json::Array(locations)));
+        }
+
+        let related_locations: Vec<_> = self
+            .locations(&diag.span, false)
+            .into_iter()
+            .chain(diag.children.iter().flat_map(|child| self.child_locations(child)))
+            .collect();
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Json::Array(related_locations)));
+        }
+
+        let fixes: Vec<_> = diag.suggestions.iter().flat_map(|sugg| self.fixes(sugg)).collect();
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+
+        object(result)
+    }
+
+    /// Returns the locations of the primary spans of `msp`, or of its
+    /// secondary spans if `primary` is false.
+    fn locations(&self, msp: &MultiSpan, primary: bool) -> Vec<Json> {
+        msp.span_labels()
+            .into_iter()
+            .filter(|span_label| span_label.is_primary == primary && !span_label.span.is_dummy())
+            .map(|span_label| {
+                let physical_location = self.physical_location(span_label.span);
+                let mut location = vec![("physicalLocation", physical_location)];
+                if let Some(label) = span_label.label {
+                    location.push(("message", message(label)));
+                }
+                object(location)
+            })
+            .collect()
+    }
+
+    /// Returns a related location for each span of a note or help, carrying
+    /// its message, or a single location without a span if it has none.
+    fn child_locations(&self, child: &SubDiagnostic) -> Vec<Json> {
+        let text = format!("{}: {}", child.level.to_str(), child.message());
+        let msp = child.render_span.as_ref().unwrap_or(&child.span);
+        let spans: Vec<_> =
+            msp.primary_spans().iter().filter(|sp| !sp.is_dummy()).cloned().collect();
+        if spans.is_empty() {
+            return vec![object(vec![("message", message(text))])];
+        }
+        spans
+            .into_iter()
+            .map(|span| {
+                object(vec![
+                    ("physicalLocation", self.physical_location(span)),
+                    ("message", message(text.clone())),
+                ])
+            })
+            .collect()
+    }
+
+    /// Returns a fix for each alternative of a suggestion.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                // SARIF groups the replacements by the file they apply to
+                let mut changes: Vec<(String, Vec<Json>)> = Vec::new();
+                for part in &substitution.parts {
+                    let uri = self.uri(part.span);
+                    let inserted_content =
+                        object(vec![("text", Json::String(part.snippet.clone()))]);
+                    let replacement = object(vec![
+                        ("deletedRegion", self.region(part.span)),
+                        ("insertedContent", inserted_content),
+                    ]);
+                    match changes.iter_mut().find(|(change_uri, _)| *change_uri == uri) {
+                        Some((_, replacements)) => replacements.push(replacement),
+                        None => changes.push((uri, vec![replacement])),
+                    }
+                }
+                let changes = changes
+                    .into_iter()
+                    .map(|(uri, replacements)| {
+                        object(vec![
+                            ("artifactLocation", artifact_location(uri)),
+                            ("replacements", Json::Array(replacements)),
+                        ])
+                    })
+                    .collect();
+                object(vec![
+                    ("description", message(suggestion.msg.clone())),
+                    ("artifactChanges", Json::Array(changes)),
+                ])
+            })
+            .collect()
+    }
+
+    fn physical_location(&self, span: Span) -> Json {
+        object(vec![
+            ("artifactLocation", artifact_location(self.uri(span))),
+            ("region", self.region(span)),
+        ])
+    }
+
+    fn region(&self, span: Span) -> Json {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        // Columns are counted in characters, as the run declares with its
+        // `columnKind`, and the end column is the one after the span.
+        object(vec![
+            ("startLine", Json::U64(start.line as u64)),
+            ("startColumn", Json::U64(start.col.0 as u64 + 1)),
+            ("endLine", Json::U64(end.line as u64)),
+            ("endColumn", Json::U64(end.col.0 as u64 + 1)),
+            ("byteOffset", Json::U64(byte_start as u64)),
+            ("byteLength", Json::U64((byte_end - byte_start) as u64)),
+        ])
+    }
+
+    /// The URI of the file of `span`: a `file` URI for absolute paths, and a
+    /// relative reference for relative paths and source that is not a file.
+    fn uri(&self, span: Span) -> String {
+        let file = self.sm.lookup_char_pos(span.lo()).file;
+        match &file.name {
+            FileName::Real(path) => {
+                let path = path.to_string_lossy().replace('\\', "/");
+                if path.starts_with('/') {
+                    format!("file://{}", path)
+                } else if path.get(1..3) == Some(":/") {
+                    // A Windows path with a drive letter
+                    format!("file:///{}", path)
+                } else {
+                    path
+                }
+            }
+            name => name.to_string(),
+        }
+    }
+
+    fn log(&mut self) -> Json {
+        let driver = object(vec![
+            ("name", Json::String("rustc".to_owned())),
+            ("informationUri", Json::String("https://www.rust-lang.org/".to_owned())),
+            ("rules", Json::Array(std::mem::take(&mut self.rules))),
+        ]);
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            ("columnKind", Json::String("unicodeCodePoints".to_owned())),
+            ("results", Json::Array(std::mem::take(&mut self.results))),
+        ]);
+        object(vec![
+            ("$schema", Json::String(SARIF_SCHEMA.to_owned())),
+            ("version", Json::String(SARIF_VERSION.to_owned())),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        // Failure notes tell how to learn more about the errors on the
+        // command line, which is of no use to the consumers of the log.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let result = self.result(diag);
+        self.results.push(result);
+    }
+
+    fn emit_diagnostic_summary(&mut self, _entries: &[DiagnosticSummaryEntry]) {
+        // The consumers of the log count the results themselves.
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = self.log();
+        let result = writeln!(&mut self.dst, "{}", log).and_then(|_| self.dst.flush());
+        // This can run while unwinding from a fatal error, where a second panic would abort.
+        if let Err(e) = result {
+            eprintln!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+fn level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::FailureNote | Level::Cancelled => "note",
+    }
+}
+
+/// The rule id for a diagnostic code, and whether the code is a lint.
+fn rule_id(code: &DiagnosticId) -> (&String, bool) {
+    match code {
+        DiagnosticId::Error(code) => (code, false),
+        DiagnosticId::Lint(name) => (name, true),
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    let fields: Object = fields.into_iter().map(|(name, value)| (name.to_owned(), value)).collect();
+    Json::Object(fields)
+}
+
+fn message(text: String) -> Json {
+    object(vec![("text", Json::String(text))])
+}
+
+/// The explanations of error codes are written in Markdown, which SARIF
+/// viewers can render, but a plain text version of the message is required.
+fn markdown_message(markdown: &str) -> Json {
+    object(vec![
+        ("text", Json::String(markdown.to_owned())),
+        ("markdown", Json::String(markdown.to_owned())),
+    ])
+}
+
+fn artifact_location(uri: String) -> Json {
+    object(vec![("uri", Json::String(uri))])
+}
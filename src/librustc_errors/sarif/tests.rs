@@ -0,0 +1,142 @@
+use super::*;
+
+use crate::sarif::SarifEmitter;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::{Applicability, Handler};
+use rustc_serialize::json::from_str;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_globals(f: impl FnOnce()) {
+    let globals = rustc_span::Globals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::GLOBALS.set(&globals, || rustc_span::GLOBALS.set(&globals, f))
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+/// Emits diagnostics for `code` and returns the run of the SARIF log.
+fn test_run(code: &str, emit: impl FnOnce(&Handler)) -> Json {
+    let mut run = None;
+    with_default_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), None, sm);
+
+        // The log is written when the handler drops its emitter
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        emit(&handler);
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        let log = from_str(str::from_utf8(&bytes).unwrap()).unwrap();
+        assert_eq!(log["version"], Json::String("2.1.0".to_owned()));
+        run = Some(log["runs"][0].clone());
+    });
+    run.unwrap()
+}
+
+fn string(s: &str) -> Json {
+    Json::String(s.to_owned())
+}
+
+#[test]
+fn region() {
+    let run = test_run("fn main() {\n    let x = 1;\n}\n", |handler| {
+        handler.span_err(span(20, 21), "foo");
+    });
+
+    let result = &run["results"][0];
+    assert_eq!(result["level"], string("error"));
+    assert_eq!(result["message"]["text"], string("foo"));
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], string("test.rs"));
+    let region = &location["region"];
+    assert_eq!(region["startLine"], Json::U64(2));
+    assert_eq!(region["startColumn"], Json::U64(9));
+    assert_eq!(region["endLine"], Json::U64(2));
+    assert_eq!(region["endColumn"], Json::U64(10));
+    assert_eq!(region["byteOffset"], Json::U64(20));
+    assert_eq!(region["byteLength"], Json::U64(1));
+}
+
+#[test]
+fn rules() {
+    let run = test_run("fn main() {}\n", |handler| {
+        let lint = DiagnosticId::Lint("dead_code".to_owned());
+        let error = DiagnosticId::Error("E0425".to_owned());
+        handler.struct_span_warn_with_code(span(3, 7), "foo", lint.clone()).emit();
+        handler.struct_span_err_with_code(span(3, 7), "bar", error).emit();
+        handler.struct_span_warn_with_code(span(3, 7), "baz", lint).emit();
+    });
+
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0]["id"], string("dead_code"));
+    assert_eq!(rules[1]["id"], string("E0425"));
+    assert_eq!(rules[1]["helpUri"], string("https://doc.rust-lang.org/error-index.html#E0425"));
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results[0]["ruleId"], string("dead_code"));
+    assert_eq!(results[0]["level"], string("warning"));
+    assert_eq!(results[1]["ruleIndex"], Json::U64(1));
+    assert_eq!(results[2]["ruleIndex"], Json::U64(0));
+}
+
+#[test]
+fn related_locations() {
+    let run = test_run("fn main() {\n    let x = 1;\n}\n", |handler| {
+        let mut err = handler.struct_span_err(span(20, 21), "foo");
+        err.span_label(span(24, 25), "bar");
+        err.span_note(span(3, 7), "baz");
+        err.help("qux");
+        err.emit();
+    });
+
+    let related = run["results"][0]["relatedLocations"].as_array().unwrap();
+    assert_eq!(related.len(), 3);
+    assert_eq!(related[0]["message"]["text"], string("bar"));
+    assert_eq!(related[0]["physicalLocation"]["region"]["startColumn"], Json::U64(13));
+    assert_eq!(related[1]["message"]["text"], string("note: baz"));
+    assert_eq!(related[1]["physicalLocation"]["region"]["startLine"], Json::U64(1));
+    assert_eq!(related[2]["message"]["text"], string("help: qux"));
+    assert!(related[2].find("physicalLocation").is_none());
+}
+
+#[test]
+fn fixes() {
+    let run = test_run("fn main() {\n    let x = 1;\n}\n", |handler| {
+        let mut err = handler.struct_span_err(span(20, 21), "foo");
+        err.span_suggestion(span(20, 21), "rename", "_x".to_owned(), Applicability::MaybeIncorrect);
+        err.emit();
+    });
+
+    let fix = &run["results"][0]["fixes"][0];
+    assert_eq!(fix["description"]["text"], string("rename"));
+    let change = &fix["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], string("test.rs"));
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], Json::U64(20));
+    assert_eq!(replacement["insertedContent"]["text"], string("_x"));
+}
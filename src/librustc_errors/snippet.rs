@@ -171,6 +171,8 @@ impl Annotation {
 pub struct StyledString {
     pub text: String,
     pub style: Style,
+    /// The URL the text links to, for the terminals that support hyperlinks.
+    pub hyperlink: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
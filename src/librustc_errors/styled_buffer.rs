@@ -6,15 +6,46 @@ use crate::snippet::{Style, StyledString};
 pub struct StyledBuffer {
     text: Vec<Vec<char>>,
     styles: Vec<Vec<Style>>,
+    hyperlinks: Vec<Hyperlink>,
+}
+
+/// A hyperlink to `url` on the columns `start..end` of `line`.
+#[derive(Debug)]
+struct Hyperlink {
+    line: usize,
+    start: usize,
+    end: usize,
+    url: String,
 }
 
 impl StyledBuffer {
     pub fn new() -> StyledBuffer {
-        StyledBuffer { text: vec![], styles: vec![] }
+        StyledBuffer { text: vec![], styles: vec![], hyperlinks: vec![] }
+    }
+
+    /// Moves the hyperlinks of `line` by `len` columns, when that many columns
+    /// are inserted before `col`.
+    fn shift_hyperlinks(&mut self, line: usize, col: usize, len: usize) {
+        for link in self.hyperlinks.iter_mut().filter(|link| link.line == line) {
+            if link.start >= col {
+                link.start += len;
+            }
+            if link.end >= col {
+                link.end += len;
+            }
+        }
+    }
+
+    fn hyperlink_at(&self, line: usize, col: usize) -> Option<&String> {
+        self.hyperlinks
+            .iter()
+            .find(|link| link.line == line && link.start <= col && col < link.end)
+            .map(|link| &link.url)
     }
 
     fn replace_tabs(&mut self) {
-        for (line_pos, line) in self.text.iter_mut().enumerate() {
+        for line_pos in 0..self.text.len() {
+            let line = &mut self.text[line_pos];
             let mut tab_pos = vec![];
             for (pos, c) in line.iter().enumerate() {
                 if *c == '\t' {
@@ -31,6 +62,9 @@ impl StyledBuffer {
                     self.styles[line_pos].insert(*pos, s);
                 }
             }
+            for pos in tab_pos.iter().rev() {
+                self.shift_hyperlinks(line_pos, *pos + 1, 3);
+            }
         }
     }
 
@@ -41,22 +75,33 @@ impl StyledBuffer {
         // before we render, replace tabs with spaces
         self.replace_tabs();
 
-        for (row, row_style) in self.text.iter().zip(&self.styles) {
+        for (line, (row, row_style)) in self.text.iter().zip(&self.styles).enumerate() {
             let mut current_style = Style::NoStyle;
+            let mut current_hyperlink = None;
             let mut current_text = String::new();
 
-            for (&c, &s) in row.iter().zip(row_style) {
-                if s != current_style {
+            for (col, (&c, &s)) in row.iter().zip(row_style).enumerate() {
+                let hyperlink = self.hyperlink_at(line, col);
+                if s != current_style || hyperlink != current_hyperlink {
                     if !current_text.is_empty() {
-                        styled_vec.push(StyledString { text: current_text, style: current_style });
+                        styled_vec.push(StyledString {
+                            text: current_text,
+                            style: current_style,
+                            hyperlink: current_hyperlink.cloned(),
+                        });
                     }
                     current_style = s;
+                    current_hyperlink = hyperlink;
                     current_text = String::new();
                 }
                 current_text.push(c);
             }
             if !current_text.is_empty() {
-                styled_vec.push(StyledString { text: current_text, style: current_style });
+                styled_vec.push(StyledString {
+                    text: current_text,
+                    style: current_style,
+                    hyperlink: current_hyperlink.cloned(),
+                });
             }
 
             // We're done with the row, push and keep going
@@ -109,6 +154,7 @@ impl StyledBuffer {
             self.styles[line].insert(0, Style::NoStyle);
             self.text[line].insert(0, ' ');
         }
+        self.shift_hyperlinks(line, 0, string_len);
 
         self.puts(line, 0, string, style);
     }
@@ -122,6 +168,36 @@ impl StyledBuffer {
         }
     }
 
+    /// Prepends `string` like `prepend`, as a hyperlink to `url` if there is one.
+    pub fn prepend_hyperlink(
+        &mut self,
+        line: usize,
+        string: &str,
+        style: Style,
+        url: Option<String>,
+    ) {
+        self.prepend(line, string, style);
+        if let Some(url) = url {
+            self.hyperlinks.push(Hyperlink { line, start: 0, end: string.chars().count(), url });
+        }
+    }
+
+    /// Appends `string` like `append`, as a hyperlink to `url` if there is one.
+    pub fn append_hyperlink(
+        &mut self,
+        line: usize,
+        string: &str,
+        style: Style,
+        url: Option<String>,
+    ) {
+        let start = self.text.get(line).map_or(0, |text| text.len());
+        self.append(line, string, style);
+        if let Some(url) = url {
+            let end = start + string.chars().count();
+            self.hyperlinks.push(Hyperlink { line, start, end, url });
+        }
+    }
+
     pub fn num_lines(&self) -> usize {
         self.text.len()
     }
@@ -0,0 +1,339 @@
+//! Catalogs of diagnostic messages, so that diagnostics can be reported in
+//! languages other than English.
+//!
+//! Messages that have been moved to a catalog are named by an identifier
+//! rather than written in the compiler, and the `Handler` looks up the text of
+//! the message in the language chosen with `-Z translate-lang`. The catalogs
+//! are written in a subset of [Fluent](https://projectfluent.org/):
+//!
+//! ```text
+//! # A comment
+//! parser-unterminated-raw-string = unterminated raw string
+//!     .label = unterminated raw string
+//!     .note = this raw string should be terminated with `"{ $hashes }`
+//! ```
+//!
+//! The attributes of a message, like `.label` above, are named
+//! `parser-unterminated-raw-string.label`, and are used for the labels, notes
+//! and helps of the diagnostic. Placeables interpolate an argument of the
+//! message, like `{ $hashes }`, or a string literal, like `{ "{" }`, which is
+//! how braces are written. A message may continue on the indented lines that
+//! follow it. Selectors, terms and functions are not supported.
+//!
+//! The English messages are built into the compiler, and are used for the
+//! messages that are missing from the catalogs of the chosen language. The
+//! catalogs of other languages are read from `share/locale/<lang>/*.ftl` in
+//! the sysroot.
+
+use rustc_data_structures::fx::FxHashMap;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The catalogs of the English messages.
+pub const DEFAULT_LOCALE_RESOURCES: &[&str] = &[include_str!("locales/en-US/parser.ftl")];
+
+/// The language of `DEFAULT_LOCALE_RESOURCES`.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Text(String),
+    Var(String),
+}
+
+/// The messages of a catalog, keyed by their identifier, or by the identifier
+/// of their message and their name for attributes.
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    messages: FxHashMap<String, Vec<Part>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatalogError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl Catalog {
+    pub fn parse(source: &str) -> Result<Catalog, CatalogError> {
+        let mut messages = FxHashMap::default();
+        // The key and the text of the message or attribute being parsed,
+        // which continues until a line that is not indented.
+        let mut current: Option<(String, usize, String)> = None;
+        // The identifier of the last message, to name its attributes.
+        let mut message_id: Option<String> = None;
+
+        let mut finish = |current: Option<(String, usize, String)>| -> Result<(), CatalogError> {
+            if let Some((key, line, text)) = current {
+                let parts = parse_pattern(&text).map_err(|msg| CatalogError { line, msg })?;
+                if messages.insert(key.clone(), parts).is_some() {
+                    let msg = format!("`{}` is defined more than once", key);
+                    return Err(CatalogError { line, msg });
+                }
+            }
+            Ok(())
+        };
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let error = |msg: &str| Err(CatalogError { line: line_number, msg: msg.to_owned() });
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                if trimmed.starts_with('.') {
+                    let (name, value) = match split_entry(&trimmed[1..]) {
+                        Some(entry) => entry,
+                        None => return error("expected an attribute like `.name = value`"),
+                    };
+                    let id = match &message_id {
+                        Some(id) => id,
+                        None => return error("attribute without a message"),
+                    };
+                    finish(current.take())?;
+                    current = Some((format!("{}.{}", id, name), line_number, value.to_owned()));
+                } else {
+                    match &mut current {
+                        Some((_, _, text)) => {
+                            if !text.is_empty() {
+                                text.push('\n');
+                            }
+                            text.push_str(trimmed);
+                        }
+                        None => return error("indented line without a message"),
+                    }
+                }
+                continue;
+            }
+
+            if line.starts_with('-') {
+                return error("terms are not supported");
+            }
+            let (id, value) = match split_entry(line) {
+                Some(entry) => entry,
+                None => return error("expected a message like `identifier = value`"),
+            };
+            finish(current.take())?;
+            message_id = Some(id.to_owned());
+            current = Some((id.to_owned(), line_number, value.to_owned()));
+        }
+        finish(current)?;
+
+        Ok(Catalog { messages })
+    }
+
+    /// Adds the messages of `other`, which replace the messages of this
+    /// catalog with the same identifier.
+    pub fn extend(&mut self, other: Catalog) {
+        self.messages.extend(other.messages);
+    }
+
+    fn get(&self, id: &str) -> Option<&[Part]> {
+        self.messages.get(id).map(|parts| &parts[..])
+    }
+}
+
+/// Splits `name = value` at the `=`, if `name` is a valid identifier.
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let eq = line.find('=')?;
+    let name = line[..eq].trim();
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !is_identifier {
+        return None;
+    }
+    Some((name, line[eq + 1..].trim()))
+}
+
+fn parse_pattern(text: &str) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find(|c| c == '{' || c == '}') {
+        if rest[open..].starts_with('}') {
+            return Err("unbalanced `}`; write `{ \"}\" }` for a literal brace".to_owned());
+        }
+        literal.push_str(&rest[..open]);
+        let inner = rest[open + 1..].trim_start();
+
+        if inner.starts_with('"') {
+            // The literal may itself be a brace, as in `{ "}" }`
+            let end = match inner[1..].find('"') {
+                Some(end) => end + 1,
+                None => return Err("unterminated string literal".to_owned()),
+            };
+            literal.push_str(&inner[1..end]);
+            let after = inner[end + 1..].trim_start();
+            if !after.starts_with('}') {
+                return Err("expected `}` after a string literal".to_owned());
+            }
+            rest = &after[1..];
+            continue;
+        }
+
+        let close = match inner.find('}') {
+            Some(close) => close,
+            None => return Err("unterminated placeable".to_owned()),
+        };
+        let placeable = inner[..close].trim_end();
+        let name = if placeable.starts_with('$') { &placeable[1..] } else { "" };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("unsupported placeable `{{ {} }}`", placeable));
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Text(std::mem::take(&mut literal)));
+        }
+        parts.push(Part::Var(name.to_owned()));
+        rest = &inner[close + 1..];
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(Part::Text(literal));
+    }
+    Ok(parts)
+}
+
+#[derive(Debug)]
+pub enum TranslationError {
+    /// There are no catalogs for the language in the sysroot.
+    MissingLocale(String, PathBuf),
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, CatalogError),
+}
+
+impl fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationError::MissingLocale(lang, dir) => write!(
+                f,
+                "no diagnostic messages for the language `{}`: `{}` does not exist",
+                lang,
+                dir.display()
+            ),
+            TranslationError::Io(path, e) => {
+                write!(f, "failed to read `{}`: {}", path.display(), e)
+            }
+            TranslationError::Parse(path, e) => {
+                write!(f, "invalid diagnostic messages in `{}`: {}", path.display(), e)
+            }
+        }
+    }
+}
+
+/// Looks up the messages of diagnostics in the chosen language, falling back
+/// to English.
+#[derive(Clone, Debug)]
+pub struct Translator {
+    catalog: Option<Catalog>,
+    fallback: Catalog,
+}
+
+impl Default for Translator {
+    /// A translator to English.
+    fn default() -> Translator {
+        let mut fallback = Catalog::default();
+        for resource in DEFAULT_LOCALE_RESOURCES {
+            let catalog = Catalog::parse(resource)
+                .unwrap_or_else(|e| panic!("invalid built-in diagnostic messages: {}", e));
+            fallback.extend(catalog);
+        }
+        Translator { catalog: None, fallback }
+    }
+}
+
+impl Translator {
+    /// A translator to English, with the messages of `catalog` replacing the
+    /// English ones.
+    pub fn with_catalog(catalog: Catalog) -> Translator {
+        Translator { catalog: Some(catalog), ..Translator::default() }
+    }
+
+    /// Loads the catalogs of `lang` from `sysroot`, and the messages of
+    /// `additional_ftl`, which replace the messages of the language and are
+    /// meant for testing translations without installing them.
+    pub fn load(
+        sysroot: &Path,
+        lang: Option<&str>,
+        additional_ftl: Option<&Path>,
+    ) -> Result<Translator, TranslationError> {
+        let mut catalog = Catalog::default();
+
+        if let Some(lang) = lang.filter(|&lang| lang != DEFAULT_LOCALE) {
+            let dir = sysroot.join("share").join("locale").join(lang);
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    return Err(TranslationError::MissingLocale(lang.to_owned(), dir));
+                }
+                Err(e) => return Err(TranslationError::Io(dir, e)),
+            };
+            let mut paths = Vec::new();
+            for entry in entries {
+                let path = entry.map_err(|e| TranslationError::Io(dir.clone(), e))?.path();
+                if path.extension().map_or(false, |ext| ext == "ftl") {
+                    paths.push(path);
+                }
+            }
+            // Read the files in a deterministic order, for duplicate messages
+            paths.sort();
+            for path in paths {
+                catalog.extend(load_catalog(&path)?);
+            }
+        }
+
+        if let Some(path) = additional_ftl {
+            catalog.extend(load_catalog(path)?);
+        }
+
+        Ok(Translator::with_catalog(catalog))
+    }
+
+    /// Returns the message `id` with `args` interpolated. Arguments that are
+    /// not given are left as `{$name}` in the message.
+    pub fn translate(&self, id: &str, args: &[(&str, String)]) -> String {
+        let parts = self
+            .catalog
+            .as_ref()
+            .and_then(|catalog| catalog.get(id))
+            .or_else(|| self.fallback.get(id))
+            .unwrap_or_else(|| panic!("no diagnostic message `{}`", id));
+
+        let mut message = String::new();
+        for part in parts {
+            match part {
+                Part::Text(text) => message.push_str(text),
+                Part::Var(name) => match args.iter().find(|(arg, _)| arg == name) {
+                    Some((_, value)) => message.push_str(value),
+                    None => {
+                        message.push_str("{$");
+                        message.push_str(name);
+                        message.push('}');
+                    }
+                },
+            }
+        }
+        message
+    }
+}
+
+fn load_catalog(path: &Path) -> Result<Catalog, TranslationError> {
+    let source = fs::read_to_string(path).map_err(|e| TranslationError::Io(path.to_owned(), e))?;
+    Catalog::parse(&source).map_err(|e| TranslationError::Parse(path.to_owned(), e))
+}
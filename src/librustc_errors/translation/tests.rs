@@ -0,0 +1,77 @@
+use super::*;
+
+fn args(args: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+    args.iter().map(|&(name, value)| (name, value.to_owned())).collect()
+}
+
+fn translator(source: &str) -> Translator {
+    Translator::with_catalog(Catalog::parse(source).unwrap())
+}
+
+#[test]
+fn messages_and_attributes() {
+    let translator = translator(
+        "# A comment\n\
+         foo = foo message\n\
+         \x20   .label = foo label\n\
+         \n\
+         bar = bar message\n",
+    );
+    assert_eq!(translator.translate("foo", &[]), "foo message");
+    assert_eq!(translator.translate("foo.label", &[]), "foo label");
+    assert_eq!(translator.translate("bar", &[]), "bar message");
+}
+
+#[test]
+fn multiline() {
+    let translator = translator("foo =\n    first line\n    second line\n");
+    assert_eq!(translator.translate("foo", &[]), "first line\nsecond line");
+}
+
+#[test]
+fn placeables() {
+    let translator = translator("foo = `{ $name }` has { $count } fields { \"{\" }{ \"}\" }\n");
+    let message = translator.translate("foo", &args(&[("count", "2"), ("name", "Point")]));
+    assert_eq!(message, "`Point` has 2 fields {}");
+
+    // Missing arguments are left as they are
+    assert_eq!(translator.translate("foo", &[]), "`{$name}` has {$count} fields {}");
+}
+
+#[test]
+fn fallback() {
+    let translator = translator("parser-unterminated-block-comment = commentaire non terminé\n");
+    assert_eq!(
+        translator.translate("parser-unterminated-block-comment", &[]),
+        "commentaire non terminé"
+    );
+    assert_eq!(
+        translator.translate("parser-unterminated-block-doc-comment", &[]),
+        "unterminated block doc-comment"
+    );
+}
+
+#[test]
+fn built_in_messages() {
+    let translator = Translator::default();
+    let message = translator.translate("parser-invalid-digit", &args(&[("base", "8")]));
+    assert_eq!(message, "invalid digit for a base 8 literal");
+    let message =
+        translator.translate("parser-unterminated-raw-string.note", &args(&[("hashes", "##")]));
+    assert_eq!(message, "this raw string should be terminated with `\"##`");
+}
+
+#[test]
+fn errors() {
+    let error = |source: &str| Catalog::parse(source).unwrap_err();
+
+    assert_eq!(error("foo = a\nfoo = b\n").line, 2);
+    assert_eq!(error("foo = a\n\n.label = b\n").line, 3);
+    assert_eq!(error("    .label = b\n").line, 1);
+    assert_eq!(error("-term = a\n").line, 1);
+    assert_eq!(error("foo = { $a\n").line, 1);
+    assert_eq!(error("foo = a }\n").line, 1);
+    assert_eq!(error("foo = { bar }\n").line, 1);
+    assert_eq!(error("foo = a\nbar = { \"b }\n").line, 2);
+    assert_eq!(error("foo bar\n").line, 1);
+}
@@ -554,6 +554,13 @@ declare_features! (
     // Allows limiting the evaluation steps of const expressions
     (active, const_eval_limit, "1.43.0", Some(67217), None),
 
+    /// Allows writing MIR bodies by hand with `#[custom_mir]`, for testing MIR optimizations.
+    (active, custom_mir, "1.43.0", None, None),
+
+    /// Allows the `#[must_not_suspend]` attribute, which lints values of a type held
+    /// across the suspension points of generators and `async` functions.
+    (active, must_not_suspend, "1.43.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
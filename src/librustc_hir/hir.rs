@@ -8,7 +8,7 @@ crate use BlockCheckMode::*;
 crate use FnRetTy::*;
 crate use UnsafeSource::*;
 
-use rustc_ast::ast::{self, AsmDialect, CrateSugar, Ident, Name};
+use rustc_ast::ast::{self, AsmDialect, AsmMemory, CrateSugar, Ident, Name};
 use rustc_ast::ast::{AttrVec, Attribute, FloatTy, IntTy, Label, LitKind, StrStyle, UintTy};
 pub use rustc_ast::ast::{BorrowKind, ImplPolarity, IsAuto};
 pub use rustc_ast::ast::{CaptureBy, Movability, Mutability};
@@ -2080,6 +2080,8 @@ pub struct InlineAsmInner {
     pub volatile: bool,
     pub alignstack: bool,
     pub dialect: AsmDialect,
+    pub memory: AsmMemory,
+    pub preserves_flags: bool,
 }
 
 #[derive(RustcEncodable, RustcDecodable, Debug, HashStable_Generic)]
@@ -257,4 +257,6 @@ language_item_table! {
 
     Arc,                         "arc",                arc,                     Target::Struct;
     Rc,                          "rc",                 rc,                      Target::Struct;
+
+    CountCodeRegionFnLangItem,   "count_code_region",  count_code_region_fn,    Target::ForeignFn;
 }
@@ -0,0 +1,77 @@
+//! The report of `-Z incremental-explain`, which tells why the queries of the
+//! previous session were executed again rather than marked green.
+//!
+//! A query of the previous session is only executed again if one of its
+//! dependencies is red, that is, its result changed, so we follow the first red
+//! dependency of each re-executed query, then the first red dependency of that
+//! one, and so on, until an input whose hash changed, like a HIR owner.
+
+use rustc::ty::TyCtxt;
+use rustc_serialize::json::as_json;
+use rustc_session::config::IncrementalExplain;
+
+#[derive(RustcEncodable)]
+struct JsonReexecutedQuery {
+    /// The re-executed query, like `typeck_tables_of(krate::main)`.
+    query: String,
+    /// The kind of the query, like `typeck_tables_of`.
+    kind: String,
+    /// Whether the result of the query changed.
+    changed: bool,
+    chain: Vec<JsonRedDependency>,
+}
+
+#[derive(RustcEncodable)]
+struct JsonRedDependency {
+    node: String,
+    kind: String,
+    /// Whether the dependency was red, rather than impossible to mark green
+    /// and to force.
+    red: bool,
+}
+
+pub fn explain_reexecuted_queries(tcx: TyCtxt<'_>) {
+    let format = match tcx.sess.opts.debugging_opts.incremental_explain {
+        Some(format) => format,
+        None => return,
+    };
+
+    for query in tcx.dep_graph.reexecuted_queries() {
+        match format {
+            IncrementalExplain::Text => {
+                println!(
+                    "[incremental] re-executed `{:?}`, {}",
+                    query.node,
+                    if query.changed { "its result changed" } else { "its result did not change" }
+                );
+                for dep in &query.chain {
+                    if dep.red {
+                        println!("[incremental]     because `{:?}` changed", dep.node);
+                    } else {
+                        println!(
+                            "[incremental]     because `{:?}` could not be marked green nor forced",
+                            dep.node
+                        );
+                    }
+                }
+            }
+            IncrementalExplain::Json => {
+                let query = JsonReexecutedQuery {
+                    query: format!("{:?}", query.node),
+                    kind: format!("{:?}", query.node.kind),
+                    changed: query.changed,
+                    chain: query
+                        .chain
+                        .iter()
+                        .map(|dep| JsonRedDependency {
+                            node: format!("{:?}", dep.node),
+                            kind: format!("{:?}", dep.node.kind),
+                            red: dep.red,
+                        })
+                        .collect(),
+                };
+                println!("{}", as_json(&query));
+            }
+        }
+    }
+}
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 
 use super::data::*;
 use super::dirty_clean;
+use super::explain;
 use super::file_format;
 use super::fs::*;
 use super::work_product;
@@ -21,6 +22,9 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
         if sess.opts.incremental.is_none() {
             return;
         }
+
+        explain::explain_reexecuted_queries(tcx);
+
         // This is going to be deleted in finalize_session_directory, so let's not create it
         if sess.has_errors_or_delayed_span_bugs() {
             return;
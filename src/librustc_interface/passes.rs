@@ -207,6 +207,7 @@ pub fn register_plugins<'a>(
             registrar(&mut registry);
         }
     });
+    lint_store.register_config_groups(sess);
 
     Ok((krate, Lrc::new(lint_store)))
 }
@@ -200,7 +200,8 @@ impl<'tcx> Queries<'tcx> {
                                 })
                                 .open(self.session())
                         });
-                    DepGraph::new(prev_graph, prev_work_products)
+                    let explain = self.session().opts.debugging_opts.incremental_explain.is_some();
+                    DepGraph::new(prev_graph, prev_work_products, explain)
                 }
             })
         })
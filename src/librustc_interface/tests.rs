@@ -8,7 +8,8 @@ use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{rustc_optgroups, ErrorOutputType, ExternLocation, Options, Passes};
 use rustc_session::config::{ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
-use rustc_session::config::{Externs, OutputType, OutputTypes, SymbolManglingVersion};
+use rustc_session::config::{Externs, OutputType, OutputTypes, SplitDebuginfo, Strip};
+use rustc_session::config::SymbolManglingVersion;
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::{build_session, Session};
@@ -241,6 +242,29 @@ fn test_lints_tracking_hash_different_construction_order() {
     assert_eq!(v2.dep_tracking_hash(), v2.clone().dep_tracking_hash());
 }
 
+#[test]
+fn test_lint_config_tracking_hash() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let mut v3 = Options::default();
+
+    v1.lint_config.levels = vec![(String::from("a"), Level::Deny)];
+
+    v2.lint_config.levels = vec![(String::from("a"), Level::Warn)];
+
+    v3.lint_config.levels = vec![(String::from("a"), Level::Deny)];
+    v3.lint_config.groups = vec![(String::from("a"), vec![String::from("b")])];
+
+    assert!(v1.dep_tracking_hash() != v2.dep_tracking_hash());
+    assert!(v1.dep_tracking_hash() != v3.dep_tracking_hash());
+    assert!(v2.dep_tracking_hash() != v3.dep_tracking_hash());
+
+    // The path of the file is not tracked, only its contents
+    let mut v4 = v1.clone();
+    v4.debugging_opts.lint_config = Some(PathBuf::from("lints.json"));
+    assert_eq!(v1.dep_tracking_hash(), v4.dep_tracking_hash());
+}
+
 #[test]
 fn test_search_paths_tracking_hash_different_order() {
     let mut v1 = Options::default();
@@ -406,6 +430,9 @@ fn test_codegen_options_tracking_hash() {
     opts.cg.incremental = Some(String::from("abc"));
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
+    opts.cg.strip = Strip::Symbols;
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+
     // Make sure changing a [TRACKED] option changes the hash
     opts = reference.clone();
     opts.cg.lto = LtoCli::Fat;
@@ -479,6 +506,14 @@ fn test_codegen_options_tracking_hash() {
     opts.cg.profile_use = Some(PathBuf::from("abc"));
     assert_ne!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
+    opts = reference.clone();
+    opts.cg.profile_sample_use = Some(PathBuf::from("abc"));
+    assert_ne!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.cg.split_debuginfo = SplitDebuginfo::Packed;
+    assert_ne!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+
     opts = reference.clone();
     opts.cg.metadata = vec![String::from("A"), String::from("B")];
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -608,6 +643,18 @@ fn test_debugging_options_tracking_hash() {
     opts.debugging_opts.mir_opt_level = 3;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+    opts = reference.clone();
+    opts.debugging_opts.inline_mir = Some(true);
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.inline_mir_threshold = Some(123);
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.instrument_coverage = true;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
     opts = reference.clone();
     opts.debugging_opts.relro_level = Some(RelroLevel::Full);
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -623,6 +670,14 @@ fn test_debugging_options_tracking_hash() {
     opts = reference.clone();
     opts.debugging_opts.symbol_mangling_version = SymbolManglingVersion::V0;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.translate_lang = Some(String::from("fr"));
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.translate_additional_ftl = Some(PathBuf::from("fr.ftl"));
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 }
 
 #[test]
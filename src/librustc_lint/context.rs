@@ -25,9 +25,9 @@ use rustc::ty::layout::{LayoutError, LayoutOf, TyLayout};
 use rustc::ty::{self, print::Printer, subst::GenericArg, Ty, TyCtxt};
 use rustc_ast::ast;
 use rustc_ast::util::lev_distance::find_best_match_for_name;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::sync;
-use rustc_errors::{struct_span_err, Applicability};
+use rustc_errors::{struct_span_err, Applicability, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_hir::def_id::{CrateNum, DefId};
 use rustc_hir::definitions::{DefPathData, DisambiguatedDefPathData};
@@ -36,6 +36,8 @@ use rustc_session::lint::{FutureIncompatibleInfo, Level, Lint, LintBuffer, LintI
 use rustc_session::Session;
 use rustc_span::{symbol::Symbol, MultiSpan, Span, DUMMY_SP};
 
+use std::borrow::Cow;
+use std::path::Path;
 use std::slice;
 
 /// Information about the registered lints.
@@ -62,7 +64,9 @@ pub struct LintStore {
     by_name: FxHashMap<String, TargetLint>,
 
     /// Map of registered lint groups to what lints they expand to.
-    lint_groups: FxHashMap<&'static str, LintGroup>,
+    /// The lint groups, whose names are static except for those of the lint
+    /// configuration file.
+    lint_groups: FxHashMap<Cow<'static, str>, LintGroup>,
 }
 
 /// The target of the `by_name` map, which accounts for renaming/deprecation.
@@ -127,7 +131,7 @@ impl LintStore {
         &self.lints
     }
 
-    pub fn get_lint_groups<'t>(&'t self) -> Vec<(&'static str, Vec<LintId>, bool)> {
+    pub fn get_lint_groups<'t>(&'t self) -> Vec<(&'t str, Vec<LintId>, bool)> {
         self.lint_groups
             .iter()
             .filter(|(_, LintGroup { depr, .. })| {
@@ -135,7 +139,7 @@ impl LintStore {
                 depr.is_none()
             })
             .map(|(k, LintGroup { lint_ids, from_plugin, .. })| {
-                (*k, lint_ids.clone(), *from_plugin)
+                (&k[..], lint_ids.clone(), *from_plugin)
             })
             .collect()
     }
@@ -181,7 +185,7 @@ impl LintStore {
             if let Some(FutureIncompatibleInfo { edition, .. }) = lint.future_incompatible {
                 if let Some(edition) = edition {
                     self.lint_groups
-                        .entry(edition.lint_name())
+                        .entry(edition.lint_name().into())
                         .or_insert(LintGroup {
                             lint_ids: vec![],
                             from_plugin: lint.is_plugin,
@@ -192,7 +196,7 @@ impl LintStore {
                 }
 
                 self.lint_groups
-                    .entry("future_incompatible")
+                    .entry("future_incompatible".into())
                     .or_insert(LintGroup {
                         lint_ids: vec![],
                         from_plugin: lint.is_plugin,
@@ -206,7 +210,7 @@ impl LintStore {
 
     pub fn register_group_alias(&mut self, lint_name: &'static str, alias: &'static str) {
         self.lint_groups.insert(
-            alias,
+            alias.into(),
             LintGroup {
                 lint_ids: vec![],
                 from_plugin: false,
@@ -224,11 +228,11 @@ impl LintStore {
     ) {
         let new = self
             .lint_groups
-            .insert(name, LintGroup { lint_ids: to, from_plugin, depr: None })
+            .insert(name.into(), LintGroup { lint_ids: to, from_plugin, depr: None })
             .is_none();
         if let Some(deprecated) = deprecated_name {
             self.lint_groups.insert(
-                deprecated,
+                deprecated.into(),
                 LintGroup {
                     lint_ids: vec![],
                     from_plugin,
@@ -242,6 +246,86 @@ impl LintStore {
         }
     }
 
+    /// Registers the custom lint groups of the lint configuration file given
+    /// with `-Z lint-config`, once all the lints are registered.
+    pub fn register_config_groups(&mut self, sess: &Session) {
+        let path = match &sess.opts.debugging_opts.lint_config {
+            Some(path) => path,
+            None => return,
+        };
+        let groups: FxIndexMap<&str, &[String]> = sess
+            .opts
+            .lint_config
+            .groups
+            .iter()
+            .map(|(name, lint_names)| (&name[..], &lint_names[..]))
+            .collect();
+        let mut visited = FxHashSet::default();
+        for &name in groups.keys() {
+            self.register_config_group(sess, path, name, &groups, &mut visited, &mut vec![]);
+        }
+    }
+
+    /// Registers the custom lint group `name` of the lint configuration file,
+    /// after the custom lint groups it contains, which may be defined after it
+    /// in the file. `stack` holds the groups being registered, which contain
+    /// `name`.
+    fn register_config_group<'c>(
+        &mut self,
+        sess: &Session,
+        path: &Path,
+        name: &'c str,
+        groups: &FxIndexMap<&'c str, &'c [String]>,
+        visited: &mut FxHashSet<&'c str>,
+        stack: &mut Vec<&'c str>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+        if self.by_name.contains_key(name) || self.lint_groups.contains_key(name) {
+            sess.err(&format!(
+                "lint group `{}` of the lint configuration file `{}` is already a lint \
+                 or a lint group",
+                name,
+                path.display()
+            ));
+            return;
+        }
+
+        stack.push(name);
+        let lint_names: &'c [String] = groups[name];
+        let mut lint_ids = vec![];
+        for lint_name in lint_names {
+            if let Some((_, &lint_name, _)) = groups.get_full(&lint_name[..]) {
+                if stack.contains(&lint_name) {
+                    sess.err(&format!(
+                        "lint group `{}` of the lint configuration file `{}` contains itself",
+                        lint_name,
+                        path.display()
+                    ));
+                    continue;
+                }
+                self.register_config_group(sess, path, lint_name, groups, visited, stack);
+            }
+            self.check_lint_name_config(sess, lint_name, path);
+            for id in self.find_lints(lint_name).unwrap_or_default() {
+                if !lint_ids.contains(&id) {
+                    lint_ids.push(id);
+                }
+            }
+        }
+        stack.pop();
+
+        self.lint_groups.insert(
+            name.to_string().into(),
+            LintGroup { lint_ids, from_plugin: false, depr: None },
+        );
+    }
+
+    pub fn is_lint_group(&self, lint_name: &str) -> bool {
+        self.lint_groups.contains_key(lint_name)
+    }
+
     pub fn register_renamed(&mut self, old_name: &str, new_name: &str) {
         let target = match self.by_name.get(new_name) {
             Some(&Id(lint_id)) => lint_id,
@@ -276,7 +360,39 @@ impl LintStore {
 
     /// Checks the validity of lint names derived from the command line
     pub fn check_lint_name_cmdline(&self, sess: &Session, lint_name: &str, level: Level) {
-        let db = match self.check_lint_name(lint_name, None) {
+        if let Some(mut db) = self.lint_name_diagnostic(sess, lint_name) {
+            let msg = format!(
+                "requested on the command line with `{} {}`",
+                match level {
+                    Level::Allow => "-A",
+                    Level::Warn => "-W",
+                    Level::Deny => "-D",
+                    Level::Forbid => "-F",
+                },
+                lint_name
+            );
+            db.note(&msg);
+            db.emit();
+        }
+    }
+
+    /// Checks the validity of lint names derived from the lint configuration
+    /// file at `path`.
+    pub fn check_lint_name_config(&self, sess: &Session, lint_name: &str, path: &Path) {
+        if let Some(mut db) = self.lint_name_diagnostic(sess, lint_name) {
+            db.note(&format!("requested by the lint configuration file `{}`", path.display()));
+            db.emit();
+        }
+    }
+
+    /// Returns the diagnostic for a lint name from outside of the crate that
+    /// does not exist, or that was renamed or removed.
+    fn lint_name_diagnostic<'s>(
+        &self,
+        sess: &'s Session,
+        lint_name: &str,
+    ) -> Option<DiagnosticBuilder<'s>> {
+        match self.check_lint_name(lint_name, None) {
             CheckLintNameResult::Ok(_) => None,
             CheckLintNameResult::Warning(ref msg, _) => Some(sess.struct_warn(msg)),
             CheckLintNameResult::NoLint(suggestion) => {
@@ -298,21 +414,6 @@ impl LintStore {
                 ))),
                 _ => None,
             },
-        };
-
-        if let Some(mut db) = db {
-            let msg = format!(
-                "requested on the command line with `{} {}`",
-                match level {
-                    Level::Allow => "-A",
-                    Level::Warn => "-W",
-                    Level::Deny => "-D",
-                    Level::Forbid => "-F",
-                },
-                lint_name
-            );
-            db.note(&msg);
-            db.emit();
         }
     }
 
@@ -364,7 +465,7 @@ impl LintStore {
                 Some(LintGroup { lint_ids, depr, .. }) => {
                     // Check if the lint group name is deprecated
                     if let Some(LintAlias { name, silent }) = depr {
-                        let LintGroup { lint_ids, .. } = self.lint_groups.get(name).unwrap();
+                        let LintGroup { lint_ids, .. } = self.lint_groups.get(*name).unwrap();
                         return if *silent {
                             CheckLintNameResult::Ok(&lint_ids)
                         } else {
@@ -399,7 +500,7 @@ impl LintStore {
                 Some(LintGroup { lint_ids, depr, .. }) => {
                     // Reaching this would be weird, but let's cover this case anyway
                     if let Some(LintAlias { name, silent }) = depr {
-                        let LintGroup { lint_ids, .. } = self.lint_groups.get(name).unwrap();
+                        let LintGroup { lint_ids, .. } = self.lint_groups.get(*name).unwrap();
                         return if *silent {
                             CheckLintNameResult::Tool(Err((Some(&lint_ids), complete_name)))
                         } else {
@@ -14,11 +14,12 @@ use rustc_errors::{struct_span_err, Applicability};
 use rustc_hir as hir;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_hir::{intravisit, HirId};
-use rustc_session::lint::{builtin, Level, Lint};
+use rustc_session::lint::{builtin, Level, Lint, LintId};
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::source_map::MultiSpan;
 use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
 
 use std::cmp;
 
@@ -71,6 +72,33 @@ impl<'s> LintLevelsBuilder<'s> {
         let mut specs = FxHashMap::default();
         self.sets.lint_cap = sess.opts.lint_cap.unwrap_or(Level::Forbid);
 
+        // The levels of the lint configuration file apply first, so that the
+        // flags override them, and the levels of lint groups apply before
+        // those of single lints.
+        if let Some(path) = &sess.opts.debugging_opts.lint_config {
+            let (groups, lints): (Vec<_>, Vec<_>) = sess
+                .opts
+                .lint_config
+                .levels
+                .iter()
+                .partition(|(lint_name, _)| store.is_lint_group(lint_name));
+            for &(ref lint_name, level) in groups.into_iter().chain(lints) {
+                store.check_lint_name_config(sess, &lint_name, path);
+
+                let level = cmp::min(level, self.sets.lint_cap);
+                let ids = match store.find_lints(&lint_name) {
+                    Ok(ids) => ids,
+                    Err(_) => continue,
+                };
+                for id in ids {
+                    if self.check_gated_lint(id, None) {
+                        let src = LintSource::ConfigFile(Symbol::intern(lint_name));
+                        specs.insert(id, (level, src));
+                    }
+                }
+            }
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             store.check_lint_name_cmdline(sess, &lint_name, level);
 
@@ -85,14 +113,41 @@ impl<'s> LintLevelsBuilder<'s> {
                 Err(_) => continue, // errors handled in check_lint_name_cmdline above
             };
             for id in ids {
-                let src = LintSource::CommandLine(lint_flag_val);
-                specs.insert(id, (level, src));
+                if self.check_gated_lint(id, None) {
+                    let src = LintSource::CommandLine(lint_flag_val);
+                    specs.insert(id, (level, src));
+                }
             }
         }
 
         self.sets.list.push(LintSet::CommandLine { specs });
     }
 
+    /// Returns whether the level of `lint_id` can be set, that is, whether the
+    /// lint is not unstable or its feature is enabled, and reports an error if
+    /// it cannot be. `span` is the span of the lint attribute, or `None` for
+    /// the command line.
+    fn check_gated_lint(&self, lint_id: LintId, span: Option<Span>) -> bool {
+        let feature = match lint_id.lint.feature_gate {
+            Some(feature) if !self.sess.features_untracked().enabled(feature) => feature,
+            _ => return true,
+        };
+        // The attributes are checked again for the late lints, without the
+        // warnings about them.
+        if self.warn_about_weird_lints {
+            let msg = format!("the `{}` lint is unstable", lint_id.lint.name_lower());
+            match span {
+                Some(span) => feature_err(&self.sess.parse_sess, feature, span, &msg).emit(),
+                None => {
+                    let help =
+                        format!("add `#![feature({})]` to the crate attributes to enable", feature);
+                    self.sess.struct_err(&msg).help(&help).emit()
+                }
+            }
+        }
+        false
+    }
+
     /// Pushes a list of AST lint attributes onto this context.
     ///
     /// This function will return a `BuilderPush` object which should be passed
@@ -213,7 +268,9 @@ impl<'s> LintLevelsBuilder<'s> {
                     CheckLintNameResult::Ok(ids) => {
                         let src = LintSource::Node(name, li.span(), reason);
                         for id in ids {
-                            specs.insert(*id, (level, src));
+                            if self.check_gated_lint(*id, Some(li.span())) {
+                                specs.insert(*id, (level, src));
+                            }
                         }
                     }
 
@@ -341,7 +398,7 @@ impl<'s> LintLevelsBuilder<'s> {
             let forbidden_lint_name = match forbid_src {
                 LintSource::Default => id.to_string(),
                 LintSource::Node(name, _, _) => name.to_string(),
-                LintSource::CommandLine(name) => name.to_string(),
+                LintSource::CommandLine(name) | LintSource::ConfigFile(name) => name.to_string(),
             };
             let (lint_attr_name, lint_attr_span) = match *src {
                 LintSource::Node(name, span, _) => (name, span),
@@ -368,6 +425,9 @@ impl<'s> LintLevelsBuilder<'s> {
                 LintSource::CommandLine(_) => {
                     diag_builder.note("`forbid` lint level was set on command line");
                 }
+                LintSource::ConfigFile(_) => {
+                    diag_builder.note("`forbid` lint level was set in the lint configuration file");
+                }
             }
             diag_builder.emit();
             // don't set a separate error for every lint in the group
@@ -111,7 +111,7 @@ macro_rules! early_lint_passes {
                 NonCamelCaseTypes: NonCamelCaseTypes,
                 DeprecatedAttr: DeprecatedAttr::new(),
                 WhileTrue: WhileTrue,
-                NonAsciiIdents: NonAsciiIdents,
+                NonAsciiIdents: NonAsciiIdents::default(),
                 IncompleteFeatures: IncompleteFeatures,
                 RedundantSemicolons: RedundantSemicolons,
                 UnusedDocComment: UnusedDocComment,
@@ -170,6 +170,7 @@ macro_rules! late_lint_mod_passes {
                 NonUpperCaseGlobals: NonUpperCaseGlobals,
                 NonShorthandFieldPatterns: NonShorthandFieldPatterns,
                 UnusedAllocation: UnusedAllocation,
+                LetUnderscore: LetUnderscore,
                 // Depends on types used in type definitions
                 MissingCopyImplementations: MissingCopyImplementations,
                 // Depends on referenced function signatures in expressions
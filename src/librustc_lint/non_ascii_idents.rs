@@ -1,5 +1,8 @@
 use crate::{EarlyContext, EarlyLintPass, LintContext};
 use rustc_ast::ast;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_span::symbol::Symbol;
+use rustc_span::Span;
 
 declare_lint! {
     pub NON_ASCII_IDENTS,
@@ -13,15 +16,56 @@ declare_lint! {
     "detects uncommon Unicode codepoints in identifiers"
 }
 
-declare_lint_pass!(NonAsciiIdents => [NON_ASCII_IDENTS, UNCOMMON_CODEPOINTS]);
+declare_lint! {
+    pub MIXED_SCRIPT_IDENTS,
+    Warn,
+    "detects identifiers that mix the characters of several scripts"
+}
+
+declare_lint! {
+    pub CONFUSABLE_IDENTS,
+    Warn,
+    "detects visually confusable pairs of identifiers written in the same script"
+}
+
+declare_lint! {
+    pub MIXED_SCRIPT_CONFUSABLES,
+    Warn,
+    "detects visually confusable pairs of identifiers, one of which mixes several scripts"
+}
+
+declare_lint! {
+    pub WHOLE_SCRIPT_CONFUSABLES,
+    Warn,
+    "detects visually confusable pairs of identifiers written in different scripts"
+}
+
+#[derive(Default)]
+pub struct NonAsciiIdents {
+    /// The identifiers of the crate with the span they first occur at,
+    /// for the checks that compare them to each other.
+    idents: FxIndexMap<Symbol, Span>,
+    has_non_ascii_idents: bool,
+}
+
+impl_lint_pass!(NonAsciiIdents => [
+    NON_ASCII_IDENTS,
+    UNCOMMON_CODEPOINTS,
+    MIXED_SCRIPT_IDENTS,
+    CONFUSABLE_IDENTS,
+    MIXED_SCRIPT_CONFUSABLES,
+    WHOLE_SCRIPT_CONFUSABLES,
+]);
 
 impl EarlyLintPass for NonAsciiIdents {
     fn check_ident(&mut self, cx: &EarlyContext<'_>, ident: ast::Ident) {
-        use unicode_security::GeneralSecurityProfile;
+        use unicode_security::{GeneralSecurityProfile, MixedScript};
+        self.idents.entry(ident.name).or_insert(ident.span);
         let name_str = ident.name.as_str();
         if name_str.is_ascii() {
             return;
         }
+        self.has_non_ascii_idents = true;
         cx.struct_span_lint(NON_ASCII_IDENTS, ident.span, |lint| {
             lint.build("identifier contains non-ASCII characters").emit()
         });
@@ -30,5 +74,81 @@ impl EarlyLintPass for NonAsciiIdents {
                 lint.build("identifier contains uncommon Unicode codepoints").emit()
             })
         }
+        // Mixed-script detection of UTS #39: the characters of a single-script
+        // identifier, once the characters common to all scripts are left out,
+        // all belong to one script, like Latin or Cyrillic, or to one writing
+        // system, like Japanese.
+        if !name_str.is_single_script() {
+            cx.struct_span_lint(MIXED_SCRIPT_IDENTS, ident.span, |lint| {
+                lint.build("identifier contains characters of several scripts")
+                    .note(
+                        "characters of different scripts can look alike, so that the identifier \
+                         can be confused with another",
+                    )
+                    .emit()
+            })
+        }
+    }
+
+    fn check_crate_post(&mut self, cx: &EarlyContext<'_>, _: &ast::Crate) {
+        use std::collections::hash_map::Entry;
+        use unicode_security::{skeleton, MixedScript};
+
+        // Identifiers that are all ASCII are not reported as confusable with
+        // each other, like `l` and `I`, so there is nothing to check in crates
+        // without any other identifiers.
+        if !self.has_non_ascii_idents {
+            return;
+        }
+
+        // Confusable detection of UTS #39: two identifiers are confusable if
+        // they have the same skeleton, which maps every character to the
+        // prototype of the characters it can be confused with. They are
+        // single-script confusables if both are written in the same script,
+        // whole-script confusables if each is written in a single script but
+        // not the same one, and mixed-script confusables otherwise.
+        let mut skeletons: FxHashMap<String, (Symbol, Span)> = FxHashMap::default();
+        for (&name, &span) in &self.idents {
+            let name_str = name.as_str();
+            match skeletons.entry(skeleton(&name_str).collect()) {
+                Entry::Vacant(entry) => {
+                    entry.insert((name, span));
+                }
+                Entry::Occupied(entry) => {
+                    let (existing, existing_span) = *entry.get();
+                    let existing_str = existing.as_str();
+                    if existing_str.is_ascii() && name_str.is_ascii() {
+                        continue;
+                    }
+                    let (mut common_set, set) =
+                        (existing_str.resolve_script_set(), name_str.resolve_script_set());
+                    let (confusable_lint, note) = if common_set.is_empty() || set.is_empty() {
+                        (MIXED_SCRIPT_CONFUSABLES, Some("the identifiers mix different scripts"))
+                    } else {
+                        common_set.intersect_with(set);
+                        if common_set.is_empty() {
+                            let note = "the identifiers are written in different scripts";
+                            (WHOLE_SCRIPT_CONFUSABLES, Some(note))
+                        } else {
+                            (CONFUSABLE_IDENTS, None)
+                        }
+                    };
+                    cx.struct_span_lint(confusable_lint, span, |lint| {
+                        let mut err = lint.build(&format!(
+                            "identifier pair considered confusable between `{}` and `{}`",
+                            existing, name
+                        ));
+                        err.span_label(
+                            existing_span,
+                            "this is where the previous identifier occurred",
+                        );
+                        if let Some(note) = note {
+                            err.note(note);
+                        }
+                        err.emit()
+                    });
+                }
+            }
+        }
     }
 }
@@ -1,4 +1,5 @@
 use crate::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintContext};
+use rustc::lint::LintDiagnosticBuilder;
 use rustc::ty::adjustment;
 use rustc::ty::{self, Ty};
 use rustc_ast::ast;
@@ -6,15 +7,17 @@ use rustc_ast::attr;
 use rustc_ast::util::parser;
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_errors::{pluralize, Applicability};
+use rustc_errors::{pluralize, Applicability, DiagnosticBuilder};
 use rustc_feature::{AttributeType, BuiltinAttribute, BUILTIN_ATTRIBUTE_MAP};
 use rustc_hir as hir;
 use rustc_hir::def::{DefKind, Res};
 use rustc_hir::def_id::DefId;
+use rustc_infer::infer::TyCtxtInferExt;
 use rustc_session::lint::builtin::UNUSED_ATTRIBUTES;
 use rustc_span::symbol::Symbol;
 use rustc_span::symbol::{kw, sym};
 use rustc_span::{BytePos, Span};
+use rustc_trait_selection::traits;
 
 use log::debug;
 
@@ -694,3 +697,136 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedAllocation {
         }
     }
 }
+
+declare_lint! {
+    pub LET_UNDERSCORE_LOCK,
+    Warn,
+    "non-binding let on a synchronization lock"
+}
+
+declare_lint! {
+    pub LET_UNDERSCORE_FUTURE,
+    Warn,
+    "non-binding let on an `impl Future` or a `#[must_use]` future"
+}
+
+declare_lint_pass!(LetUnderscore => [LET_UNDERSCORE_LOCK, LET_UNDERSCORE_FUTURE]);
+
+/// The diagnostic items of the lock guards of `std::sync`.
+const SYNC_GUARD_SYMBOLS: [Symbol; 3] =
+    [sym::MutexGuard, sym::RwLockReadGuard, sym::RwLockWriteGuard];
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for LetUnderscore {
+    fn check_local(&mut self, cx: &LateContext<'a, 'tcx>, local: &'tcx hir::Local<'tcx>) {
+        // Unlike `let _x = expr;`, `let _ = expr;` doesn't bind the value of `expr`,
+        // which is dropped at the end of the statement, unless `expr` is a place:
+        // `let _ = guard;` neither moves nor drops `guard`.
+        let init = match (local.source, &local.pat.kind, local.init) {
+            (hir::LocalSource::Normal, hir::PatKind::Wild, Some(init))
+                if !init.is_place_expr(|_| false) =>
+            {
+                init
+            }
+            _ => return,
+        };
+
+        let init_ty = cx.tables.expr_ty(init);
+        if owns_sync_guard(cx, init_ty) {
+            cx.struct_span_lint(LET_UNDERSCORE_LOCK, local.pat.span, |lint| {
+                build_let_underscore_lint(
+                    lint,
+                    local,
+                    init,
+                    "non-binding let on a synchronization lock",
+                    "this lock is not assigned to a binding and is immediately dropped",
+                )
+                .emit()
+            });
+            return;
+        }
+
+        // Dropping other futures can be meaningful, like detaching a task by
+        // dropping its handle, so only the futures that must be used are linted.
+        let is_must_use_future = is_must_use_future_ty(cx, init_ty)
+            && cx.tcx.lang_items().future_trait().map_or(false, |future_trait| {
+                cx.tcx.infer_ctxt().enter(|infcx| {
+                    traits::type_known_to_meet_bound_modulo_regions(
+                        &infcx,
+                        cx.param_env,
+                        init_ty,
+                        future_trait,
+                        init.span,
+                    )
+                })
+            });
+        if is_must_use_future {
+            cx.struct_span_lint(LET_UNDERSCORE_FUTURE, local.pat.span, |lint| {
+                build_let_underscore_lint(
+                    lint,
+                    local,
+                    init,
+                    "non-binding let on a future",
+                    "this future is not assigned to a binding and is immediately dropped",
+                )
+                .note("futures do nothing unless you `.await` or poll them")
+                .emit()
+            });
+        }
+    }
+}
+
+/// Returns whether the value of type `ty` owns a lock guard of `std::sync`,
+/// itself or in an `Option`, a `Result` or a tuple, rather than borrowing it.
+fn owns_sync_guard(cx: &LateContext<'_, '_>, ty: Ty<'_>) -> bool {
+    match ty.kind {
+        ty::Adt(def, substs) => {
+            let is_item = |name| cx.tcx.is_diagnostic_item(name, def.did);
+            if SYNC_GUARD_SYMBOLS.iter().any(|&name| is_item(name)) {
+                true
+            } else if is_item(sym::option_type) || is_item(sym::result_type) {
+                substs.types().any(|ty| owns_sync_guard(cx, ty))
+            } else {
+                false
+            }
+        }
+        ty::Tuple(..) => ty.tuple_fields().any(|ty| owns_sync_guard(cx, ty)),
+        _ => false,
+    }
+}
+
+/// Returns whether `ty` is an `impl Future`, like the result of an `async fn`,
+/// or a `#[must_use]` type, the futures that are a mistake to drop unpolled.
+fn is_must_use_future_ty(cx: &LateContext<'_, '_>, ty: Ty<'_>) -> bool {
+    match ty.kind {
+        ty::Opaque(..) => true,
+        ty::Adt(def, _) => attr::contains_name(&cx.tcx.get_attrs(def.did), sym::must_use),
+        _ => false,
+    }
+}
+
+fn build_let_underscore_lint<'a>(
+    lint: LintDiagnosticBuilder<'a>,
+    local: &hir::Local<'_>,
+    init: &hir::Expr<'_>,
+    msg: &str,
+    label: &str,
+) -> DiagnosticBuilder<'a> {
+    let mut err = lint.build(msg);
+    err.span_label(local.pat.span, label);
+    err.span_label(init.span, "this binding will immediately drop the value assigned to it");
+    err.span_suggestion(
+        local.pat.span,
+        "consider binding to an unused variable to avoid immediately dropping the value",
+        "_unused".to_string(),
+        Applicability::MaybeIncorrect,
+    );
+    err.multipart_suggestion(
+        "consider immediately dropping the value",
+        vec![
+            (local.span.until(init.span), "drop(".to_string()),
+            (init.span.shrink_to_hi(), ")".to_string()),
+        ],
+        Applicability::MaybeIncorrect,
+    );
+    err
+}
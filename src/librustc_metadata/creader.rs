@@ -684,7 +684,10 @@ impl<'a> CrateLoader<'a> {
     }
 
     fn inject_profiler_runtime(&mut self) {
-        if self.sess.opts.debugging_opts.profile || self.sess.opts.cg.profile_generate.enabled() {
+        if self.sess.opts.debugging_opts.profile
+            || self.sess.opts.debugging_opts.instrument_coverage
+            || self.sess.opts.cg.profile_generate.enabled()
+        {
             info!("loading profiler");
 
             let name = Symbol::intern("profiler_builtins");
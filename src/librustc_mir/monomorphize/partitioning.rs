@@ -91,9 +91,24 @@
 //! Note though that as a side-effect of creating a codegen units per
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
+//!
+//! A Note on Codegen Unit Costs
+//! ----------------------------
+//! When the number of codegen units is fixed, the per-module codegen units
+//! are merged until that number is reached. Since the codegen units are
+//! optimized by LLVM in parallel, the time this takes is bounded by the most
+//! costly of them, so the merging tries to balance their costs rather than
+//! their number of items. The cost of an item is estimated from the size of
+//! its MIR, and the cost of a codegen unit includes the copies of the
+//! `#[inline]` items it will get, as LLVM has to optimize those too.
+//! `-Z cgu-cost-report` writes the final estimates out, which helps with
+//! picking a value for `-C codegen-units`.
 
 use std::cmp;
 use std::collections::hash_map::Entry;
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::sync::Arc;
 
 use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
@@ -107,6 +122,7 @@ use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdSet, CRATE_DEF_INDEX, LOCAL_CRATE};
+use rustc_serialize::json::as_pretty_json;
 use rustc_span::symbol::Symbol;
 
 use crate::monomorphize::collector::InliningMap;
@@ -152,7 +168,7 @@ where
     // until that count is reached.
     if let PartitioningStrategy::FixedUnitCount(count) = strategy {
         let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
-        merge_codegen_units(tcx, &mut initial_partitioning, count);
+        merge_codegen_units(tcx, &mut initial_partitioning, inlining_map, count);
         debug_dump(tcx, "POST MERGING:", initial_partitioning.codegen_units.iter());
     }
 
@@ -464,38 +480,46 @@ fn default_visibility(tcx: TyCtxt<'_>, id: DefId, is_generic: bool) -> Visibilit
 fn merge_codegen_units<'tcx>(
     tcx: TyCtxt<'tcx>,
     initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    inlining_map: &InliningMap<'tcx>,
     target_cgu_count: usize,
 ) {
     assert!(target_cgu_count >= 1);
     let codegen_units = &mut initial_partitioning.codegen_units;
 
+    // Each codegen unit will also get a copy of the `#[inline]` items its
+    // items refer to, which LLVM has to optimize as well, so account for them
+    // in the cost of the codegen unit. Merging codegen units that refer to the
+    // same inline items overestimates the cost of the result, but that is
+    // corrected once the inline items are actually placed.
+    for cgu in codegen_units.iter_mut() {
+        let inlined_size = estimate_inlined_size(tcx, cgu, inlining_map);
+        cgu.modify_size_estimate(inlined_size);
+    }
+
     // Note that at this point in time the `codegen_units` here may not be in a
     // deterministic order (but we know they're deterministically the same set).
     // We want this merging to produce a deterministic ordering of codegen units
     // from the input.
     //
-    // Due to basically how we've implemented the merging below (merge the two
-    // smallest into each other) we're sure to start off with a deterministic
-    // order (sorted by name). This'll mean that if two cgus have the same size
-    // the stable sort below will keep everything nice and deterministic.
+    // So we start off with a deterministic order (sorted by name), which the
+    // stable sort by cost below keeps for codegen units of the same cost.
     codegen_units.sort_by_cached_key(|cgu| cgu.name().as_str());
-
-    // Merge the two smallest codegen units until the target size is reached.
-    while codegen_units.len() > target_cgu_count {
-        // Sort small cgus to the back
-        codegen_units.sort_by_cached_key(|cgu| cmp::Reverse(cgu.size_estimate()));
-        let mut smallest = codegen_units.pop().unwrap();
-        let second_smallest = codegen_units.last_mut().unwrap();
-
-        second_smallest.modify_size_estimate(smallest.size_estimate());
-        for (k, v) in smallest.items_mut().drain() {
-            second_smallest.items_mut().insert(k, v);
+    codegen_units.sort_by_key(|cgu| cmp::Reverse(cgu.size_estimate()));
+
+    // Keep the most costly codegen units, and merge each of the others, from
+    // the most to the least costly, into the kept codegen unit that is the
+    // cheapest at that point. As opposed to repeatedly merging the two
+    // cheapest codegen units, this doesn't let one of them grow far beyond the
+    // others, which would then dominate the time spent in LLVM.
+    let rest = codegen_units.split_off(cmp::min(target_cgu_count, codegen_units.len()));
+    for mut cgu in rest {
+        let cheapest = codegen_units.iter_mut().min_by_key(|cgu| cgu.size_estimate()).unwrap();
+
+        cheapest.modify_size_estimate(cgu.size_estimate());
+        for (k, v) in cgu.items_mut().drain() {
+            cheapest.items_mut().insert(k, v);
         }
-        debug!(
-            "CodegenUnit {} merged in to CodegenUnit {}",
-            smallest.name(),
-            second_smallest.name()
-        );
+        debug!("CodegenUnit {} merged in to CodegenUnit {}", cgu.name(), cheapest.name());
     }
 
     let cgu_name_builder = &mut CodegenUnitNameBuilder::new(tcx);
@@ -504,6 +528,24 @@ fn merge_codegen_units<'tcx>(
     }
 }
 
+/// Estimates the size of the `#[inline]` items that `cgu` will get a copy of.
+fn estimate_inlined_size<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    cgu: &CodegenUnit<'tcx>,
+    inlining_map: &InliningMap<'tcx>,
+) -> usize {
+    let mut reachable = FxHashSet::default();
+    for &root in cgu.items().keys() {
+        follow_inlining(root, inlining_map, &mut reachable);
+    }
+
+    reachable
+        .iter()
+        .filter(|item| !cgu.contains_item(item))
+        .map(|item| item.size_estimate(tcx))
+        .sum()
+}
+
 fn place_inlined_mono_items<'tcx>(
     initial_partitioning: PreInliningPartitioning<'tcx>,
     inlining_map: &InliningMap<'tcx>,
@@ -571,25 +613,25 @@ fn place_inlined_mono_items<'tcx>(
         new_partitioning.push(new_codegen_unit);
     }
 
-    return PostInliningPartitioning {
+    PostInliningPartitioning {
         codegen_units: new_partitioning,
         mono_item_placements,
         internalization_candidates,
-    };
-
-    fn follow_inlining<'tcx>(
-        mono_item: MonoItem<'tcx>,
-        inlining_map: &InliningMap<'tcx>,
-        visited: &mut FxHashSet<MonoItem<'tcx>>,
-    ) {
-        if !visited.insert(mono_item) {
-            return;
-        }
+    }
+}
 
-        inlining_map.with_inlining_candidates(mono_item, |target| {
-            follow_inlining(target, inlining_map, visited);
-        });
+fn follow_inlining<'tcx>(
+    mono_item: MonoItem<'tcx>,
+    inlining_map: &InliningMap<'tcx>,
+    visited: &mut FxHashSet<MonoItem<'tcx>>,
+) {
+    if !visited.insert(mono_item) {
+        return;
     }
+
+    inlining_map.with_inlining_candidates(mono_item, |target| {
+        follow_inlining(target, inlining_map, visited);
+    });
 }
 
 fn internalize_symbols<'tcx>(
@@ -795,6 +837,75 @@ where
     }
 }
 
+#[derive(RustcEncodable)]
+struct CguCostReport {
+    total_cost: usize,
+    max_cost: usize,
+    /// The cost of the most costly codegen unit relative to the mean cost.
+    imbalance: f64,
+    codegen_units: Vec<CguCost>,
+}
+
+#[derive(RustcEncodable)]
+struct CguCost {
+    name: String,
+    cost: usize,
+    items: usize,
+    /// The most costly items of the codegen unit.
+    largest_items: Vec<ItemCost>,
+}
+
+#[derive(RustcEncodable)]
+struct ItemCost {
+    item: String,
+    cost: usize,
+}
+
+/// Writes the estimated cost of each of `codegen_units` to `path`, as JSON,
+/// most costly first.
+fn write_cgu_cost_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[Arc<CodegenUnit<'tcx>>],
+    path: &Path,
+) -> io::Result<()> {
+    const LARGEST_ITEMS: usize = 10;
+
+    let mut cgu_costs: Vec<_> = codegen_units
+        .iter()
+        .map(|cgu| {
+            let mut largest_items: Vec<_> = cgu
+                .items()
+                .keys()
+                .map(|item| ItemCost {
+                    item: item.to_string(tcx, true),
+                    cost: item.size_estimate(tcx),
+                })
+                .collect();
+            largest_items.sort_by(|a, b| b.cost.cmp(&a.cost).then_with(|| a.item.cmp(&b.item)));
+            largest_items.truncate(LARGEST_ITEMS);
+
+            CguCost {
+                name: cgu.name().to_string(),
+                cost: cgu.size_estimate(),
+                items: cgu.items().len(),
+                largest_items,
+            }
+        })
+        .collect();
+    cgu_costs.sort_by(|a, b| b.cost.cmp(&a.cost).then_with(|| a.name.cmp(&b.name)));
+
+    let total_cost: usize = cgu_costs.iter().map(|cgu| cgu.cost).sum();
+    let max_cost = cgu_costs.first().map_or(0, |cgu| cgu.cost);
+    let imbalance = if total_cost == 0 {
+        1.0
+    } else {
+        max_cost as f64 * cgu_costs.len() as f64 / total_cost as f64
+    };
+
+    let report = CguCostReport { total_cost, max_cost, imbalance, codegen_units: cgu_costs };
+    fs::write(path, format!("{}\n", as_pretty_json(&report)))
+}
+
 #[inline(never)] // give this a place in the profiler
 fn assert_symbols_are_distinct<'a, 'tcx, I>(tcx: TyCtxt<'tcx>, mono_items: I)
 where
@@ -954,6 +1065,16 @@ fn collect_and_partition_mono_items(
         }
     }
 
+    if let Some(ref path) = tcx.sess.opts.debugging_opts.cgu_cost_report {
+        if let Err(err) = write_cgu_cost_report(tcx, &codegen_units, path) {
+            tcx.sess.err(&format!(
+                "failed to write codegen unit cost report to `{}`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+
     (Arc::new(mono_items), Arc::new(codegen_units))
 }
 
@@ -0,0 +1,332 @@
+//! Global value numbering.
+//!
+//! This pass assigns a value number to the rvalue defining each local that is assigned exactly
+//! once and never borrowed. When the same value has already been computed into such a local at a
+//! location that dominates the current one, the computation is replaced by a copy of that local.
+//!
+//! To give an example, we look for patterns that look like:
+//!
+//!     _3 = Len((*_1));
+//!     ...
+//!     _7 = Len((*_1));
+//!
+//! and replace them with:
+//!
+//!     _3 = Len((*_1));
+//!     ...
+//!     _7 = _3;
+//!
+//! Only side-effect free rvalues are numbered (`Len`, `Discriminant`, unary, binary and checked
+//! binary operations), and all of their operands must themselves be numbered locals or constants.
+//! Since none of the locals involved can be written to or borrowed after their definition, a value
+//! cannot change between the place it is computed and the place it is reused.
+//!
+//! A local whose value gets reused may now be read after its original last use, so its storage
+//! markers are removed and any moves out of it are turned into copies.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{
+    MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
+};
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+
+pub struct GlobalValueNumbering;
+
+impl<'tcx> MirPass<'tcx> for GlobalValueNumbering {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // Only run at `-Z mir-opt-level=2` and above until the pass has seen more use.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        debug!("running GlobalValueNumbering on {:?}", source);
+
+        let (replacements, reused) = {
+            let read_only = read_only!(body);
+            let dominators = read_only.dominators();
+            let ssa = SsaLocals::new(read_only);
+            let mut numbering = ValueNumbering::new(body);
+
+            for arg in body.args_iter() {
+                if ssa.defs[arg] == LocalDef::Argument {
+                    numbering.insert_opaque(arg);
+                }
+            }
+
+            let mut replacements = FxHashMap::default();
+            let mut reused = BitSet::new_empty(body.local_decls.len());
+            for (block, data) in traversal::reverse_postorder(body) {
+                for (statement_index, statement) in data.statements.iter().enumerate() {
+                    let location = Location { block, statement_index };
+                    let (local, rvalue) = match &statement.kind {
+                        StatementKind::Assign(box (place, rvalue)) => match place.as_local() {
+                            Some(local) if ssa.defs[local] == LocalDef::Once(location) => {
+                                (local, rvalue)
+                            }
+                            _ => continue,
+                        },
+                        _ => continue,
+                    };
+
+                    let value = match numbering.number_rvalue(rvalue) {
+                        Some(value) => value,
+                        None => {
+                            numbering.insert_opaque(local);
+                            continue;
+                        }
+                    };
+
+                    if let Rvalue::Use(_) = rvalue {
+                        // Copies and constants are as cheap as the copy we would replace them
+                        // with, so we only record them.
+                    } else if let Some(holder) =
+                        numbering.dominating_holder(value, location, &dominators)
+                    {
+                        debug!("replacing {:?} at {:?} with {:?}", rvalue, location, holder);
+                        replacements.insert(location, holder);
+                        reused.insert(holder);
+                    }
+
+                    numbering.assign(local, value, location);
+                }
+            }
+
+            (replacements, reused)
+        };
+
+        if replacements.is_empty() {
+            return;
+        }
+
+        MutVisitor::visit_body(&mut Replacer { tcx, replacements, reused }, body);
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum LocalDef {
+    /// The local is never assigned.
+    Unassigned,
+    /// The local is an argument that is never assigned or borrowed.
+    Argument,
+    /// The local is assigned by a single statement, and never borrowed.
+    Once(Location),
+    /// The local may hold different values over its lifetime.
+    Invalid,
+}
+
+/// Finds the locals whose value can never change once they have been defined.
+struct SsaLocals {
+    defs: IndexVec<Local, LocalDef>,
+}
+
+impl SsaLocals {
+    fn new(body: ReadOnlyBodyAndCache<'_, '_>) -> SsaLocals {
+        let defs = body
+            .local_decls
+            .indices()
+            .map(|local| match body.local_kind(local) {
+                LocalKind::Arg => LocalDef::Argument,
+                _ => LocalDef::Unassigned,
+            })
+            .collect();
+        let mut ssa = SsaLocals { defs };
+        ssa.visit_body(body);
+        ssa
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for SsaLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        match rvalue {
+            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place)
+                if place.projection.first() != Some(&ProjectionElem::Deref) =>
+            {
+                self.defs[place.local] = LocalDef::Invalid;
+            }
+            _ => {}
+        }
+
+        self.super_rvalue(rvalue, location)
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if place.projection.first() == Some(&ProjectionElem::Deref) {
+            // Whatever happens to the pointee, we only read the pointer held in the local.
+            let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy);
+            self.visit_local(&place.local, context, location);
+            self.visit_projection(place.local, &place.projection, context, location);
+        } else {
+            self.super_place(place, context, location)
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+        let def = &mut self.defs[local];
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store) => {
+                *def = match *def {
+                    LocalDef::Unassigned => LocalDef::Once(location),
+                    _ => LocalDef::Invalid,
+                };
+            }
+            PlaceContext::MutatingUse(_) => *def = LocalDef::Invalid,
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => {
+                *def = LocalDef::Invalid
+            }
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+}
+
+rustc_index::newtype_index! {
+    struct VnIndex {
+        DEBUG_FORMAT = "vn{}"
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Value<'tcx> {
+    /// A value we know nothing about, like an argument or the result of a call. Each local
+    /// defined this way gets a distinct value.
+    Opaque(Local),
+    Constant(&'tcx ty::Const<'tcx>),
+    /// The length of an array or slice, either held directly in a local or behind the pointer
+    /// held in a local.
+    Len { base: VnIndex, deref: bool },
+    Discriminant(VnIndex),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+}
+
+struct ValueNumbering<'tcx> {
+    values: IndexVec<VnIndex, Value<'tcx>>,
+    numbers: FxHashMap<Value<'tcx>, VnIndex>,
+    /// The value held by each local whose definition has been visited.
+    locals: IndexVec<Local, Option<VnIndex>>,
+    /// The locals holding each value, along with the location of their definition.
+    holders: FxHashMap<VnIndex, Vec<(Local, Location)>>,
+}
+
+impl<'tcx> ValueNumbering<'tcx> {
+    fn new(body: &Body<'tcx>) -> Self {
+        ValueNumbering {
+            values: IndexVec::new(),
+            numbers: FxHashMap::default(),
+            locals: IndexVec::from_elem(None, &body.local_decls),
+            holders: FxHashMap::default(),
+        }
+    }
+
+    fn insert(&mut self, value: Value<'tcx>) -> VnIndex {
+        let values = &mut self.values;
+        *self.numbers.entry(value).or_insert_with(|| values.push(value))
+    }
+
+    fn insert_opaque(&mut self, local: Local) {
+        self.locals[local] = Some(self.insert(Value::Opaque(local)));
+    }
+
+    fn assign(&mut self, local: Local, value: VnIndex, location: Location) {
+        self.locals[local] = Some(value);
+        self.holders.entry(value).or_default().push((local, location));
+    }
+
+    /// Returns a local holding `value` whose definition dominates `location`, if any.
+    fn dominating_holder(
+        &self,
+        value: VnIndex,
+        location: Location,
+        dominators: &Dominators<BasicBlock>,
+    ) -> Option<Local> {
+        self.holders
+            .get(&value)?
+            .iter()
+            .find(|&&(_, def)| def.dominates(location, dominators))
+            .map(|&(holder, _)| holder)
+    }
+
+    fn number_operand(&mut self, operand: &Operand<'tcx>) -> Option<VnIndex> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.locals[place.as_local()?],
+            Operand::Constant(constant) => Some(self.insert(Value::Constant(constant.literal))),
+        }
+    }
+
+    fn number_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> Option<VnIndex> {
+        let value = match *rvalue {
+            Rvalue::Use(ref operand) => return self.number_operand(operand),
+            Rvalue::Len(place) => {
+                let base = self.locals[place.local]?;
+                match &place.projection[..] {
+                    [] => Value::Len { base, deref: false },
+                    [ProjectionElem::Deref] => Value::Len { base, deref: true },
+                    _ => return None,
+                }
+            }
+            Rvalue::Discriminant(place) => Value::Discriminant(self.locals[place.as_local()?]?),
+            Rvalue::UnaryOp(op, ref operand) => Value::UnaryOp(op, self.number_operand(operand)?),
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                Value::BinaryOp(op, self.number_operand(lhs)?, self.number_operand(rhs)?)
+            }
+            Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                Value::CheckedBinaryOp(op, self.number_operand(lhs)?, self.number_operand(rhs)?)
+            }
+            Rvalue::Repeat(..)
+            | Rvalue::Ref(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::Cast(..)
+            | Rvalue::NullaryOp(..)
+            | Rvalue::Aggregate(..) => return None,
+        };
+        Some(self.insert(value))
+    }
+}
+
+struct Replacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    replacements: FxHashMap<Location, Local>,
+    reused: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if self.reused.contains(local) =>
+            {
+                statement.make_nop()
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
+        if let Some(&holder) = self.replacements.get(&location) {
+            *rvalue = Rvalue::Use(Operand::Copy(Place::from(holder)));
+        } else {
+            self.super_rvalue(rvalue, location)
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        match *operand {
+            Operand::Move(place) if self.reused.contains(place.local) => {
+                *operand = Operand::Copy(place);
+            }
+            _ => self.super_operand(operand, location),
+        }
+    }
+}
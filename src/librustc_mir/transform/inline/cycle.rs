@@ -0,0 +1,159 @@
+//! Call graph queries used by the inliner to avoid cycles.
+//!
+//! Inlining a local function requires its optimized MIR, which in turn runs the inliner on it.
+//! If the callee can (transitively) call back into the function we are optimizing, that would be
+//! a query cycle, so we walk the call graph of the callee before asking for its MIR. The walk only
+//! looks at MIR from before inlining, so it cannot itself cause a cycle.
+
+use rustc::mir::{Body, TerminatorKind};
+use rustc::ty::query::Providers;
+use rustc::ty::subst::SubstsRef;
+use rustc::ty::{self, Instance, InstanceDef, ParamEnv, TyCtxt};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::DefId;
+
+pub(crate) fn provide(providers: &mut Providers<'_>) {
+    *providers = Providers { mir_callgraph_reachable, mir_inliner_callees, ..*providers };
+}
+
+/// Returns `true` if `root`, which is going to be inlined into `target`, can call `target`.
+fn mir_callgraph_reachable<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    (root, target): (Instance<'tcx>, DefId),
+) -> bool {
+    debug!("mir_callgraph_reachable({:?}, {:?})", root, target);
+    assert_ne!(root.def_id(), target, "direct self recursion should be checked by the caller");
+    assert!(target.is_local());
+    match root.def {
+        InstanceDef::Item(_) => {}
+        _ => bug!("`mir_callgraph_reachable` called on a shim: {:?}", root),
+    }
+
+    let param_env = tcx.param_env(target).with_reveal_all();
+    let recursion_limit = *tcx.sess.recursion_limit.get();
+    let mut stack = Vec::new();
+    let mut seen = FxHashSet::default();
+    let mut recursion_depths = FxHashMap::default();
+    process(
+        tcx,
+        param_env,
+        root,
+        target,
+        &mut stack,
+        &mut seen,
+        &mut recursion_depths,
+        recursion_limit,
+    )
+}
+
+fn process<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    caller: Instance<'tcx>,
+    target: DefId,
+    stack: &mut Vec<Instance<'tcx>>,
+    seen: &mut FxHashSet<Instance<'tcx>>,
+    recursion_depths: &mut FxHashMap<DefId, usize>,
+    recursion_limit: usize,
+) -> bool {
+    debug!("process({:?}) stack={:?}", caller, stack);
+    for &(callee, substs) in tcx.mir_inliner_callees(caller.def) {
+        let substs = tcx.subst_and_normalize_erasing_regions(caller.substs, param_env, &substs);
+        let callee = match Instance::resolve(tcx, param_env, callee, substs) {
+            Some(callee) => callee,
+            None => continue,
+        };
+
+        if callee.def_id() == target {
+            return true;
+        }
+
+        match callee.def {
+            InstanceDef::Item(def_id) => {
+                // If there is no MIR available (either because it was not in metadata or because
+                // it has none, like an extern function), then inlining won't cause cycles here.
+                // Constructors are built as shims and never contain calls.
+                if !tcx.is_mir_available(def_id) || tcx.is_constructor(def_id) {
+                    continue;
+                }
+            }
+            // These have MIR, and if that MIR is inlined and substituted, further inlining could
+            // end up in a cycle.
+            InstanceDef::VtableShim(_)
+            | InstanceDef::ReifyShim(_)
+            | InstanceDef::FnPtrShim(..)
+            | InstanceDef::ClosureOnceShim { .. }
+            | InstanceDef::CloneShim(..) => {}
+            // Drop glue only calls `Drop` impls, which we cannot see without the substituted type;
+            // intrinsics and virtual calls are never inlined.
+            InstanceDef::DropGlue(..) | InstanceDef::Intrinsic(_) | InstanceDef::Virtual(..) => {
+                continue;
+            }
+        }
+
+        if seen.insert(callee) {
+            let depth = recursion_depths.entry(callee.def_id()).or_default();
+            // Polymorphic recursion can produce infinitely many instances; treat that as a cycle.
+            if *depth > recursion_limit {
+                return true;
+            }
+            *depth += 1;
+            stack.push(callee);
+            let found = process(
+                tcx,
+                param_env,
+                callee,
+                target,
+                stack,
+                seen,
+                recursion_depths,
+                recursion_limit,
+            );
+            stack.pop();
+            if found {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+fn mir_inliner_callees<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: InstanceDef<'tcx>,
+) -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+    let steal;
+    let guard;
+    let instance_mir;
+    let body: &Body<'tcx> = match instance {
+        // Local items have not been optimized yet, and optimizing them is what runs the inliner,
+        // so use the MIR from before any inlining.
+        InstanceDef::Item(def_id) if def_id.is_local() => {
+            steal = tcx.mir_validated(def_id).0;
+            guard = steal.borrow();
+            &guard
+        }
+        _ => {
+            instance_mir = tcx.instance_mir(instance);
+            &instance_mir
+        }
+    };
+
+    let mut seen = FxHashSet::default();
+    let mut calls = Vec::new();
+    for bb_data in body.basic_blocks() {
+        let terminator = bb_data.terminator();
+        if let TerminatorKind::Call { func, .. } = &terminator.kind {
+            let ty = func.ty(body, tcx);
+            let call = match ty.kind {
+                ty::FnDef(def_id, substs) => (def_id, substs),
+                _ => continue,
+            };
+            if seen.insert(call) {
+                calls.push(call);
+            }
+        }
+    }
+    tcx.arena.alloc_from_iter(calls)
+}
@@ -17,6 +17,8 @@ use crate::transform::{MirPass, MirSource};
 use std::collections::VecDeque;
 use std::iter;
 
+pub mod cycle;
+
 const DEFAULT_THRESHOLD: usize = 50;
 const HINT_THRESHOLD: usize = 100;
 
@@ -24,6 +26,7 @@ const INSTR_COST: usize = 5;
 const CALL_PENALTY: usize = 25;
 const LANDINGPAD_PENALTY: usize = 50;
 const RESUME_PENALTY: usize = 45;
+const UNRESOLVED_CALL_PENALTY: usize = 25;
 
 const UNKNOWN_SIZE_COST: usize = 10;
 
@@ -37,9 +40,26 @@ struct CallSite<'tcx> {
     location: SourceInfo,
 }
 
+/// Returns whether the inliner runs on this session: with an explicit `-Z inline-mir`, or
+/// otherwise from `-Z mir-opt-level=2`.
+pub fn is_enabled(tcx: TyCtxt<'_>) -> bool {
+    let opts = &tcx.sess.opts;
+    // Coverage counters are attributed to the function whose MIR contains them, so inlining a
+    // callee would count its regions as part of the caller.
+    if opts.debugging_opts.instrument_coverage {
+        return false;
+    }
+
+    if let Some(enabled) = opts.debugging_opts.inline_mir {
+        return enabled;
+    }
+
+    opts.debugging_opts.mir_opt_level >= 2
+}
+
 impl<'tcx> MirPass<'tcx> for Inline {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
+        if is_enabled(tcx) {
             Inliner { tcx, source }.run_pass(body);
         }
     }
@@ -52,17 +72,20 @@ struct Inliner<'tcx> {
 
 impl Inliner<'tcx> {
     fn run_pass(&self, caller_body: &mut BodyAndCache<'tcx>) {
-        // Keep a queue of callsites to try inlining on. We take
-        // advantage of the fact that queries detect cycles here to
-        // allow us to try and fetch the fully optimized MIR of a
-        // call; if it succeeds, we can inline it and we know that
-        // they do not call us.  Otherwise, we just don't try to
-        // inline.
+        // Keep a queue of callsites to try inlining on. Before fetching
+        // the optimized MIR of a local callee, we check through the
+        // `mir_callgraph_reachable` query that it cannot call back
+        // into us; otherwise optimizing it would need our own
+        // optimized MIR, which is a query cycle.
         //
         // We use a queue so that we inline "broadly" before we inline
         // in depth. It is unclear if this is the best heuristic,
         // really, but that's true of all the heuristics in this
         // file. =)
+        //
+        // Each callsite comes with the functions that were inlined to
+        // make it appear in our body, so that we never inline a
+        // function into a copy of itself.
 
         let mut callsites = VecDeque::new();
 
@@ -75,7 +98,7 @@ impl Inliner<'tcx> {
                 if let Some(callsite) =
                     self.get_valid_function_call(bb, bb_data, caller_body, param_env)
                 {
-                    callsites.push_back(callsite);
+                    callsites.push_back((callsite, vec![]));
                 }
             }
         } else {
@@ -87,33 +110,28 @@ impl Inliner<'tcx> {
 
         loop {
             local_change = false;
-            while let Some(callsite) = callsites.pop_front() {
+            while let Some((callsite, mut history)) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
                 if !self.tcx.is_mir_available(callsite.callee) {
                     debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
                     continue;
                 }
 
-                let self_node_id = self.tcx.hir().as_local_node_id(self.source.def_id()).unwrap();
-                let callee_node_id = self.tcx.hir().as_local_node_id(callsite.callee);
-
-                let callee_body = if let Some(callee_node_id) = callee_node_id {
-                    // Avoid a cycle here by only using `optimized_mir` only if we have
-                    // a lower node id than the callee. This ensures that the callee will
-                    // not inline us. This trick only works without incremental compilation.
-                    // So don't do it if that is enabled.
-                    if !self.tcx.dep_graph.is_fully_enabled()
-                        && self_node_id.as_u32() < callee_node_id.as_u32()
-                    {
-                        self.tcx.optimized_mir(callsite.callee)
-                    } else {
+                if callsite.callee.is_local() {
+                    let caller = self.source.def_id();
+                    if callsite.callee == caller {
+                        debug!("checking whether to inline callsite {:?} - recursive", callsite);
                         continue;
                     }
-                } else {
-                    // This cannot result in a cycle since the callee MIR is from another crate
-                    // and is already optimized.
-                    self.tcx.optimized_mir(callsite.callee)
-                };
+                    // If the callee can call us, optimizing it may require our own optimized
+                    // MIR, and inlining it would let us inline ourselves.
+                    let callee = Instance::new(callsite.callee, callsite.substs);
+                    if self.tcx.mir_callgraph_reachable((callee, caller)) {
+                        debug!("checking whether to inline callsite {:?} - cycle", callsite);
+                        continue;
+                    }
+                }
+                let callee_body = self.tcx.optimized_mir(callsite.callee);
 
                 let callee_body = if self.consider_optimizing(callsite, callee_body) {
                     self.tcx.subst_and_normalize_erasing_regions(
@@ -134,13 +152,15 @@ impl Inliner<'tcx> {
                 debug!("attempting to inline callsite {:?} - success", callsite);
 
                 // Add callsites from inlined function
+                history.push(callsite.callee);
                 for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated().skip(start) {
                     if let Some(new_callsite) =
                         self.get_valid_function_call(bb, bb_data, caller_body, param_env)
                     {
-                        // Don't inline the same function multiple times.
-                        if callsite.callee != new_callsite.callee {
-                            callsites.push_back(new_callsite);
+                        // Don't inline a function into a copy of itself, which would
+                        // never terminate for recursive functions.
+                        if !history.contains(&new_callsite.callee) {
+                            callsites.push_back((new_callsite, history.clone()));
                         }
                     }
                 }
@@ -180,16 +200,16 @@ impl Inliner<'tcx> {
             if let ty::FnDef(callee_def_id, substs) = op.ty(caller_body, self.tcx).kind {
                 let instance = Instance::resolve(self.tcx, param_env, callee_def_id, substs)?;
 
-                if let InstanceDef::Virtual(..) = instance.def {
-                    return None;
+                // Only items have optimized MIR to inline; shims are built on demand
+                // and virtual calls have no known callee.
+                if let InstanceDef::Item(_) = instance.def {
+                    return Some(CallSite {
+                        callee: instance.def_id(),
+                        substs: instance.substs,
+                        bb,
+                        location: terminator.source_info,
+                    });
                 }
-
-                return Some(CallSite {
-                    callee: instance.def_id(),
-                    substs: instance.substs,
-                    bb,
-                    location: terminator.source_info,
-                });
             }
         }
 
@@ -221,6 +241,18 @@ impl Inliner<'tcx> {
             return false;
         }
 
+        // Code compiled with some target features must not end up in a function compiled
+        // without them, and inlining the other way around would change how the callee is
+        // compiled, so only inline between functions with the same features.
+        let caller_features = &tcx.codegen_fn_attrs(self.source.def_id()).target_features;
+        let callee_features = &codegen_fn_attrs.target_features;
+        if !callee_features.iter().all(|feature| caller_features.contains(feature))
+            || !caller_features.iter().all(|feature| callee_features.contains(feature))
+        {
+            debug!("`#[target_feature]` mismatch - not inlining");
+            return false;
+        }
+
         // Avoid inlining functions marked as no_sanitize if sanitizer is enabled,
         // since instrumentation might be enabled and performed on the caller.
         match self.tcx.sess.opts.debugging_opts.sanitizer {
@@ -239,6 +271,24 @@ impl Inliner<'tcx> {
                     return false;
                 }
             }
+            Some(Sanitizer::Cfi) => {
+                if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NO_SANITIZE_CFI) {
+                    return false;
+                }
+            }
+            Some(Sanitizer::HwAddress) => {
+                if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NO_SANITIZE_HWADDRESS) {
+                    return false;
+                }
+            }
+            Some(Sanitizer::ShadowCallStack) => {
+                if codegen_fn_attrs
+                    .flags
+                    .contains(CodegenFnAttrFlags::NO_SANITIZE_SHADOW_CALL_STACK)
+                {
+                    return false;
+                }
+            }
             Some(Sanitizer::Leak) => {}
             None => {}
         }
@@ -266,7 +316,12 @@ impl Inliner<'tcx> {
             }
         }
 
-        let mut threshold = if hinted { HINT_THRESHOLD } else { DEFAULT_THRESHOLD };
+        let debugging_opts = &tcx.sess.opts.debugging_opts;
+        let mut threshold = if hinted {
+            debugging_opts.inline_mir_hint_threshold.unwrap_or(HINT_THRESHOLD)
+        } else {
+            debugging_opts.inline_mir_threshold.unwrap_or(DEFAULT_THRESHOLD)
+        };
 
         // Significantly lower the threshold for inlining cold functions
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
@@ -286,48 +341,25 @@ impl Inliner<'tcx> {
         let param_env = tcx.param_env(self.source.def_id());
 
         let mut first_block = true;
-        let mut cost = 0;
+        let mut checker = CostChecker {
+            tcx,
+            param_env,
+            callee_body,
+            substs: callsite.substs,
+            cost: 0,
+            work_list: vec![START_BLOCK],
+        };
 
         // Traverse the MIR manually so we can account for the effects of
         // inlining on the CFG.
-        let mut work_list = vec![START_BLOCK];
         let mut visited = BitSet::new_empty(callee_body.basic_blocks().len());
-        while let Some(bb) = work_list.pop() {
+        while let Some(bb) = checker.work_list.pop() {
             if !visited.insert(bb.index()) {
                 continue;
             }
             let blk = &callee_body.basic_blocks()[bb];
 
-            for stmt in &blk.statements {
-                // Don't count StorageLive/StorageDead in the inlining cost.
-                match stmt.kind {
-                    StatementKind::StorageLive(_)
-                    | StatementKind::StorageDead(_)
-                    | StatementKind::Nop => {}
-                    _ => cost += INSTR_COST,
-                }
-            }
-            let term = blk.terminator();
-            let mut is_drop = false;
-            match term.kind {
-                TerminatorKind::Drop { ref location, target, unwind }
-                | TerminatorKind::DropAndReplace { ref location, target, unwind, .. } => {
-                    is_drop = true;
-                    work_list.push(target);
-                    // If the location doesn't actually need dropping, treat it like
-                    // a regular goto.
-                    let ty = location.ty(callee_body, tcx).subst(tcx, callsite.substs).ty;
-                    if ty.needs_drop(tcx, param_env) {
-                        cost += CALL_PENALTY;
-                        if let Some(unwind) = unwind {
-                            cost += LANDINGPAD_PENALTY;
-                            work_list.push(unwind);
-                        }
-                    } else {
-                        cost += INSTR_COST;
-                    }
-                }
-
+            match blk.terminator().kind {
                 TerminatorKind::Unreachable | TerminatorKind::Call { destination: None, .. }
                     if first_block =>
                 {
@@ -335,59 +367,19 @@ impl Inliner<'tcx> {
                     // unless the cost is zero
                     threshold = 0;
                 }
-
-                TerminatorKind::Call { func: Operand::Constant(ref f), cleanup, .. } => {
-                    if let ty::FnDef(def_id, _) = f.literal.ty.kind {
-                        // Don't give intrinsics the extra penalty for calls
-                        let f = tcx.fn_sig(def_id);
-                        if f.abi() == Abi::RustIntrinsic || f.abi() == Abi::PlatformIntrinsic {
-                            cost += INSTR_COST;
-                        } else {
-                            cost += CALL_PENALTY;
-                        }
-                    } else {
-                        cost += CALL_PENALTY;
-                    }
-                    if cleanup.is_some() {
-                        cost += LANDINGPAD_PENALTY;
-                    }
-                }
-                TerminatorKind::Assert { cleanup, .. } => {
-                    cost += CALL_PENALTY;
-
-                    if cleanup.is_some() {
-                        cost += LANDINGPAD_PENALTY;
-                    }
-                }
-                TerminatorKind::Resume => cost += RESUME_PENALTY,
-                _ => cost += INSTR_COST,
-            }
-
-            if !is_drop {
-                for &succ in term.successors() {
-                    work_list.push(succ);
-                }
+                _ => {}
             }
 
+            checker.visit_basic_block_data(bb, blk);
             first_block = false;
         }
 
         // Count up the cost of local variables and temps, if we know the size
         // use that, otherwise we use a moderately-large dummy cost.
-
-        let ptr_size = tcx.data_layout.pointer_size.bytes();
-
         for v in callee_body.vars_and_temps_iter() {
-            let v = &callee_body.local_decls[v];
-            let ty = v.ty.subst(tcx, callsite.substs);
-            // Cost of the var is the size in machine-words, if we know
-            // it.
-            if let Some(size) = type_size_of(tcx, param_env, ty) {
-                cost += (size / ptr_size) as usize;
-            } else {
-                cost += UNKNOWN_SIZE_COST;
-            }
+            checker.visit_local_decl(v, &callee_body.local_decls[v]);
         }
+        let cost = checker.cost;
 
         if let attr::InlineAttr::Always = codegen_fn_attrs.inline {
             debug!("INLINING {:?} because inline(always) [cost={}]", callsite, cost);
@@ -634,6 +626,100 @@ impl Inliner<'tcx> {
     }
 }
 
+/// Computes the cost of inlining a callee body, as an estimate of the code it adds to the caller.
+///
+/// The blocks to visit are tracked in `work_list`, so that blocks which would become dead after
+/// inlining, like unwind paths of drops that are no-ops for the callsite's types, are not counted.
+struct CostChecker<'b, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    callee_body: &'b Body<'tcx>,
+    /// The substitutions of the callsite, which decide which drops and locals are costly.
+    substs: SubstsRef<'tcx>,
+    cost: usize,
+    work_list: Vec<BasicBlock>,
+}
+
+impl<'tcx> Visitor<'tcx> for CostChecker<'_, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, _: Location) {
+        // Don't count StorageLive/StorageDead in the inlining cost.
+        match statement.kind {
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) | StatementKind::Nop => {}
+            _ => self.cost += INSTR_COST,
+        }
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, _: Location) {
+        let tcx = self.tcx;
+        match terminator.kind {
+            TerminatorKind::Drop { ref location, target, unwind }
+            | TerminatorKind::DropAndReplace { ref location, target, unwind, .. } => {
+                self.work_list.push(target);
+                // If the location doesn't actually need dropping, treat it like
+                // a regular goto.
+                let ty = location.ty(self.callee_body, tcx).subst(tcx, self.substs).ty;
+                if ty.needs_drop(tcx, self.param_env) {
+                    self.cost += CALL_PENALTY;
+                    if let Some(unwind) = unwind {
+                        self.cost += LANDINGPAD_PENALTY;
+                        self.work_list.push(unwind);
+                    }
+                } else {
+                    self.cost += INSTR_COST;
+                }
+                // The successors have been handled above.
+                return;
+            }
+            TerminatorKind::Call { func: Operand::Constant(ref f), cleanup, .. } => {
+                if let ty::FnDef(def_id, substs) = f.literal.ty.subst(tcx, self.substs).kind {
+                    // Don't give intrinsics the extra penalty for calls
+                    let f = tcx.fn_sig(def_id);
+                    if f.abi() == Abi::RustIntrinsic || f.abi() == Abi::PlatformIntrinsic {
+                        self.cost += INSTR_COST;
+                    } else {
+                        self.cost += CALL_PENALTY;
+                        // A callee that is only known after monomorphization, like a method
+                        // of a type parameter, cannot be inlined in turn, so it stays a call
+                        // whatever it turns out to be.
+                        if Instance::resolve(tcx, self.param_env, def_id, substs).is_none() {
+                            self.cost += UNRESOLVED_CALL_PENALTY;
+                        }
+                    }
+                } else {
+                    self.cost += CALL_PENALTY;
+                }
+                if cleanup.is_some() {
+                    self.cost += LANDINGPAD_PENALTY;
+                }
+            }
+            TerminatorKind::Call { cleanup, .. } | TerminatorKind::Assert { cleanup, .. } => {
+                self.cost += CALL_PENALTY;
+                if cleanup.is_some() {
+                    self.cost += LANDINGPAD_PENALTY;
+                }
+            }
+            TerminatorKind::Resume => self.cost += RESUME_PENALTY,
+            _ => self.cost += INSTR_COST,
+        }
+
+        self.work_list.extend(terminator.successors());
+    }
+
+    /// Counts the size of the local in machine words if it is known, and a moderately large
+    /// dummy cost otherwise.
+    fn visit_local_decl(&mut self, _: Local, local_decl: &LocalDecl<'tcx>) {
+        let tcx = self.tcx;
+        let ptr_size = tcx.data_layout.pointer_size.bytes();
+
+        let ty = local_decl.ty.subst(tcx, self.substs);
+        if let Some(size) = type_size_of(tcx, self.param_env, ty) {
+            self.cost += (size / ptr_size) as usize;
+        } else {
+            self.cost += UNKNOWN_SIZE_COST;
+        }
+    }
+}
+
 fn type_size_of<'tcx>(
     tcx: TyCtxt<'tcx>,
     param_env: ty::ParamEnv<'tcx>,
@@ -0,0 +1,297 @@
+//! Inserts the code coverage counters used by `-Z instrument-coverage`.
+//!
+//! Each counter is a call to the `count_code_region` intrinsic, which codegen replaces with an
+//! `llvm.instrprof.increment` and records, together with the source region it counts, in the
+//! coverage map of the function. `llvm-cov` then combines the mapping with the counter values
+//! written by the profiler runtime into a region-accurate report.
+//!
+//! Counters are inserted:
+//!
+//! - at the start of the function, counting the whole function body;
+//! - on every edge out of a `SwitchInt` into a block that has no other predecessor, counting the
+//!   straight-line code that starts at that block. Each branch of an `if` or arm of a `match`
+//!   gets its own count this way, as do loop bodies.
+//!
+//! `llvm-cov` reports the innermost region containing a line, so code that is not covered by one
+//! of the branch regions (like the code after an `if`) is reported with the count of the function.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::middle::lang_items;
+use rustc::mir::*;
+use rustc::ty::query::Providers;
+use rustc::ty::{self, InstanceDef, ParamEnv, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hir::def_id::DefId;
+use rustc_index::vec::IndexVec;
+use rustc_span::Span;
+use std::hash::Hash;
+
+pub struct InstrumentCoverage;
+
+pub(crate) fn provide(providers: &mut Providers<'_>) {
+    providers.coverageinfo = coverageinfo_from_mir;
+}
+
+/// Summarizes the counters that `InstrumentCoverage` added to the optimized MIR of `instance`.
+fn coverageinfo_from_mir<'tcx>(tcx: TyCtxt<'tcx>, instance: InstanceDef<'tcx>) -> CoverageInfo {
+    let body = tcx.instance_mir(instance);
+    let count_code_region_fn = tcx.require_lang_item(lang_items::CountCodeRegionFnLangItem, None);
+
+    let mut info = CoverageInfo::default();
+    for data in body.basic_blocks() {
+        let (func, args) = match &data.terminator().kind {
+            TerminatorKind::Call { func: Operand::Constant(func), args, .. } => (func, args),
+            _ => continue,
+        };
+        match func.literal.ty.kind {
+            ty::FnDef(called_def_id, _) if called_def_id == count_code_region_fn => {}
+            _ => continue,
+        }
+
+        let eval = |arg: &Operand<'tcx>, ty| match arg {
+            Operand::Constant(constant) => {
+                constant.literal.try_eval_bits(tcx, ParamEnv::empty(), ty)
+            }
+            _ => None,
+        };
+        let (hash, index) = match (eval(&args[0], tcx.types.u64), eval(&args[1], tcx.types.u32)) {
+            (Some(hash), Some(index)) => (hash as u64, index as u32),
+            _ => bug!("non-constant arguments to `count_code_region` in {:?}", instance),
+        };
+        // Optimizations may have removed some counters, so the indices are not necessarily dense.
+        info.function_source_hash = hash;
+        info.num_counters = info.num_counters.max(index + 1);
+    }
+    info
+}
+
+impl<'tcx> MirPass<'tcx> for InstrumentCoverage {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.instrument_coverage {
+            return;
+        }
+
+        // Promoted constants and the bodies of `const` and `static` items are evaluated at
+        // compile time, and are not part of any function's coverage.
+        if source.promoted.is_some() {
+            return;
+        }
+        let hir_id = tcx.hir().as_local_hir_id(source.def_id()).unwrap();
+        if !tcx.hir().body_owner_kind(hir_id).is_fn_or_closure() {
+            return;
+        }
+
+        debug!("instrumenting {:?} for coverage", source);
+
+        let count_code_region_fn =
+            tcx.require_lang_item(lang_items::CountCodeRegionFnLangItem, Some(body.span));
+        let function_source_hash = hash_source(tcx, body.span);
+        let temp = body.local_decls.push(LocalDecl::new_temp(tcx.mk_unit(), body.span));
+        let mut instrumentor = Instrumentor {
+            tcx,
+            count_code_region_fn,
+            body_span: body.span,
+            function_source_hash,
+            temp,
+            num_counters: 0,
+        };
+
+        instrumentor.instrument_branches(body);
+        instrumentor.instrument_entry(body);
+    }
+}
+
+struct Instrumentor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    count_code_region_fn: DefId,
+    /// The span of the function body, which the regions of the counters are relative to.
+    body_span: Span,
+    function_source_hash: u64,
+    /// The unit local that the counter calls return into.
+    temp: Local,
+    num_counters: u32,
+}
+
+impl<'tcx> Instrumentor<'tcx> {
+    /// Counts the entries into the function with a counter for the whole body. The counter is
+    /// put in a new block, which takes the place of `START_BLOCK`.
+    fn instrument_entry(&mut self, body: &mut BodyAndCache<'tcx>) {
+        let span = body.span;
+        let basic_blocks = body.basic_blocks_mut();
+        let old_start = basic_blocks.push(BasicBlockData::new(None));
+        basic_blocks.swap(START_BLOCK, old_start);
+
+        // Jumps back to the start of the function (like the back edge of a loop that starts the
+        // function) are not entries into the function.
+        for data in basic_blocks.iter_mut() {
+            if let Some(terminator) = &mut data.terminator {
+                for target in terminator.successors_mut() {
+                    if *target == START_BLOCK {
+                        *target = old_start;
+                    }
+                }
+            }
+        }
+
+        basic_blocks[START_BLOCK] = self.counter_block(span, old_start);
+    }
+
+    /// Counts the edges out of each `SwitchInt` into blocks that can only be reached through that
+    /// edge, which is then also the execution count of the straight-line code starting there.
+    fn instrument_branches(&mut self, body: &mut BodyAndCache<'tcx>) {
+        let body_span = body.span;
+        let mut counted = FxHashMap::default();
+        let mut new_blocks = Vec::new();
+        {
+            let read_only = read_only!(body);
+            let predecessors = read_only.predecessors();
+            for (bb, data) in read_only.basic_blocks().iter_enumerated() {
+                if data.is_cleanup {
+                    continue;
+                }
+                if let TerminatorKind::SwitchInt { targets, .. } = &data.terminator().kind {
+                    for &target in targets {
+                        if counted.contains_key(&target) || predecessors[target] != [bb] {
+                            continue;
+                        }
+                        let region = match region_span(&read_only, predecessors, target, body_span)
+                        {
+                            Some(region) => region,
+                            None => continue,
+                        };
+                        let counter = self.counter_block(region, target);
+                        let counter_bb =
+                            BasicBlock::new(read_only.basic_blocks().len() + new_blocks.len());
+                        counted.insert(target, counter_bb);
+                        new_blocks.push(counter);
+                    }
+                }
+            }
+        }
+
+        if counted.is_empty() {
+            return;
+        }
+        let basic_blocks = body.basic_blocks_mut();
+        for data in basic_blocks.iter_mut() {
+            if let TerminatorKind::SwitchInt { targets, .. } = &mut data.terminator_mut().kind {
+                for target in targets {
+                    if let Some(&counter_bb) = counted.get(target) {
+                        *target = counter_bb;
+                    }
+                }
+            }
+        }
+        basic_blocks.extend(new_blocks);
+    }
+
+    /// Creates a block that increments a new counter for the code in `span`, then continues at
+    /// `target`.
+    ///
+    /// The region is passed as offsets from the start of the body rather than as source map
+    /// positions, which are only meaningful in this crate, while the MIR of generic and
+    /// `#[inline]` functions is codegened in other crates too.
+    fn counter_block(&mut self, span: Span, target: BasicBlock) -> BasicBlockData<'tcx> {
+        let tcx = self.tcx;
+        let index = self.num_counters;
+        self.num_counters += 1;
+
+        let constant = |value: u64, ty| {
+            Operand::Constant(box Constant {
+                span,
+                user_ty: None,
+                literal: ty::Const::from_bits(tcx, value.into(), ParamEnv::empty().and(ty)),
+            })
+        };
+        let u32_constant = |value: u32| constant(value.into(), tcx.types.u32);
+
+        let source_info = SourceInfo { span, scope: OUTERMOST_SOURCE_SCOPE };
+        BasicBlockData::new(Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Call {
+                func: Operand::function_handle(
+                    tcx,
+                    self.count_code_region_fn,
+                    tcx.intern_substs(&[]),
+                    span,
+                ),
+                args: vec![
+                    constant(self.function_source_hash, tcx.types.u64),
+                    u32_constant(index),
+                    u32_constant((span.lo() - self.body_span.lo()).0),
+                    u32_constant((span.hi() - self.body_span.lo()).0),
+                ],
+                destination: Some((Place::from(self.temp), target)),
+                cleanup: None,
+                from_hir_call: false,
+            },
+        }))
+    }
+}
+
+/// Computes the source region of the straight-line code starting at `bb`: the blocks that are
+/// only reached by falling through from the previous one.
+///
+/// Only the spans of statements and terminators that do something are used, since the jumps out
+/// of a branch are given the span of the whole `if` or `match`. Spans outside of the function,
+/// from macros defined elsewhere, are ignored as well.
+fn region_span(
+    body: &Body<'_>,
+    predecessors: &IndexVec<BasicBlock, Vec<BasicBlock>>,
+    mut bb: BasicBlock,
+    body_span: Span,
+) -> Option<Span> {
+    let mut region: Option<Span> = None;
+    let mut add = |span: Span| {
+        if !span.is_dummy() && body_span.contains(span) {
+            region = Some(region.map_or(span, |region| region.to(span)));
+        }
+    };
+
+    loop {
+        let data = &body[bb];
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::Assign(..)
+                | StatementKind::SetDiscriminant { .. }
+                | StatementKind::InlineAsm(..) => add(statement.source_info.span),
+                _ => {}
+            }
+        }
+
+        let terminator = data.terminator();
+        let next = match terminator.kind {
+            TerminatorKind::Goto { target }
+            | TerminatorKind::FalseUnwind { real_target: target, .. } => Some(target),
+            TerminatorKind::Call { destination: Some((_, target)), .. }
+            | TerminatorKind::Assert { target, .. }
+            | TerminatorKind::Drop { target, .. }
+            | TerminatorKind::DropAndReplace { target, .. } => {
+                add(terminator.source_info.span);
+                Some(target)
+            }
+            TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::Call { destination: None, .. }
+            | TerminatorKind::Yield { .. } => {
+                add(terminator.source_info.span);
+                None
+            }
+            _ => None,
+        };
+        match next {
+            Some(next) if predecessors[next] == [bb] => bb = next,
+            _ => return region,
+        }
+    }
+}
+
+/// Hashes the source of the function, so that `llvm-cov` can tell when profile data was collected
+/// with a different version of it.
+fn hash_source(tcx: TyCtxt<'_>, span: Span) -> u64 {
+    let mut hasher = StableHasher::new();
+    if let Ok(snippet) = tcx.sess.source_map().span_to_snippet(span) {
+        snippet.hash(&mut hasher);
+    }
+    hasher.finish()
+}
@@ -0,0 +1,340 @@
+//! Loop-invariant code motion.
+//!
+//! This pass finds the natural loops of the CFG, and hoists the computations inside them whose
+//! result is the same on every iteration into the loop's pre-header. This mostly targets the
+//! length reads that indexing a slice emits on every iteration, like:
+//!
+//!     bb1: {
+//!         ...
+//!         _7 = Len((*_1));
+//!         _8 = Lt(_6, _7);
+//!         assert(move _8, ...) -> bb2;
+//!     }
+//!
+//! where `_7 = Len((*_1))` is moved out of the loop, leaving only the comparison to the loop body.
+//!
+//! A statement `_x = rvalue` is hoisted when:
+//!
+//! - `_x` is assigned by this statement only, and never borrowed, so it holds the same value
+//!   wherever it is read;
+//! - the rvalue cannot have side effects, trap or cause UB, since it now runs even on iterations
+//!   (or entries into the loop) where it would not have been evaluated before;
+//! - every local the rvalue reads is never borrowed and is not written to inside the loop.
+//!
+//! Hoisting a statement can make the locals it defines invariant in turn, so we repeat until
+//! nothing changes. Loops are visited from the innermost out, so that a value hoisted into the
+//! pre-header of an inner loop can then move out of the enclosing loops.
+//!
+//! Only loops that already have a pre-header are optimized: a block outside of the loop that is
+//! the only way into it, and that does nothing but fall through into the loop header.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{
+    MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
+};
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // Moving code out of loops is new, so only do it at `-Z mir-opt-level=2` and above.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        debug!("running LoopInvariantCodeMotion on {:?}", source);
+
+        let (mut loops, locals) = {
+            let read_only = read_only!(body);
+            let dominators = read_only.dominators();
+            let loops = find_loops(&read_only, read_only.predecessors(), &dominators);
+            let mut locals = LocalUses::new(&read_only);
+            locals.visit_body(read_only);
+            (loops, locals)
+        };
+
+        // Visit inner loops first.
+        loops.sort_by_key(|natural_loop| natural_loop.blocks.count());
+
+        let mut hoisted = BitSet::new_empty(body.local_decls.len());
+        for natural_loop in &loops {
+            hoist_invariants(body, natural_loop, &locals, &mut hoisted);
+        }
+
+        if hoisted.is_empty() {
+            return;
+        }
+
+        MutVisitor::visit_body(&mut StorageRemover { tcx, hoisted }, body);
+    }
+}
+
+struct NaturalLoop {
+    header: BasicBlock,
+    blocks: BitSet<BasicBlock>,
+    preheader: BasicBlock,
+}
+
+/// Finds the natural loops of `body` that have a pre-header.
+///
+/// A natural loop is defined by its header, which dominates the sources of the back edges going
+/// to it. Its body is made of the blocks that can reach one of those back edges without going
+/// through the header.
+fn find_loops(
+    body: &Body<'_>,
+    predecessors: &IndexVec<BasicBlock, Vec<BasicBlock>>,
+    dominators: &Dominators<BasicBlock>,
+) -> Vec<NaturalLoop> {
+    let mut back_edges: IndexVec<BasicBlock, Vec<BasicBlock>> =
+        IndexVec::from_elem(vec![], body.basic_blocks());
+    for (bb, _) in traversal::reverse_postorder(body) {
+        for &succ in body[bb].terminator().successors() {
+            if dominators.is_dominated_by(bb, succ) {
+                back_edges[succ].push(bb);
+            }
+        }
+    }
+
+    let mut loops = vec![];
+    for (header, latches) in back_edges.iter_enumerated() {
+        if latches.is_empty() || body[header].is_cleanup {
+            continue;
+        }
+
+        let mut blocks = BitSet::new_empty(body.basic_blocks().len());
+        blocks.insert(header);
+        let mut stack = latches.clone();
+        while let Some(bb) = stack.pop() {
+            if blocks.insert(bb) {
+                let reachable = |&&pred: &&BasicBlock| dominators.is_reachable(pred);
+                stack.extend(predecessors[bb].iter().filter(reachable));
+            }
+        }
+
+        let mut entries = predecessors[header]
+            .iter()
+            .filter(|&&pred| dominators.is_reachable(pred) && !blocks.contains(pred));
+        let preheader = match (entries.next(), entries.next()) {
+            (Some(&preheader), None) => preheader,
+            _ => continue,
+        };
+        match body[preheader].terminator().kind {
+            TerminatorKind::Goto { target } if target == header => {}
+            _ => continue,
+        }
+
+        debug!("found loop with header {:?} and pre-header {:?}", header, preheader);
+        loops.push(NaturalLoop { header, blocks, preheader });
+    }
+    loops
+}
+
+/// How each local is used in the whole body.
+struct LocalUses {
+    /// The number of times the local may be assigned, counting arguments as assigned once on
+    /// entry. Any use that could modify part of the local counts as two assignments.
+    assignments: IndexVec<Local, usize>,
+    /// Whether a reference or pointer to the local may exist.
+    borrowed: BitSet<Local>,
+}
+
+impl LocalUses {
+    fn new(body: &Body<'_>) -> Self {
+        let mut assignments = IndexVec::from_elem(0, &body.local_decls);
+        for arg in body.args_iter() {
+            assignments[arg] = 1;
+        }
+        LocalUses { assignments, borrowed: BitSet::new_empty(body.local_decls.len()) }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUses {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        visit_place_through_deref(self, place, context, location)
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store)
+            | PlaceContext::MutatingUse(MutatingUseContext::Call) => {
+                self.assignments[local] += 1;
+            }
+            PlaceContext::MutatingUse(MutatingUseContext::Borrow)
+            | PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => {
+                self.borrowed.insert(local);
+            }
+            PlaceContext::MutatingUse(_) => self.assignments[local] += 2,
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+}
+
+/// Visits `place`, treating a place behind a dereference as a read of the pointer.
+///
+/// Writing through a pointer does not change the pointer itself, and none of the rvalues we hoist
+/// read memory behind a pointer.
+fn visit_place_through_deref<'tcx>(
+    visitor: &mut impl Visitor<'tcx>,
+    place: &Place<'tcx>,
+    context: PlaceContext,
+    location: Location,
+) {
+    if place.projection.first() == Some(&ProjectionElem::Deref) {
+        let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy);
+        visitor.visit_local(&place.local, context, location);
+        visitor.visit_projection(place.local, &place.projection, context, location);
+    } else {
+        visitor.super_place(place, context, location)
+    }
+}
+
+/// Collects the locals that may be modified inside a loop.
+struct LoopWrites {
+    written: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for LoopWrites {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        visit_place_through_deref(self, place, context, location)
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() {
+            self.written.insert(local);
+        }
+    }
+}
+
+fn hoist_invariants<'tcx>(
+    body: &mut BodyAndCache<'tcx>,
+    natural_loop: &NaturalLoop,
+    locals: &LocalUses,
+    hoisted: &mut BitSet<Local>,
+) {
+    loop {
+        let mut writes = LoopWrites { written: BitSet::new_empty(body.local_decls.len()) };
+        for bb in natural_loop.blocks.iter() {
+            writes.visit_basic_block_data(bb, &body[bb]);
+        }
+
+        let is_invariant = |operand: &Operand<'tcx>| match operand {
+            Operand::Copy(place) | Operand::Move(place) => match place.as_local() {
+                Some(local) => !writes.written.contains(local) && !locals.borrowed.contains(local),
+                None => false,
+            },
+            Operand::Constant(_) => true,
+        };
+        let is_invariant_place = |place: &Place<'tcx>| {
+            is_invariant(&Operand::Copy(Place::from(place.local)))
+                && match &place.projection[..] {
+                    [] => true,
+                    // The length of a slice is part of the reference, not of the pointee.
+                    [ProjectionElem::Deref] => body.local_decls[place.local].ty.is_region_ptr(),
+                    _ => false,
+                }
+        };
+
+        let mut candidate = None;
+        'blocks: for bb in natural_loop.blocks.iter() {
+            if body[bb].is_cleanup {
+                continue;
+            }
+            for (statement_index, statement) in body[bb].statements.iter().enumerate() {
+                let (local, rvalue) = match &statement.kind {
+                    StatementKind::Assign(box (place, rvalue)) => match place.as_local() {
+                        Some(local) => (local, rvalue),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                if locals.assignments[local] != 1 || locals.borrowed.contains(local) {
+                    continue;
+                }
+
+                let hoistable = match rvalue {
+                    Rvalue::Use(operand) | Rvalue::UnaryOp(_, operand) => is_invariant(operand),
+                    Rvalue::Len(place) => is_invariant_place(place),
+                    Rvalue::Discriminant(place) => {
+                        place.as_local().is_some() && is_invariant_place(place)
+                    }
+                    Rvalue::BinaryOp(op, lhs, rhs) => {
+                        // Division and shifts are UB for some operands, which is guarded against
+                        // by assertions we would be moving the operation above.
+                        let can_trap = match op {
+                            BinOp::Div | BinOp::Rem | BinOp::Shl | BinOp::Shr | BinOp::Offset => {
+                                true
+                            }
+                            _ => false,
+                        };
+                        !can_trap && is_invariant(lhs) && is_invariant(rhs)
+                    }
+                    Rvalue::CheckedBinaryOp(_, lhs, rhs) => is_invariant(lhs) && is_invariant(rhs),
+                    Rvalue::Repeat(..)
+                    | Rvalue::Ref(..)
+                    | Rvalue::AddressOf(..)
+                    | Rvalue::Cast(..)
+                    | Rvalue::NullaryOp(..)
+                    | Rvalue::Aggregate(..) => false,
+                };
+                if hoistable {
+                    candidate = Some((Location { block: bb, statement_index }, local));
+                    break 'blocks;
+                }
+            }
+        }
+
+        let (location, local) = match candidate {
+            Some(candidate) => candidate,
+            None => return,
+        };
+        debug!("hoisting {:?} out of the loop at {:?}", location, natural_loop.header);
+
+        let statement = &mut body[location.block].statements[location.statement_index];
+        let hoisted_statement = statement.clone();
+        statement.make_nop();
+        body[natural_loop.preheader].statements.push(hoisted_statement);
+        hoisted.insert(local);
+    }
+}
+
+/// Removes the storage markers of the hoisted locals, which are now live across iterations, and
+/// turns moves out of them into copies.
+struct StorageRemover<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    hoisted: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for StorageRemover<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if self.hoisted.contains(local) =>
+            {
+                statement.make_nop()
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        match *operand {
+            Operand::Move(place) if self.hoisted.contains(place.local) => {
+                *operand = Operand::Copy(place);
+            }
+            _ => self.super_operand(operand, location),
+        }
+    }
+}
@@ -23,8 +23,11 @@ pub mod deaggregator;
 pub mod dump_mir;
 pub mod elaborate_drops;
 pub mod generator;
+pub mod gvn;
 pub mod inline;
 pub mod instcombine;
+pub mod instrument_coverage;
+pub mod licm;
 pub mod no_landing_pads;
 pub mod promote_consts;
 pub mod qualify_min_const_fn;
@@ -35,9 +38,12 @@ pub mod simplify_branches;
 pub mod simplify_try;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
+pub mod validate;
 
 pub(crate) fn provide(providers: &mut Providers<'_>) {
     self::check_unsafety::provide(providers);
+    self::inline::cycle::provide(providers);
+    self::instrument_coverage::provide(providers);
     *providers = Providers {
         mir_keys,
         mir_const,
@@ -149,6 +155,13 @@ pub fn run_passes(
     }
 
     let source = MirSource { instance, promoted };
+    let validate = tcx.sess.opts.debugging_opts.validate_mir;
+
+    if validate {
+        validate::Validator { when: format!("input to phase {:?}", mir_phase) }
+            .run_pass(tcx, source, body);
+    }
+
     let mut index = 0;
     let mut run_pass = |pass: &dyn MirPass<'tcx>| {
         let run_hooks = |body: &_, index, is_after| {
@@ -165,6 +178,13 @@ pub fn run_passes(
         pass.run_pass(tcx, source, body);
         run_hooks(body, index, true);
 
+        if validate {
+            validate::Validator {
+                when: format!("after {} ({:03}-{:03})", pass.name(), phase_index, index),
+            }
+            .run_pass(tcx, source, body);
+        }
+
         index += 1;
     };
 
@@ -279,6 +299,8 @@ fn run_optimization_passes<'tcx>(
             &simplify_branches::SimplifyBranches::new("initial"),
             &remove_noop_landing_pads::RemoveNoopLandingPads,
             &cleanup_post_borrowck::CleanupNonCodegenStatements,
+            // Add coverage counters before optimizations move code around.
+            &instrument_coverage::InstrumentCoverage,
             &simplify::SimplifyCfg::new("early-opt"),
             // These next passes must be executed together
             &add_call_guards::CriticalCallEdges,
@@ -305,6 +327,8 @@ fn run_optimization_passes<'tcx>(
             &generator::StateTransform,
             &instcombine::InstCombine,
             &const_prop::ConstProp,
+            &gvn::GlobalValueNumbering,
+            &licm::LoopInvariantCodeMotion,
             &simplify_branches::SimplifyBranches::new("after-const-prop"),
             &deaggregator::Deaggregator,
             &copy_prop::CopyPropagation,
@@ -334,6 +358,12 @@ fn optimized_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &BodyAndCache<'_> {
     // execute before we can steal.
     tcx.ensure().mir_borrowck(def_id);
 
+    // The inliner walks the call graph through the MIR from before inlining, so record the calls
+    // of this body before we steal it.
+    if inline::is_enabled(tcx) {
+        tcx.ensure().mir_inliner_callees(InstanceDef::Item(def_id));
+    }
+
     let (body, _) = tcx.mir_validated(def_id);
     let mut body = body.steal();
     run_optimization_passes(tcx, &mut body, def_id, None);
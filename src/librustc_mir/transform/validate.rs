@@ -0,0 +1,327 @@
+//! Validates the MIR to ensure that invariants are upheld.
+//!
+//! This is run after every MIR pass when `-Z validate-mir` is passed, so that a transform breaking
+//! the MIR is reported right away instead of showing up as a miscompilation much later.
+
+use super::{MirPass, MirSource};
+use crate::dataflow::generic::{Analysis, ResultsCursor};
+use crate::dataflow::MaybeStorageLive;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::{
+    read_only, traversal, BasicBlock, Body, BodyAndCache, Local, LocalKind, Location, Operand,
+    Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
+};
+use rustc::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_index::bit_set::BitSet;
+
+#[derive(Copy, Clone, Debug)]
+enum EdgeKind {
+    Unwind,
+    Normal,
+}
+
+pub struct Validator {
+    /// Describes at which point in the pipeline this validation is happening.
+    pub when: String,
+}
+
+impl<'tcx> MirPass<'tcx> for Validator {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let param_env = tcx.param_env(def_id).with_reveal_all();
+        let read_only = read_only!(body);
+        let body_ref: &Body<'tcx> = &read_only;
+
+        // Only the locals with storage markers have their storage checked, the others being live
+        // for the whole body.
+        let mut has_storage_markers = BitSet::new_empty(body_ref.local_decls.len());
+        for data in body_ref.basic_blocks() {
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                        has_storage_markers.insert(local);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut reachable_blocks = BitSet::new_empty(body_ref.basic_blocks().len());
+        for (bb, _) in traversal::preorder(body_ref) {
+            reachable_blocks.insert(bb);
+        }
+        let storage_liveness = MaybeStorageLive
+            .into_engine(tcx, body_ref, def_id)
+            .iterate_to_fixpoint()
+            .into_results_cursor(body_ref);
+
+        TypeChecker {
+            when: &self.when,
+            source,
+            body: body_ref,
+            tcx,
+            param_env,
+            has_storage_markers,
+            reachable_blocks,
+            storage_liveness,
+        }
+        .visit_body(read_only);
+    }
+}
+
+struct TypeChecker<'a, 'tcx> {
+    when: &'a str,
+    source: MirSource<'tcx>,
+    body: &'a Body<'tcx>,
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    has_storage_markers: BitSet<Local>,
+    reachable_blocks: BitSet<BasicBlock>,
+    storage_liveness: ResultsCursor<'a, 'tcx, MaybeStorageLive>,
+}
+
+impl<'a, 'tcx> TypeChecker<'a, 'tcx> {
+    fn fail(&self, location: Location, msg: impl AsRef<str>) {
+        let span = self.body.source_info(location).span;
+        // We use `delay_span_bug` as we might see broken MIR when other errors have already
+        // occurred.
+        self.tcx.sess.delay_span_bug(
+            span,
+            &format!(
+                "broken MIR in {:?} ({}) at {:?}:\n{}",
+                self.source.instance,
+                self.when,
+                location,
+                msg.as_ref()
+            ),
+        );
+    }
+
+    fn check_edge(&self, location: Location, bb: BasicBlock, edge_kind: EdgeKind) {
+        if let Some(target) = self.body.basic_blocks().get(bb) {
+            let src = &self.body[location.block];
+            match (src.is_cleanup, target.is_cleanup, edge_kind) {
+                // Non-cleanup blocks can jump to non-cleanup blocks along non-unwind edges.
+                (false, false, EdgeKind::Normal)
+                // Non-cleanup blocks can jump to cleanup blocks along unwind edges.
+                | (false, true, EdgeKind::Unwind)
+                // Cleanup blocks can jump to cleanup blocks along non-unwind edges.
+                | (true, true, EdgeKind::Normal) => {}
+                // All other jumps are invalid.
+                _ => self.fail(
+                    location,
+                    format!(
+                        "{:?} edge to {:?} violates unwind invariants (cleanup {:?} -> {:?})",
+                        edge_kind, bb, src.is_cleanup, target.is_cleanup,
+                    ),
+                ),
+            }
+        } else {
+            self.fail(location, format!("encountered jump to invalid basic block {:?}", bb))
+        }
+    }
+
+    /// Checks whether a value of type `src` can be assigned to a place of type `dest`.
+    fn mir_assign_valid_types(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> bool {
+        // Fast path before we normalize.
+        if src == dest {
+            return true;
+        }
+        // Subtyping can change the higher-ranked lifetimes of a type, which are not erased by
+        // `normalize_erasing_regions`, so erase all regions ourselves before comparing.
+        let erase_all_regions = |ty: Ty<'tcx>| {
+            let ty = self.tcx.normalize_erasing_regions(self.param_env, ty);
+            self.tcx.fold_regions(&ty, &mut false, |_, _| self.tcx.lifetimes.re_erased)
+        };
+        erase_all_regions(src) == erase_all_regions(dest)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for TypeChecker<'a, 'tcx> {
+    fn visit_local(&mut self, local: &Local, context: PlaceContext, location: Location) {
+        if self.body.local_decls.get(*local).is_none() {
+            self.fail(location, format!("use of undeclared local {:?}", local));
+            return;
+        }
+        // A local may only be used while its storage is live, and its storage must be dead
+        // before it is made live again. Unreachable blocks are skipped, as the dataflow analysis
+        // considers every local dead in them.
+        if !self.has_storage_markers.contains(*local)
+            || !self.reachable_blocks.contains(location.block)
+        {
+            return;
+        }
+        if context.is_use() {
+            self.storage_liveness.seek_before(location);
+            if !self.storage_liveness.contains(*local) {
+                self.fail(location, format!("use of local {:?}, which has no storage here", local));
+            }
+        } else if context.is_storage_live_marker() {
+            self.storage_liveness.seek_before(location);
+            if self.storage_liveness.contains(*local) {
+                self.fail(
+                    location,
+                    format!("`StorageLive` of local {:?}, which may be live", local),
+                );
+            }
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        // `Operand::Copy` is only supposed to be used with `Copy` types.
+        if let Operand::Copy(place) = operand {
+            let ty = place.ty(self.body, self.tcx).ty;
+            let span = self.body.source_info(location).span;
+            if !ty.is_copy_modulo_regions(self.tcx, self.param_env, span) {
+                self.fail(location, format!("`Operand::Copy` with non-`Copy` type {}", ty));
+            }
+        }
+
+        self.super_operand(operand, location);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        match &statement.kind {
+            StatementKind::Assign(box (dest, rvalue)) => {
+                // LHS and RHS of the assignment must have the same type.
+                let left_ty = dest.ty(self.body, self.tcx).ty;
+                let right_ty = rvalue.ty(self.body, self.tcx);
+                if !self.mir_assign_valid_types(right_ty, left_ty) {
+                    self.fail(
+                        location,
+                        format!(
+                            "encountered `Assign` statement with incompatible types:\n\
+                             left-hand side has type: {}\n\
+                             right-hand side has type: {}",
+                            left_ty, right_ty,
+                        ),
+                    );
+                }
+                // The sides of an assignment must not alias. Currently this just checks whether
+                // the places are identical.
+                match rvalue {
+                    Rvalue::Use(Operand::Copy(src)) | Rvalue::Use(Operand::Move(src)) => {
+                        if dest == src {
+                            self.fail(
+                                location,
+                                "encountered `Assign` statement with overlapping memory",
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                match self.body.local_kind(*local) {
+                    LocalKind::Arg | LocalKind::ReturnPointer => self.fail(
+                        location,
+                        format!("storage marker for argument or return place {:?}", local),
+                    ),
+                    LocalKind::Var | LocalKind::Temp => {}
+                }
+            }
+            _ => {}
+        }
+
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        match &terminator.kind {
+            TerminatorKind::Goto { target } => {
+                self.check_edge(location, *target, EdgeKind::Normal);
+            }
+            TerminatorKind::SwitchInt { discr, switch_ty, values, targets } => {
+                let ty = discr.ty(self.body, self.tcx);
+                if ty != *switch_ty {
+                    self.fail(
+                        location,
+                        format!(
+                            "encountered `SwitchInt` terminator with type mismatch: {:?} != {:?}",
+                            ty, switch_ty,
+                        ),
+                    );
+                }
+                if targets.len() != values.len() + 1 {
+                    self.fail(
+                        location,
+                        format!(
+                            "encountered `SwitchInt` terminator with {} values, but {} targets \
+                             (should be values+1)",
+                            values.len(),
+                            targets.len(),
+                        ),
+                    );
+                }
+                for target in targets {
+                    self.check_edge(location, *target, EdgeKind::Normal);
+                }
+            }
+            TerminatorKind::Drop { target, unwind, .. }
+            | TerminatorKind::DropAndReplace { target, unwind, .. } => {
+                self.check_edge(location, *target, EdgeKind::Normal);
+                if let Some(unwind) = unwind {
+                    self.check_edge(location, *unwind, EdgeKind::Unwind);
+                }
+            }
+            TerminatorKind::Call { func, destination, cleanup, .. } => {
+                let func_ty = func.ty(self.body, self.tcx);
+                match func_ty.kind {
+                    ty::FnPtr(..) | ty::FnDef(..) => {}
+                    _ => self.fail(
+                        location,
+                        format!("encountered non-callable type {} in `Call` terminator", func_ty),
+                    ),
+                }
+                if let Some((_, target)) = destination {
+                    self.check_edge(location, *target, EdgeKind::Normal);
+                }
+                if let Some(cleanup) = cleanup {
+                    self.check_edge(location, *cleanup, EdgeKind::Unwind);
+                }
+            }
+            TerminatorKind::Assert { cond, target, cleanup, .. } => {
+                let cond_ty = cond.ty(self.body, self.tcx);
+                if cond_ty != self.tcx.types.bool {
+                    self.fail(
+                        location,
+                        format!(
+                            "encountered non-boolean condition of type {} in `Assert` terminator",
+                            cond_ty
+                        ),
+                    );
+                }
+                self.check_edge(location, *target, EdgeKind::Normal);
+                if let Some(cleanup) = cleanup {
+                    self.check_edge(location, *cleanup, EdgeKind::Unwind);
+                }
+            }
+            TerminatorKind::Yield { resume, drop, .. } => {
+                self.check_edge(location, *resume, EdgeKind::Normal);
+                if let Some(drop) = drop {
+                    self.check_edge(location, *drop, EdgeKind::Normal);
+                }
+            }
+            TerminatorKind::FalseEdges { real_target, imaginary_target } => {
+                self.check_edge(location, *real_target, EdgeKind::Normal);
+                self.check_edge(location, *imaginary_target, EdgeKind::Normal);
+            }
+            TerminatorKind::FalseUnwind { real_target, unwind } => {
+                self.check_edge(location, *real_target, EdgeKind::Normal);
+                if let Some(unwind) = unwind {
+                    self.check_edge(location, *unwind, EdgeKind::Unwind);
+                }
+            }
+            TerminatorKind::Return => {
+                if self.body[location.block].is_cleanup {
+                    self.fail(location, "cannot `Return` from cleanup basic block");
+                }
+            }
+            TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Unreachable
+            | TerminatorKind::GeneratorDrop => {}
+        }
+
+        self.super_terminator(terminator, location);
+    }
+}
@@ -0,0 +1,565 @@
+//! Provides the implementation of the `#[custom_mir]` attribute.
+//!
+//! Up until MIR building, this attribute has absolutely no effect. The `mir!` macro from
+//! `core::intrinsics::mir` is a normal decl macro that expands like any other, and the code goes
+//! through parsing, name resolution and type checking like all other code. In MIR building we
+//! finally detect whether this attribute is present, and if so we branch off into this module,
+//! which implements the attribute by lowering the HAIR of the body directly into MIR, instead of
+//! building MIR with the semantics of the Rust source.
+//!
+//! The resulting MIR is returned as-is from `mir_built` and then goes through the usual pipeline
+//! of passes, which lets mir-opt tests feed a precise MIR shape into the pass they are testing.
+//!
+//! Only the shapes of code that `mir!` and the functions next to it are documented to produce are
+//! understood here; anything else is reported as an error pointing at the offending expression.
+
+use crate::hair::cx::Cx;
+use crate::hair::{Block, Expr, ExprKind, ExprRef, Pat, PatKind, StmtKind};
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_index::vec::IndexVec;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
+use std::borrow::Cow;
+
+/// The functions from `core::intrinsics::mir` that have a meaning in custom MIR.
+const MIR_FUNCTIONS: &[Symbol] = &[
+    sym::mir_call,
+    sym::mir_checked,
+    sym::mir_discriminant,
+    sym::mir_drop,
+    sym::mir_goto,
+    sym::mir_len,
+    sym::mir_move,
+    sym::mir_return,
+    sym::mir_storage_dead,
+    sym::mir_storage_live,
+    sym::mir_unreachable,
+];
+
+struct ParseError {
+    span: Span,
+    item_description: &'static str,
+    expected: &'static str,
+}
+
+type PResult<T> = Result<T, ParseError>;
+
+crate fn build_custom_mir<'a, 'tcx>(
+    hir: Cx<'a, 'tcx>,
+    body_id: hir::BodyId,
+    fn_sig: ty::FnSig<'tcx>,
+    return_ty_span: Span,
+) -> Body<'tcx> {
+    let tcx = hir.tcx();
+    let fn_id = tcx.hir().body_owner(body_id);
+    let body = tcx.hir().body(body_id);
+    let span = tcx.hir().span(fn_id);
+    // Like everything else in freshly built MIR, the signature must not mention free regions.
+    let fn_sig = tcx.erase_regions(&fn_sig);
+
+    let mut source_scopes = IndexVec::new();
+    source_scopes.push(SourceScopeData {
+        span,
+        parent_scope: None,
+        local_data: ClearCrossCrate::Set(SourceScopeLocalData {
+            lint_root: fn_id,
+            safety: Safety::Safe,
+        }),
+    });
+
+    let mut pctxt = ParseCtxt {
+        hir,
+        tcx,
+        local_decls: IndexVec::new(),
+        var_debug_info: Vec::new(),
+        local_map: FxHashMap::default(),
+        blocks: IndexVec::new(),
+        block_spans: IndexVec::new(),
+        block_map: FxHashMap::default(),
+        seen_entry: false,
+    };
+    pctxt.local_decls.push(LocalDecl::new_return_place(fn_sig.output(), return_ty_span));
+    // The entry block is not declared like the others; it is simply the first one in the body.
+    pctxt.blocks.push(BasicBlockData::new(None));
+    pctxt.block_spans.push(body.value.span);
+
+    if let Err(err) = pctxt.parse_args(body, fn_sig).and_then(|()| pctxt.parse_body(body)) {
+        tcx.sess
+            .struct_span_err(err.span, &format!("could not parse {}", err.item_description))
+            .note(&format!("expected {}", err.expected))
+            .emit();
+        return super::construct_error(pctxt.hir, body_id);
+    }
+
+    Body::new(
+        pctxt.blocks,
+        source_scopes,
+        pctxt.local_decls,
+        IndexVec::new(),
+        body.params.len(),
+        pctxt.var_debug_info,
+        span,
+        vec![],
+        None,
+    )
+}
+
+struct ParseCtxt<'a, 'tcx> {
+    hir: Cx<'a, 'tcx>,
+    tcx: TyCtxt<'tcx>,
+
+    local_decls: IndexVec<Local, LocalDecl<'tcx>>,
+    var_debug_info: Vec<VarDebugInfo<'tcx>>,
+    local_map: FxHashMap<hir::HirId, Local>,
+
+    blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>,
+    block_spans: IndexVec<BasicBlock, Span>,
+    block_map: FxHashMap<hir::HirId, BasicBlock>,
+    seen_entry: bool,
+}
+
+impl<'a, 'tcx> ParseCtxt<'a, 'tcx> {
+    fn error(
+        &self,
+        span: Span,
+        item_description: &'static str,
+        expected: &'static str,
+    ) -> ParseError {
+        ParseError { span, item_description, expected }
+    }
+
+    fn source_info(&self, span: Span) -> SourceInfo {
+        SourceInfo { span, scope: OUTERMOST_SOURCE_SCOPE }
+    }
+
+    /// Mirrors `expr`, skipping over the scopes HAIR wraps around every expression.
+    fn peel(&mut self, expr: ExprRef<'tcx>) -> Expr<'tcx> {
+        let mut expr = self.hir.mirror(expr);
+        loop {
+            match expr.kind {
+                ExprKind::Scope { value, .. } => expr = self.hir.mirror(value),
+                _ => return expr,
+            }
+        }
+    }
+
+    /// Returns the name of the `core::intrinsics::mir` function of type `ty`, if any.
+    fn mir_function(&self, ty: Ty<'tcx>) -> Option<Symbol> {
+        let def_id = match ty.kind {
+            ty::FnDef(def_id, _) => def_id,
+            _ => return None,
+        };
+        MIR_FUNCTIONS.iter().copied().find(|&name| self.tcx.is_diagnostic_item(name, def_id))
+    }
+
+    fn declare_local(&mut self, name: Symbol, var: hir::HirId, local: Local, span: Span) {
+        self.local_map.insert(var, local);
+        self.var_debug_info.push(VarDebugInfo {
+            name,
+            source_info: self.source_info(span),
+            place: Place::from(local),
+        });
+    }
+
+    fn parse_args(&mut self, body: &'tcx hir::Body<'tcx>, fn_sig: ty::FnSig<'tcx>) -> PResult<()> {
+        for (param, &ty) in body.params.iter().zip(fn_sig.inputs()) {
+            match param.pat.kind {
+                hir::PatKind::Binding(_, var, ident, None) => {
+                    let local = self.local_decls.push(LocalDecl::new_temp(ty, param.span));
+                    self.declare_local(ident.name, var, local, param.span);
+                }
+                _ => {
+                    return Err(self.error(
+                        param.pat.span,
+                        "function parameter",
+                        "a parameter bound to a plain identifier",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_body(&mut self, body: &'tcx hir::Body<'tcx>) -> PResult<()> {
+        let expr = self.peel(ExprRef::Hair(&body.value));
+        self.parse_nested_blocks(expr)?;
+
+        for (bb, data) in self.blocks.iter_enumerated() {
+            if data.terminator.is_none() {
+                return Err(self.error(
+                    self.block_spans[bb],
+                    "basic block",
+                    "every declared basic block to be defined",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Walks the nested blocks produced by `mir!`, declaring locals and basic blocks and parsing
+    /// the contents of each basic block.
+    fn parse_nested_blocks(&mut self, expr: Expr<'tcx>) -> PResult<()> {
+        let block: Block<'tcx> = match expr.kind {
+            ExprKind::Block { body } => self.hir.mirror(body),
+            _ => return Err(self.error(expr.span, "custom MIR body", "a `mir!` invocation")),
+        };
+
+        for stmt in block.stmts {
+            let stmt = self.hir.mirror(stmt);
+            match stmt.kind {
+                StmtKind::Let { pattern, initializer: None, .. } => self.parse_let(&pattern)?,
+                StmtKind::Let { pattern, .. } => {
+                    return Err(self.error(
+                        pattern.span,
+                        "local declaration",
+                        "a declaration without initializer",
+                    ));
+                }
+                StmtKind::Expr { expr, .. } => {
+                    let expr = self.peel(expr);
+                    match expr.kind {
+                        ExprKind::Block { body } if !self.seen_entry => {
+                            self.seen_entry = true;
+                            self.parse_block(START_BLOCK, body)?;
+                        }
+                        ExprKind::Assign { lhs, rhs } => {
+                            let bb = self.parse_block_ref(lhs)?;
+                            let rhs = self.peel(rhs);
+                            match rhs.kind {
+                                ExprKind::Block { body } => self.parse_block(bb, body)?,
+                                _ => {
+                                    return Err(self.error(
+                                        rhs.span,
+                                        "basic block",
+                                        "a block expression",
+                                    ));
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(self.error(
+                                expr.span,
+                                "basic block",
+                                "the entry block, or `bbN = { ... }`",
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        let tail = match block.expr {
+            Some(tail) => self.peel(tail),
+            None => return Err(self.error(block.span, "custom MIR body", "a `mir!` invocation")),
+        };
+        match tail.kind {
+            ExprKind::Block { .. } => self.parse_nested_blocks(tail),
+            ExprKind::VarRef { id } if self.local_map.get(&id) == Some(&RETURN_PLACE) => Ok(()),
+            _ => Err(self.error(tail.span, "custom MIR body", "a `mir!` invocation")),
+        }
+    }
+
+    fn parse_let(&mut self, pattern: &Pat<'tcx>) -> PResult<()> {
+        match *pattern.kind {
+            PatKind::AscribeUserType { ref subpattern, .. } => self.parse_let(subpattern),
+            PatKind::Binding { name, var, ty, subpattern: None, .. } => {
+                let is_basic_block = ty.ty_adt_def().map_or(false, |adt_def| {
+                    self.tcx.is_diagnostic_item(sym::mir_basic_block, adt_def.did)
+                });
+                if is_basic_block {
+                    let bb = self.blocks.push(BasicBlockData::new(None));
+                    self.block_spans.push(pattern.span);
+                    self.block_map.insert(var, bb);
+                } else if name == sym::RET {
+                    self.local_map.insert(var, RETURN_PLACE);
+                } else {
+                    let local = self.local_decls.push(LocalDecl::new_temp(ty, pattern.span));
+                    self.declare_local(name, var, local, pattern.span);
+                }
+                Ok(())
+            }
+            _ => Err(self.error(pattern.span, "local declaration", "`let name: Type;`")),
+        }
+    }
+
+    fn parse_block(&mut self, bb: BasicBlock, body: &'tcx hir::Block<'tcx>) -> PResult<()> {
+        let block: Block<'tcx> = self.hir.mirror(body);
+
+        for stmt in block.stmts {
+            let stmt = self.hir.mirror(stmt);
+            let statement = match stmt.kind {
+                StmtKind::Expr { expr, .. } => self.parse_statement(expr)?,
+                StmtKind::Let { pattern, .. } => {
+                    return Err(self.error(
+                        pattern.span,
+                        "statement",
+                        "locals to be declared before the basic blocks",
+                    ));
+                }
+            };
+            self.blocks[bb].statements.push(statement);
+        }
+
+        let terminator = match block.expr {
+            Some(expr) => self.parse_terminator(expr)?,
+            None => {
+                return Err(self.error(
+                    block.span,
+                    "basic block",
+                    "a terminator as the last expression of the block",
+                ));
+            }
+        };
+        self.blocks[bb].terminator = Some(terminator);
+        Ok(())
+    }
+
+    fn parse_statement(&mut self, expr: ExprRef<'tcx>) -> PResult<Statement<'tcx>> {
+        let expr = self.peel(expr);
+        let source_info = self.source_info(expr.span);
+        let kind = match expr.kind {
+            ExprKind::Assign { lhs, rhs } => {
+                let place = self.parse_place(lhs)?;
+                let rvalue = self.parse_rvalue(rhs)?;
+                StatementKind::Assign(box (place, rvalue))
+            }
+            ExprKind::Call { ty, ref args, .. } => match self.mir_function(ty) {
+                Some(sym::mir_storage_live) => {
+                    StatementKind::StorageLive(self.parse_local(args[0].clone())?)
+                }
+                Some(sym::mir_storage_dead) => {
+                    StatementKind::StorageDead(self.parse_local(args[0].clone())?)
+                }
+                _ => {
+                    return Err(self.error(
+                        expr.span,
+                        "statement",
+                        "an assignment, `StorageLive(..)` or `StorageDead(..)`",
+                    ));
+                }
+            },
+            _ => {
+                return Err(self.error(
+                    expr.span,
+                    "statement",
+                    "an assignment, `StorageLive(..)` or `StorageDead(..)`",
+                ));
+            }
+        };
+        Ok(Statement { source_info, kind })
+    }
+
+    fn parse_terminator(&mut self, expr: ExprRef<'tcx>) -> PResult<Terminator<'tcx>> {
+        const EXPECTED: &str =
+            "`Return()`, `Goto(..)`, `Unreachable()`, `Drop(..)`, `Call(..)` or a `match`";
+
+        let expr = self.peel(expr);
+        let source_info = self.source_info(expr.span);
+        let kind = match expr.kind {
+            ExprKind::Call { ty, ref args, .. } => match self.mir_function(ty) {
+                Some(sym::mir_return) => TerminatorKind::Return,
+                Some(sym::mir_unreachable) => TerminatorKind::Unreachable,
+                Some(sym::mir_goto) => {
+                    TerminatorKind::Goto { target: self.parse_block_ref(args[0].clone())? }
+                }
+                Some(sym::mir_drop) => TerminatorKind::Drop {
+                    location: self.parse_place(args[0].clone())?,
+                    target: self.parse_block_ref(args[1].clone())?,
+                    unwind: None,
+                },
+                Some(sym::mir_call) => {
+                    let destination = self.parse_place(args[0].clone())?;
+                    let target = self.parse_block_ref(args[1].clone())?;
+                    let call = self.peel(args[2].clone());
+                    match call.kind {
+                        ExprKind::Call { fun, args, from_hir_call, .. } => TerminatorKind::Call {
+                            func: self.parse_operand(fun)?,
+                            args: args
+                                .into_iter()
+                                .map(|arg| self.parse_operand(arg))
+                                .collect::<PResult<_>>()?,
+                            destination: Some((destination, target)),
+                            cleanup: None,
+                            from_hir_call,
+                        },
+                        _ => return Err(self.error(call.span, "call", "a function call")),
+                    }
+                }
+                _ => return Err(self.error(expr.span, "terminator", EXPECTED)),
+            },
+            ExprKind::Match { scrutinee, arms } => {
+                let scrutinee = self.peel(scrutinee);
+                let switch_ty = scrutinee.ty;
+                let discr = self.parse_operand(ExprRef::Mirror(box scrutinee))?;
+
+                let mut values = vec![];
+                let mut targets = vec![];
+                let mut seen_otherwise = false;
+                for arm in arms {
+                    if seen_otherwise || arm.guard.is_some() {
+                        return Err(self.error(arm.span, "switch arm", "`value => bbN`"));
+                    }
+                    match *arm.pattern.kind {
+                        PatKind::Constant { value } => values.push(value.eval_bits(
+                            self.tcx,
+                            self.hir.param_env,
+                            switch_ty,
+                        )),
+                        PatKind::Wild => seen_otherwise = true,
+                        _ => {
+                            return Err(self.error(
+                                arm.pattern.span,
+                                "switch arm",
+                                "a constant or `_` pattern",
+                            ));
+                        }
+                    }
+                    targets.push(self.parse_block_ref(arm.body)?);
+                }
+                if !seen_otherwise {
+                    return Err(self.error(expr.span, "switch", "a final `_ => bbN` arm"));
+                }
+
+                TerminatorKind::SwitchInt { discr, switch_ty, values: Cow::Owned(values), targets }
+            }
+            _ => return Err(self.error(expr.span, "terminator", EXPECTED)),
+        };
+        Ok(Terminator { source_info, kind })
+    }
+
+    fn parse_block_ref(&mut self, expr: ExprRef<'tcx>) -> PResult<BasicBlock> {
+        let expr = self.peel(expr);
+        match expr.kind {
+            ExprKind::VarRef { id } => match self.block_map.get(&id) {
+                Some(&bb) => Ok(bb),
+                None => Err(self.error(expr.span, "basic block", "the name of a basic block")),
+            },
+            _ => Err(self.error(expr.span, "basic block", "the name of a basic block")),
+        }
+    }
+
+    fn parse_local(&mut self, expr: ExprRef<'tcx>) -> PResult<Local> {
+        let expr = self.peel(expr);
+        match expr.kind {
+            ExprKind::VarRef { id } => match self.local_map.get(&id) {
+                Some(&local) => Ok(local),
+                None => Err(self.error(expr.span, "local", "a local declared in `mir!`")),
+            },
+            _ => Err(self.error(expr.span, "local", "the name of a local")),
+        }
+    }
+
+    fn parse_place(&mut self, expr: ExprRef<'tcx>) -> PResult<Place<'tcx>> {
+        let expr = self.peel(expr);
+        let (base, elem) = match expr.kind {
+            ExprKind::VarRef { .. } => {
+                return self.parse_local(ExprRef::Mirror(box expr)).map(Place::from);
+            }
+            ExprKind::Deref { arg } => (arg, ProjectionElem::Deref),
+            ExprKind::Field { lhs, name } => (lhs, ProjectionElem::Field(name, expr.ty)),
+            ExprKind::Index { lhs, index } => {
+                (lhs, ProjectionElem::Index(self.parse_local(index)?))
+            }
+            _ => {
+                return Err(self.error(
+                    expr.span,
+                    "place",
+                    "a local, a dereference, a field access or an indexing by a local",
+                ));
+            }
+        };
+        let base = self.parse_place(base)?;
+        Ok(self.tcx.mk_place_elem(base, elem))
+    }
+
+    fn parse_operand(&mut self, expr: ExprRef<'tcx>) -> PResult<Operand<'tcx>> {
+        let expr = self.peel(expr);
+        match expr.kind {
+            ExprKind::Call { ty, ref args, .. } if self.mir_function(ty) == Some(sym::mir_move) => {
+                self.parse_place(args[0].clone()).map(Operand::Move)
+            }
+            ExprKind::Literal { literal, .. } => {
+                Ok(Operand::Constant(box Constant { span: expr.span, user_ty: None, literal }))
+            }
+            _ => self.parse_place(ExprRef::Mirror(box expr)).map(Operand::Copy),
+        }
+    }
+
+    fn parse_rvalue(&mut self, expr: ExprRef<'tcx>) -> PResult<Rvalue<'tcx>> {
+        let expr = self.peel(expr);
+        let rvalue = match expr.kind {
+            ExprKind::Call { ty, ref args, .. } => match self.mir_function(ty) {
+                Some(sym::mir_checked) => {
+                    let binop = self.peel(args[0].clone());
+                    match binop.kind {
+                        ExprKind::Binary { op, lhs, rhs } => Rvalue::CheckedBinaryOp(
+                            op,
+                            self.parse_operand(lhs)?,
+                            self.parse_operand(rhs)?,
+                        ),
+                        _ => {
+                            return Err(self.error(
+                                binop.span,
+                                "checked operation",
+                                "a binary operation, as in `Checked(a + b)`",
+                            ));
+                        }
+                    }
+                }
+                Some(sym::mir_len) => {
+                    let borrow = self.peel(args[0].clone());
+                    match borrow.kind {
+                        ExprKind::Borrow { arg, .. } => Rvalue::Len(self.parse_place(arg)?),
+                        _ => {
+                            return Err(self.error(
+                                borrow.span,
+                                "length",
+                                "a reference to a place, as in `Len(&*slice)`",
+                            ));
+                        }
+                    }
+                }
+                Some(sym::mir_discriminant) => {
+                    let rvalue = Rvalue::Discriminant(self.parse_place(args[0].clone())?);
+                    // `Discriminant` returns an `isize`, so the discriminant must have that
+                    // type for the assignment to be well-typed.
+                    if rvalue.ty(&self.local_decls, self.tcx) != self.tcx.types.isize {
+                        return Err(self.error(
+                            expr.span,
+                            "discriminant",
+                            "a place whose discriminant is an `isize`, like an enum without a \
+                             `#[repr]` integer type",
+                        ));
+                    }
+                    rvalue
+                }
+                _ => Rvalue::Use(self.parse_operand(ExprRef::Mirror(box expr))?),
+            },
+            ExprKind::Borrow { borrow_kind, arg } => {
+                Rvalue::Ref(self.tcx.lifetimes.re_erased, borrow_kind, self.parse_place(arg)?)
+            }
+            ExprKind::AddressOf { mutability, arg } => {
+                Rvalue::AddressOf(mutability, self.parse_place(arg)?)
+            }
+            ExprKind::Binary { op, lhs, rhs } => {
+                Rvalue::BinaryOp(op, self.parse_operand(lhs)?, self.parse_operand(rhs)?)
+            }
+            ExprKind::Unary { op, arg } => Rvalue::UnaryOp(op, self.parse_operand(arg)?),
+            ExprKind::Cast { source } => {
+                Rvalue::Cast(CastKind::Misc, self.parse_operand(source)?, expr.ty)
+            }
+            ExprKind::Pointer { cast, source } => {
+                Rvalue::Cast(CastKind::Pointer(cast), self.parse_operand(source)?, expr.ty)
+            }
+            ExprKind::Tuple { fields } => Rvalue::Aggregate(
+                box AggregateKind::Tuple,
+                fields.into_iter().map(|field| self.parse_operand(field)).collect::<PResult<_>>()?,
+            ),
+            _ => Rvalue::Use(self.parse_operand(ExprRef::Mirror(box expr))?),
+        };
+        Ok(rvalue)
+    }
+}
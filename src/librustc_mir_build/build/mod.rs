@@ -9,11 +9,12 @@ use rustc::ty::subst::Subst;
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc_attr::{self as attr, UnwindAttr};
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
 use rustc_hir::{GeneratorKind, HirIdMap, Node};
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_infer::infer::TyCtxtInferExt;
-use rustc_span::symbol::kw;
+use rustc_span::symbol::{kw, sym};
 use rustc_span::Span;
 use rustc_target::spec::abi::Abi;
 use rustc_target::spec::PanicStrategy;
@@ -62,6 +63,12 @@ fn mir_build(tcx: TyCtxt<'_>, def_id: DefId) -> BodyAndCache<'_> {
         let cx = Cx::new(&infcx, id);
         let body = if cx.tables().tainted_by_errors {
             build::construct_error(cx, body_id)
+        } else if tcx.has_attr(def_id, sym::custom_mir) && tcx.def_kind(def_id) == Some(DefKind::Fn)
+        {
+            // `check_attr` rejects `#[custom_mir]` on anything but fn items, but
+            // the MIR of the other bodies can still be built after that error.
+            let fn_sig = cx.tables().liberated_fn_sigs()[id];
+            custom::build_custom_mir(cx, body_id, fn_sig, return_ty_span)
         } else if cx.body_owner_kind.is_fn_or_closure() {
             // fetch the fully liberated fn signature (that is, all bound
             // types/lifetimes replaced)
@@ -1026,6 +1033,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
 
 mod block;
 mod cfg;
+mod custom;
 mod expr;
 mod into;
 mod matches;
@@ -1,12 +1,18 @@
+use rustc_ast::ast;
 use rustc_ast::token::{self, Token, TokenKind};
 use rustc_ast::util::comments;
+use rustc_ast::util::unicode::{contains_text_flow_control_chars, TEXT_FLOW_CONTROL_CHARS};
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::{error_code, DiagnosticBuilder, FatalError};
 use rustc_lexer::unescape;
 use rustc_lexer::Base;
+use rustc_session::lint::builtin::{
+    TEXT_DIRECTION_CODEPOINT_IN_COMMENT, TEXT_DIRECTION_CODEPOINT_IN_LITERAL,
+};
+use rustc_session::lint::Lint;
 use rustc_session::parse::ParseSess;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{BytePos, Pos, Span};
+use rustc_span::{BytePos, MultiSpan, Pos, Span};
 
 use log::debug;
 use std::char;
@@ -136,6 +142,11 @@ impl<'a> StringReader<'a> {
         Token::new(kind, span)
     }
 
+    /// The message `id` of the catalogs of diagnostic messages, with `args`.
+    fn msg(&self, id: &str, args: &[(&str, String)]) -> String {
+        self.sess.span_diagnostic.translate(id, args)
+    }
+
     /// Report a fatal lexical error with a given span.
     fn fatal_span(&self, sp: Span, m: &str) -> FatalError {
         self.sess.span_diagnostic.span_fatal(sp, m)
@@ -169,14 +180,14 @@ impl<'a> StringReader<'a> {
         &self,
         from_pos: BytePos,
         to_pos: BytePos,
-        m: &str,
+        id: &str,
         c: char,
     ) -> DiagnosticBuilder<'a> {
-        let mut m = m.to_string();
-        m.push_str(": ");
-        push_escaped_char(&mut m, c);
+        let mut escaped = String::new();
+        push_escaped_char(&mut escaped, c);
+        let m = self.msg(id, &[("char", escaped)]);
 
-        self.sess.span_diagnostic.struct_span_fatal(self.mk_sp(from_pos, to_pos), &m[..])
+        self.sess.span_diagnostic.struct_span_fatal(self.mk_sp(from_pos, to_pos), &m)
     }
 
     /// Turns simple `rustc_lexer::TokenKind` enum into a rich
@@ -186,9 +197,15 @@ impl<'a> StringReader<'a> {
         match token {
             rustc_lexer::TokenKind::LineComment => {
                 let string = self.str_from(start);
+                self.lint_text_flow_control_chars(
+                    start,
+                    string,
+                    TEXT_DIRECTION_CODEPOINT_IN_COMMENT,
+                    "parser-text-direction-codepoint-in-comment",
+                );
                 // comments with only more "/"s are not doc comments
                 if comments::is_line_doc_comment(string) {
-                    self.forbid_bare_cr(start, string, "bare CR not allowed in doc-comment");
+                    self.forbid_bare_cr(start, string, "parser-bare-cr-in-doc-comment");
                     token::DocComment(Symbol::intern(string))
                 } else {
                     token::Comment
@@ -201,17 +218,23 @@ impl<'a> StringReader<'a> {
                 let is_doc_comment = comments::is_block_doc_comment(string);
 
                 if !terminated {
-                    let msg = if is_doc_comment {
-                        "unterminated block doc-comment"
+                    let id = if is_doc_comment {
+                        "parser-unterminated-block-doc-comment"
                     } else {
-                        "unterminated block comment"
+                        "parser-unterminated-block-comment"
                     };
                     let last_bpos = self.pos;
-                    self.fatal_span_(start, last_bpos, msg).raise();
+                    self.fatal_span_(start, last_bpos, &self.msg(id, &[])).raise();
                 }
+                self.lint_text_flow_control_chars(
+                    start,
+                    string,
+                    TEXT_DIRECTION_CODEPOINT_IN_COMMENT,
+                    "parser-text-direction-codepoint-in-comment",
+                );
 
                 if is_doc_comment {
-                    self.forbid_bare_cr(start, string, "bare CR not allowed in block doc-comment");
+                    self.forbid_bare_cr(start, string, "parser-bare-cr-in-block-doc-comment");
                     token::DocComment(Symbol::intern(string))
                 } else {
                     token::Comment
@@ -228,7 +251,9 @@ impl<'a> StringReader<'a> {
                 if is_raw_ident {
                     let span = self.mk_sp(start, self.pos);
                     if !sym.can_be_raw() {
-                        self.err_span(span, &format!("`{}` cannot be a raw identifier", sym));
+                        let args = [("ident", sym.to_string())];
+                        let msg = self.msg("parser-cannot-be-raw-identifier", &args);
+                        self.err_span(span, &msg);
                     }
                     self.sess.raw_identifier_spans.borrow_mut().push(span);
                 }
@@ -237,6 +262,12 @@ impl<'a> StringReader<'a> {
             rustc_lexer::TokenKind::Literal { kind, suffix_start } => {
                 let suffix_start = start + BytePos(suffix_start as u32);
                 let (kind, symbol) = self.cook_lexer_literal(start, suffix_start, kind);
+                self.lint_text_flow_control_chars(
+                    start,
+                    self.str_from_to(start, suffix_start),
+                    TEXT_DIRECTION_CODEPOINT_IN_LITERAL,
+                    "parser-text-direction-codepoint-in-literal",
+                );
                 let suffix = if suffix_start < self.pos {
                     let string = self.str_from(suffix_start);
                     if string == "_" {
@@ -244,18 +275,10 @@ impl<'a> StringReader<'a> {
                             .span_diagnostic
                             .struct_span_warn(
                                 self.mk_sp(suffix_start, self.pos),
-                                "underscore literal suffix is not allowed",
-                            )
-                            .warn(
-                                "this was previously accepted by the compiler but is \
-                                   being phased out; it will become a hard error in \
-                                   a future release!",
-                            )
-                            .note(
-                                "see issue #42326 \
-                                 <https://github.com/rust-lang/rust/issues/42326> \
-                                 for more information",
+                                &self.msg("parser-underscore-literal-suffix", &[]),
                             )
+                            .warn(&self.msg("parser-underscore-literal-suffix.warn", &[]))
+                            .note(&self.msg("parser-underscore-literal-suffix.note", &[]))
                             .emit();
                         None
                     } else {
@@ -272,7 +295,8 @@ impl<'a> StringReader<'a> {
                 // this is necessary.
                 let lifetime_name = self.str_from(start);
                 if starts_with_number {
-                    self.err_span_(start, self.pos, "lifetimes cannot start with a number");
+                    let msg = self.msg("parser-lifetime-starts-with-number", &[]);
+                    self.err_span_(start, self.pos, &msg);
                 }
                 let ident = Symbol::intern(lifetime_name);
                 token::Lifetime(ident)
@@ -307,8 +331,12 @@ impl<'a> StringReader<'a> {
 
             rustc_lexer::TokenKind::Unknown => {
                 let c = self.str_from(start).chars().next().unwrap();
-                let mut err =
-                    self.struct_fatal_span_char(start, self.pos, "unknown start of token", c);
+                let mut err = self.struct_fatal_span_char(
+                    start,
+                    self.pos,
+                    "parser-unknown-start-of-token",
+                    c,
+                );
                 // FIXME: the lexer could be used to turn the ASCII version of unicode homoglyphs,
                 // instead of keeping a table in `check_for_substitution`into the token. Ideally,
                 // this should be inside `rustc_lexer`. However, we should first remove compound
@@ -331,7 +359,8 @@ impl<'a> StringReader<'a> {
         match kind {
             rustc_lexer::LiteralKind::Char { terminated } => {
                 if !terminated {
-                    self.fatal_span_(start, suffix_start, "unterminated character literal").raise()
+                    let msg = self.msg("parser-unterminated-char", &[]);
+                    self.fatal_span_(start, suffix_start, &msg).raise()
                 }
                 let content_start = start + BytePos(1);
                 let content_end = suffix_start - BytePos(1);
@@ -341,8 +370,8 @@ impl<'a> StringReader<'a> {
             }
             rustc_lexer::LiteralKind::Byte { terminated } => {
                 if !terminated {
-                    self.fatal_span_(start + BytePos(1), suffix_start, "unterminated byte constant")
-                        .raise()
+                    let msg = self.msg("parser-unterminated-byte", &[]);
+                    self.fatal_span_(start + BytePos(1), suffix_start, &msg).raise()
                 }
                 let content_start = start + BytePos(2);
                 let content_end = suffix_start - BytePos(1);
@@ -352,8 +381,8 @@ impl<'a> StringReader<'a> {
             }
             rustc_lexer::LiteralKind::Str { terminated } => {
                 if !terminated {
-                    self.fatal_span_(start, suffix_start, "unterminated double quote string")
-                        .raise()
+                    let msg = self.msg("parser-unterminated-string", &[]);
+                    self.fatal_span_(start, suffix_start, &msg).raise()
                 }
                 let content_start = start + BytePos(1);
                 let content_end = suffix_start - BytePos(1);
@@ -363,12 +392,8 @@ impl<'a> StringReader<'a> {
             }
             rustc_lexer::LiteralKind::ByteStr { terminated } => {
                 if !terminated {
-                    self.fatal_span_(
-                        start + BytePos(1),
-                        suffix_start,
-                        "unterminated double quote byte string",
-                    )
-                    .raise()
+                    let msg = self.msg("parser-unterminated-byte-string", &[]);
+                    self.fatal_span_(start + BytePos(1), suffix_start, &msg).raise()
                 }
                 let content_start = start + BytePos(2);
                 let content_end = suffix_start - BytePos(1);
@@ -408,7 +433,7 @@ impl<'a> StringReader<'a> {
             }
             rustc_lexer::LiteralKind::Int { base, empty_int } => {
                 if empty_int {
-                    self.err_span_(start, suffix_start, "no valid digits found for number");
+                    self.err_span_(start, suffix_start, &self.msg("parser-no-digits", &[]));
                     (token::Integer, sym::integer(0))
                 } else {
                     self.validate_int_literal(base, start, suffix_start);
@@ -417,27 +442,19 @@ impl<'a> StringReader<'a> {
             }
             rustc_lexer::LiteralKind::Float { base, empty_exponent } => {
                 if empty_exponent {
-                    let mut err = self.struct_span_fatal(
-                        start,
-                        self.pos,
-                        "expected at least one digit in exponent",
-                    );
+                    let msg = self.msg("parser-empty-exponent", &[]);
+                    let mut err = self.struct_span_fatal(start, self.pos, &msg);
                     err.emit();
                 }
 
-                match base {
-                    Base::Hexadecimal => self.err_span_(
-                        start,
-                        suffix_start,
-                        "hexadecimal float literal is not supported",
-                    ),
-                    Base::Octal => {
-                        self.err_span_(start, suffix_start, "octal float literal is not supported")
-                    }
-                    Base::Binary => {
-                        self.err_span_(start, suffix_start, "binary float literal is not supported")
-                    }
-                    _ => (),
+                let unsupported = match base {
+                    Base::Hexadecimal => Some("parser-hexadecimal-float"),
+                    Base::Octal => Some("parser-octal-float"),
+                    Base::Binary => Some("parser-binary-float"),
+                    _ => None,
+                };
+                if let Some(id) = unsupported {
+                    self.err_span_(start, suffix_start, &self.msg(id, &[]));
                 }
 
                 let id = self.symbol_from_to(start, suffix_start);
@@ -474,14 +491,37 @@ impl<'a> StringReader<'a> {
         &self.src[self.src_index(start)..self.src_index(end)]
     }
 
-    fn forbid_bare_cr(&self, start: BytePos, s: &str, errmsg: &str) {
+    /// Lints the text flow control characters of `s`, the text of the comment
+    /// or literal at `start`, which make the code display differently from
+    /// how it is compiled.
+    ///
+    /// The lint is buffered for the whole crate, as the items have no node ids
+    /// yet, so only an `#![allow]` at the crate root silences it.
+    fn lint_text_flow_control_chars(&self, start: BytePos, s: &str, lint: &'static Lint, id: &str) {
+        if !contains_text_flow_control_chars(s) {
+            return;
+        }
+        let spans = s
+            .char_indices()
+            .filter(|(_, c)| TEXT_FLOW_CONTROL_CHARS.contains(c))
+            .map(|(i, c)| {
+                let lo = start + BytePos::from_usize(i);
+                self.mk_sp(lo, lo + BytePos::from_usize(c.len_utf8()))
+            })
+            .collect();
+        let msg = self.msg(id, &[]);
+        self.sess.buffer_lint(lint, MultiSpan::from_spans(spans), ast::CRATE_NODE_ID, &msg);
+    }
+
+    fn forbid_bare_cr(&self, start: BytePos, s: &str, id: &str) {
+        let errmsg = self.msg(id, &[]);
         let mut idx = 0;
         loop {
             idx = match s[idx..].find('\r') {
                 None => break,
                 Some(it) => idx + it + 1,
             };
-            self.err_span_(start + BytePos(idx as u32 - 1), start + BytePos(idx as u32), errmsg);
+            self.err_span_(start + BytePos(idx as u32 - 1), start + BytePos(idx as u32), &errmsg);
         }
     }
 
@@ -490,8 +530,7 @@ impl<'a> StringReader<'a> {
         self.struct_fatal_span_char(
             start,
             self.pos,
-            "found invalid character; only `#` is allowed \
-                 in raw string delimitation",
+            "parser-invalid-raw-string-delimiter",
             bad_char,
         )
         .emit();
@@ -501,16 +540,15 @@ impl<'a> StringReader<'a> {
     fn report_unterminated_raw_string(&self, start: BytePos, n_hashes: usize) -> ! {
         let mut err = self.sess.span_diagnostic.struct_span_fatal_with_code(
             self.mk_sp(start, start),
-            "unterminated raw string",
+            &self.msg("parser-unterminated-raw-string", &[]),
             error_code!(E0748),
         );
-        err.span_label(self.mk_sp(start, start), "unterminated raw string");
+        let label = self.msg("parser-unterminated-raw-string.label", &[]);
+        err.span_label(self.mk_sp(start, start), label);
 
         if n_hashes > 0 {
-            err.note(&format!(
-                "this raw string should be terminated with `\"{}`",
-                "#".repeat(n_hashes as usize)
-            ));
+            let hashes = "#".repeat(n_hashes as usize);
+            err.note(&self.msg("parser-unterminated-raw-string.note", &[("hashes", hashes)]));
         }
 
         err.emit();
@@ -521,13 +559,8 @@ impl<'a> StringReader<'a> {
         match n_hashes.try_into() {
             Ok(n_hashes) => n_hashes,
             Err(_) => {
-                self.fatal_span_(
-                    start,
-                    self.pos,
-                    "too many `#` symbols: raw strings may be \
-                                  delimited by up to 65535 `#` symbols",
-                )
-                .raise();
+                let msg = self.msg("parser-too-many-raw-string-hashes", &[]);
+                self.fatal_span_(start, self.pos, &msg).raise();
             }
         }
     }
@@ -636,7 +669,8 @@ impl<'a> StringReader<'a> {
             if c != '_' && c.to_digit(base).is_none() {
                 let lo = content_start + BytePos(2 + idx);
                 let hi = content_start + BytePos(2 + idx + c.len_utf8() as u32);
-                self.err_span_(lo, hi, &format!("invalid digit for a base {} literal", base));
+                let msg = self.msg("parser-invalid-digit", &[("base", base.to_string())]);
+                self.err_span_(lo, hi, &msg);
             }
         }
     }
@@ -64,8 +64,12 @@ impl CheckAttrVisitor<'tcx> {
                 self.check_non_exhaustive(attr, span, target)
             } else if attr.check_name(sym::marker) {
                 self.check_marker(attr, span, target)
+            } else if attr.check_name(sym::must_not_suspend) {
+                self.check_must_not_suspend(attr, span, target)
             } else if attr.check_name(sym::target_feature) {
                 self.check_target_feature(attr, span, target)
+            } else if attr.check_name(sym::custom_mir) {
+                self.check_custom_mir(attr, span, target)
             } else if attr.check_name(sym::track_caller) {
                 self.check_track_caller(&attr.span, attrs, span, target)
             } else {
@@ -209,6 +213,25 @@ impl CheckAttrVisitor<'tcx> {
         }
     }
 
+    /// Checks if the `#[must_not_suspend]` attribute on an `item` is valid. Returns `true` if
+    /// valid.
+    fn check_must_not_suspend(&self, attr: &Attribute, span: &Span, target: Target) -> bool {
+        match target {
+            Target::Struct | Target::Enum | Target::Union => true,
+            _ => {
+                self.tcx
+                    .sess
+                    .struct_span_err(
+                        attr.span,
+                        "attribute can only be applied to a struct, enum or union",
+                    )
+                    .span_label(*span, "not a struct, enum or union")
+                    .emit();
+                false
+            }
+        }
+    }
+
     /// Checks if the `#[target_feature]` attribute on `item` is valid. Returns `true` if valid.
     fn check_target_feature(&self, attr: &Attribute, span: &Span, target: Target) -> bool {
         match target {
@@ -226,6 +249,25 @@ impl CheckAttrVisitor<'tcx> {
         }
     }
 
+    /// Checks if the `#[custom_mir]` attribute on `item` is valid. Returns `true` if valid.
+    fn check_custom_mir(&self, attr: &Attribute, span: &Span, target: Target) -> bool {
+        match target {
+            Target::Fn => true,
+            _ => {
+                self.emit_custom_mir_error(attr.span, *span);
+                false
+            }
+        }
+    }
+
+    fn emit_custom_mir_error(&self, attr_span: Span, span: Span) {
+        self.tcx
+            .sess
+            .struct_span_err(attr_span, "attribute should be applied to a function item")
+            .span_label(span, "not a function item")
+            .emit();
+    }
+
     /// Checks if the `#[repr]` attributes on `item` are valid.
     fn check_repr(
         &self,
@@ -401,6 +443,9 @@ impl CheckAttrVisitor<'tcx> {
                     "not defining a struct, enum, or union",
                 );
             }
+            if attr.check_name(sym::custom_mir) {
+                self.emit_custom_mir_error(attr.span, expr.span);
+            }
         }
     }
 
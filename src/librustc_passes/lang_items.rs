@@ -28,6 +28,12 @@ struct LanguageItemCollector<'tcx> {
 
 impl ItemLikeVisitor<'v> for LanguageItemCollector<'tcx> {
     fn visit_item(&mut self, item: &hir::Item<'_>) {
+        if let hir::ItemKind::ForeignMod(ref foreign_mod) = item.kind {
+            for foreign_item in foreign_mod.items {
+                self.visit_foreign_item(foreign_item);
+            }
+        }
+
         if let Some((value, span)) = extract(&item.attrs) {
             let actual_target = Target::from_item(item);
             match ITEM_REFS.get(&*value.as_str()).cloned() {
@@ -85,6 +91,20 @@ impl LanguageItemCollector<'tcx> {
         LanguageItemCollector { tcx, items: LanguageItems::new() }
     }
 
+    fn visit_foreign_item(&mut self, foreign_item: &hir::ForeignItem<'_>) {
+        // Foreign items with a lang attribute are usually declarations of weak lang items, which
+        // are checked in `weak_lang_items`. Only collect the ones that are lang items themselves,
+        // like the `count_code_region` intrinsic.
+        if let Some((value, _)) = extract(&foreign_item.attrs) {
+            if let Some((item_index, expected_target)) = ITEM_REFS.get(&*value.as_str()).cloned() {
+                if Target::from_foreign_item(foreign_item) == expected_target {
+                    let def_id = self.tcx.hir().local_def_id(foreign_item.hir_id);
+                    self.collect_item(item_index, def_id);
+                }
+            }
+        }
+    }
+
     fn collect_item(&mut self, item_index: usize, item_def_id: DefId) {
         // Check for duplicates.
         if let Some(original_def_id) = self.items.items[item_index] {
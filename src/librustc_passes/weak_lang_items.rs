@@ -8,6 +8,7 @@ use rustc_errors::struct_span_err;
 use rustc_hir as hir;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_hir::weak_lang_items::WEAK_ITEMS_REFS;
+use rustc_hir::Target;
 use rustc_session::config;
 use rustc_span::symbol::Symbol;
 use rustc_span::Span;
@@ -91,7 +92,12 @@ impl<'a, 'tcx, 'v> Visitor<'v> for Context<'a, 'tcx> {
 
     fn visit_foreign_item(&mut self, i: &hir::ForeignItem<'_>) {
         if let Some((lang_item, _)) = hir::lang_items::extract(&i.attrs) {
-            self.register(lang_item, i.span);
+            // Some lang items are foreign functions themselves rather than declarations of a weak
+            // lang item defined elsewhere; those are collected with the other lang items.
+            match hir::lang_items::ITEM_REFS.get(&*lang_item.as_str()) {
+                Some(&(_, Target::ForeignFn)) => {}
+                _ => self.register(lang_item, i.span),
+            }
         }
         intravisit::walk_foreign_item(self, i)
     }
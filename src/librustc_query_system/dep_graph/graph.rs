@@ -92,6 +92,52 @@ struct DepGraphData<K: DepKind> {
     previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode<K>, String>>,
+
+    /// Why the nodes of the previous session were re-executed, recorded only
+    /// for `-Z incremental-explain`.
+    explain: Option<ExplainData>,
+}
+
+/// Why a node of the previous session could not be marked green.
+#[derive(Clone, Copy)]
+enum RedCause {
+    /// This dependency was red.
+    Dependency(SerializedDepNodeIndex),
+    /// This dependency was not red, but it could not be marked green nor be
+    /// forced to find out.
+    NotForced(SerializedDepNodeIndex),
+}
+
+#[derive(Default)]
+struct ExplainData {
+    /// The dependency that kept each node that could not be marked green from
+    /// being marked green.
+    causes: Lock<FxHashMap<SerializedDepNodeIndex, RedCause>>,
+    /// The nodes of the previous session that were executed again, in order,
+    /// except for the `eval_always` ones.
+    reexecuted: Lock<Vec<SerializedDepNodeIndex>>,
+}
+
+/// Why a query of the previous session was executed again rather than marked
+/// green, as reported by `-Z incremental-explain`.
+pub struct ReexecutedQuery<K> {
+    /// The node of the query.
+    pub node: DepNode<K>,
+    /// Whether the result of the query changed, which made it red in turn.
+    pub changed: bool,
+    /// The dependencies that kept the query from being marked green: the
+    /// first one is a dependency of `node`, and each other one a dependency of
+    /// the previous one. The last one is usually an input whose hash changed,
+    /// like a HIR owner.
+    pub chain: Vec<RedDependency<K>>,
+}
+
+/// A dependency that kept a node from being marked green.
+pub struct RedDependency<K> {
+    pub node: DepNode<K>,
+    /// Whether the dependency was red. If not, it could not be marked green,
+    /// and its query could not be forced to find out whether it changed.
+    pub red: bool,
 }
 
 pub fn hash_result<HashCtxt, R>(hcx: &mut HashCtxt, result: &R) -> Option<Fingerprint>
@@ -108,6 +154,7 @@ impl<K: DepKind> DepGraph<K> {
     pub fn new(
         prev_graph: PreviousDepGraph<K>,
         prev_work_products: FxHashMap<WorkProductId, WorkProduct>,
+        explain: bool,
     ) -> DepGraph<K> {
         let prev_graph_node_count = prev_graph.node_count();
 
@@ -120,6 +167,7 @@ impl<K: DepKind> DepGraph<K> {
                 emitting_diagnostics_cond_var: Condvar::new(),
                 previous: prev_graph,
                 colors: DepNodeColorMap::new(prev_graph_node_count),
+                explain: if explain { Some(Default::default()) } else { None },
             })),
             virtual_dep_node_index: Lrc::new(AtomicU32::new(0)),
         }
@@ -302,6 +350,12 @@ impl<K: DepKind> DepGraph<K> {
                 );
 
                 data.colors.insert(prev_index, color);
+
+                if let Some(ref explain) = data.explain {
+                    if !key.kind.is_eval_always() {
+                        explain.reexecuted.lock().push(prev_index);
+                    }
+                }
             } else {
                 if print_status {
                     eprintln!("[task::new] {:?}", key);
@@ -464,6 +518,44 @@ impl<K: DepKind> DepGraph<K> {
         }
     }
 
+    /// Returns the queries of the previous session that were executed again,
+    /// in order, with the chain of dependencies that kept each of them from
+    /// being marked green. This is only recorded for `-Z incremental-explain`.
+    pub fn reexecuted_queries(&self) -> Vec<ReexecutedQuery<K>> {
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return Vec::new(),
+        };
+        let explain = match data.explain {
+            Some(ref explain) => explain,
+            None => return Vec::new(),
+        };
+        let causes = explain.causes.lock();
+
+        explain
+            .reexecuted
+            .lock()
+            .iter()
+            .map(|&prev_index| {
+                let mut chain = Vec::new();
+                let mut index = prev_index;
+                while let Some(&cause) = causes.get(&index) {
+                    let (dep_index, red) = match cause {
+                        RedCause::Dependency(dep_index) => (dep_index, true),
+                        RedCause::NotForced(dep_index) => (dep_index, false),
+                    };
+                    chain.push(RedDependency { node: data.previous.index_to_node(dep_index), red });
+                    index = dep_index;
+                }
+                ReexecutedQuery {
+                    node: data.previous.index_to_node(prev_index),
+                    changed: data.colors.get(prev_index) == Some(DepNodeColor::Red),
+                    chain,
+                }
+            })
+            .collect()
+    }
+
     pub fn serialize(&self) -> SerializedDepGraph<K> {
         let data = self.data.as_ref().unwrap().current.data.lock();
 
@@ -608,6 +700,10 @@ impl<K: DepKind> DepGraph<K> {
                         dep_node,
                         data.previous.index_to_node(dep_dep_node_index)
                     );
+                    data.record_red_cause(
+                        prev_dep_node_index,
+                        RedCause::Dependency(dep_dep_node_index),
+                    );
                     return None;
                 }
                 None => {
@@ -663,6 +759,10 @@ impl<K: DepKind> DepGraph<K> {
                                         dependency {:?} was red after forcing",
                                     dep_node, dep_dep_node
                                 );
+                                data.record_red_cause(
+                                    prev_dep_node_index,
+                                    RedCause::Dependency(dep_dep_node_index),
+                                );
                                 return None;
                             }
                             None => {
@@ -698,6 +798,10 @@ impl<K: DepKind> DepGraph<K> {
                                 could not be forced",
                             dep_node, dep_dep_node
                         );
+                        data.record_red_cause(
+                            prev_dep_node_index,
+                            RedCause::NotForced(dep_dep_node_index),
+                        );
                         return None;
                     }
                 }
@@ -883,6 +987,7 @@ pub enum WorkProductFileKind {
     Object,
     Bytecode,
     BytecodeCompressed,
+    DwarfObject,
 }
 
 #[derive(Clone)]
@@ -1042,6 +1147,15 @@ impl<K: DepKind> CurrentDepGraph<K> {
 }
 
 impl<K: DepKind> DepGraphData<K> {
+    /// Records why the given node of the previous session could not be marked
+    /// green, for `-Z incremental-explain`. Only the first cause is kept, as
+    /// the node is only re-executed once.
+    fn record_red_cause(&self, prev_index: SerializedDepNodeIndex, cause: RedCause) {
+        if let Some(ref explain) = self.explain {
+            explain.causes.lock().entry(prev_index).or_insert(cause);
+        }
+    }
+
     #[inline(never)]
     fn read_index(&self, source: DepNodeIndex) {
         K::read_deps(|task_deps| {
@@ -9,6 +9,7 @@ mod serialized;
 pub use dep_node::{DepNode, DepNodeParams, WorkProductId};
 pub use graph::WorkProductFileKind;
 pub use graph::{hash_result, DepGraph, DepNodeColor, DepNodeIndex, TaskDeps, WorkProduct};
+pub use graph::{RedDependency, ReexecutedQuery};
 pub use prev::PreviousDepGraph;
 pub use query::DepGraphQuery;
 pub use safe::AssertDepGraphSafe;
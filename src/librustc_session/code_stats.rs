@@ -1,7 +1,11 @@
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::sync::Lock;
+use rustc_serialize::json::as_pretty_json;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::fs;
+use std::io;
+use std::path::Path;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -26,6 +30,33 @@ pub struct FieldInfo {
     pub align: u64,
 }
 
+/// How the discriminant of an enum is stored.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DiscrKind {
+    /// In a tag of its own.
+    Tag,
+    /// In the niche of a field of `dataful_variant`, where the discriminants of the other
+    /// variants are encoded starting at `niche_start`.
+    Niche { dataful_variant: String, niche_start: u128 },
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DiscrInfo {
+    pub kind: DiscrKind,
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// The largest range of invalid values of a type, which enums containing it can use to store
+/// their discriminant.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    /// The number of invalid values.
+    pub available: u128,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DataTypeKind {
     Struct,
@@ -41,10 +72,72 @@ pub struct TypeSizeInfo {
     pub align: u64,
     pub overall_size: u64,
     pub packed: bool,
-    pub opt_discr_size: Option<u64>,
+    pub discr: Option<DiscrInfo>,
+    pub niche: Option<NicheInfo>,
+    /// The variants in source order.
     pub variants: Vec<VariantInfo>,
 }
 
+impl TypeSizeInfo {
+    /// The size of the tag of an enum, if it has one.
+    fn tag_size(&self) -> Option<u64> {
+        match self.discr {
+            Some(DiscrInfo { kind: DiscrKind::Tag, size, .. }) => Some(size),
+            _ => None,
+        }
+    }
+}
+
+// The shape of the entries of the `-Z type-layout-report` file. It is kept flat, so that the
+// reports of two builds can be compared with a plain `diff`.
+
+#[derive(RustcEncodable)]
+struct TypeLayout<'a> {
+    name: &'a str,
+    kind: &'static str,
+    size: u64,
+    align: u64,
+    packed: bool,
+    discriminant: Option<DiscriminantLayout<'a>>,
+    niche: Option<NicheLayout>,
+    variants: Vec<VariantLayout<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct DiscriminantLayout<'a> {
+    /// `"tag"` or `"niche"`.
+    encoding: &'static str,
+    offset: u64,
+    size: u64,
+    dataful_variant: Option<&'a str>,
+    niche_start: Option<u128>,
+}
+
+#[derive(RustcEncodable)]
+struct NicheLayout {
+    offset: u64,
+    size: u64,
+    available: u128,
+}
+
+#[derive(RustcEncodable)]
+struct VariantLayout<'a> {
+    name: Option<&'a str>,
+    /// The size is only a lower bound for unsized types.
+    sized: bool,
+    size: u64,
+    align: u64,
+    fields: Vec<FieldLayout<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct FieldLayout<'a> {
+    name: &'a str,
+    offset: u64,
+    size: u64,
+    align: u64,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
@@ -58,20 +151,18 @@ impl CodeStats {
         align: Align,
         overall_size: Size,
         packed: bool,
-        opt_discr_size: Option<Size>,
-        mut variants: Vec<VariantInfo>,
+        discr: Option<DiscrInfo>,
+        niche: Option<NicheInfo>,
+        variants: Vec<VariantInfo>,
     ) {
-        // Sort variants so the largest ones are shown first. A stable sort is
-        // used here so that source code order is preserved for all variants
-        // that have the same size.
-        variants.sort_by(|info1, info2| info2.size.cmp(&info1.size));
         let info = TypeSizeInfo {
             kind,
             type_description: type_desc.to_string(),
             align: align.bytes(),
             overall_size: overall_size.bytes(),
             packed,
-            opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            discr,
+            niche,
             variants,
         };
         self.type_sizes.borrow_mut().insert(info);
@@ -98,7 +189,7 @@ impl CodeStats {
             );
             let indent = "    ";
 
-            let discr_size = if let Some(discr_size) = info.opt_discr_size {
+            let discr_size = if let Some(discr_size) = info.tag_size() {
                 println!("print-type-size {}discriminant: {} bytes", indent, discr_size);
                 discr_size
             } else {
@@ -115,7 +206,12 @@ impl CodeStats {
                 DataTypeKind::Struct | DataTypeKind::Closure => true,
                 DataTypeKind::Enum | DataTypeKind::Union => false,
             };
-            for (i, variant_info) in info.variants.iter().enumerate() {
+            // Sort variants so the largest ones are shown first. A stable sort is
+            // used here so that source code order is preserved for all variants
+            // that have the same size.
+            let mut variants: Vec<_> = info.variants.iter().enumerate().collect();
+            variants.sort_by(|(_, info1), (_, info2)| info2.size.cmp(&info1.size));
+            for (i, variant_info) in variants {
                 let VariantInfo { ref name, kind: _, align: _, size, ref fields } = *variant_info;
                 let indent = if !struct_like {
                     let name = match name.as_ref() {
@@ -190,4 +286,72 @@ impl CodeStats {
             }
         }
     }
+
+    /// Writes the layouts of all the recorded types to `path`, as JSON, for
+    /// `-Z type-layout-report`. The types are sorted by name, and the variants
+    /// and fields are kept in source order, so that the reports of two builds
+    /// can be diffed.
+    pub fn write_type_layout_report(&self, path: &Path) -> io::Result<()> {
+        let type_sizes = self.type_sizes.borrow();
+        let mut sorted: Vec<_> = type_sizes.iter().collect();
+        sorted.sort_by(|info1, info2| info1.type_description.cmp(&info2.type_description));
+
+        let report: Vec<_> = sorted
+            .into_iter()
+            .map(|info| TypeLayout {
+                name: &info.type_description,
+                kind: match info.kind {
+                    DataTypeKind::Struct => "struct",
+                    DataTypeKind::Union => "union",
+                    DataTypeKind::Enum => "enum",
+                    DataTypeKind::Closure => "closure",
+                },
+                size: info.overall_size,
+                align: info.align,
+                packed: info.packed,
+                discriminant: info.discr.as_ref().map(|discr| {
+                    let (encoding, dataful_variant, niche_start) = match discr.kind {
+                        DiscrKind::Tag => ("tag", None, None),
+                        DiscrKind::Niche { ref dataful_variant, niche_start } => {
+                            ("niche", Some(&dataful_variant[..]), Some(niche_start))
+                        }
+                    };
+                    DiscriminantLayout {
+                        encoding,
+                        offset: discr.offset,
+                        size: discr.size,
+                        dataful_variant,
+                        niche_start,
+                    }
+                }),
+                niche: info.niche.as_ref().map(|niche| NicheLayout {
+                    offset: niche.offset,
+                    size: niche.size,
+                    available: niche.available,
+                }),
+                variants: info
+                    .variants
+                    .iter()
+                    .map(|variant| VariantLayout {
+                        name: variant.name.as_deref(),
+                        sized: variant.kind == SizeKind::Exact,
+                        size: variant.size,
+                        align: variant.align,
+                        fields: variant
+                            .fields
+                            .iter()
+                            .map(|field| FieldLayout {
+                                name: &field.name,
+                                offset: field.offset,
+                                size: field.size,
+                                align: field.align,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        fs::write(path, format!("{}\n", as_pretty_json(&report)))
+    }
 }
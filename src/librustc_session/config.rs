@@ -4,6 +4,7 @@
 pub use crate::options::*;
 
 use crate::lint;
+use crate::lint::config_file::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLibraryKind;
 use crate::{early_error, early_warn, Session};
@@ -42,6 +43,9 @@ pub enum Sanitizer {
     Leak,
     Memory,
     Thread,
+    Cfi,
+    HwAddress,
+    ShadowCallStack,
 }
 
 impl fmt::Display for Sanitizer {
@@ -51,6 +55,9 @@ impl fmt::Display for Sanitizer {
             Sanitizer::Leak => "leak".fmt(f),
             Sanitizer::Memory => "memory".fmt(f),
             Sanitizer::Thread => "thread".fmt(f),
+            Sanitizer::Cfi => "cfi".fmt(f),
+            Sanitizer::HwAddress => "hwaddress".fmt(f),
+            Sanitizer::ShadowCallStack => "shadow-call-stack".fmt(f),
         }
     }
 }
@@ -63,6 +70,9 @@ impl FromStr for Sanitizer {
             "leak" => Ok(Sanitizer::Leak),
             "memory" => Ok(Sanitizer::Memory),
             "thread" => Ok(Sanitizer::Thread),
+            "cfi" => Ok(Sanitizer::Cfi),
+            "hwaddress" => Ok(Sanitizer::HwAddress),
+            "shadow-call-stack" => Ok(Sanitizer::ShadowCallStack),
             _ => Err(()),
         }
     }
@@ -81,6 +91,34 @@ pub enum CFGuard {
     Checks,
 }
 
+/// The different settings that the `-C strip` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum Strip {
+    /// Do not strip anything from the linked output.
+    None,
+
+    /// Strip the debuginfo from the linked output.
+    Debuginfo,
+
+    /// Strip the debuginfo and the symbol table from the linked output.
+    Symbols,
+}
+
+/// The different settings that the `-C split-debuginfo` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SplitDebuginfo {
+    /// Keep the DWARF in the object files and let the linker copy it into the output.
+    Off,
+
+    /// Write the DWARF of each codegen unit to a `.dwo` file next to its object file, and leave
+    /// the `.dwo` files in place for the debugger to find.
+    Unpacked,
+
+    /// Like `Unpacked`, but package the `.dwo` files of the output into a single `.dwp` file
+    /// with `llvm-dwp` once it is linked.
+    Packed,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -159,6 +197,16 @@ impl SwitchWithOptPath {
     }
 }
 
+/// The formats of the report of `-Z incremental-explain`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum IncrementalExplain {
+    /// One line per re-executed query, followed by its changed dependencies.
+    Text,
+
+    /// One JSON object per re-executed query.
+    Json,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, RustcEncodable, RustcDecodable)]
 pub enum SymbolManglingVersion {
     Legacy,
@@ -268,6 +316,8 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A SARIF log, consumed by code scanning tools.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -523,6 +573,11 @@ impl OutputFilenames {
         self.with_extension(&extension)
     }
 
+    /// The path of the split DWARF file of a codegen unit, for `-C split-debuginfo`.
+    pub fn split_dwarf_path(&self, codegen_unit_name: Option<&str>) -> PathBuf {
+        self.temp_path_ext("dwo", codegen_unit_name)
+    }
+
     pub fn with_extension(&self, extension: &str) -> PathBuf {
         let mut path = self.out_directory.join(&self.filestem);
         path.set_extension(extension);
@@ -550,6 +605,7 @@ impl Default for Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: LintConfig::default(),
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -628,6 +684,7 @@ impl DebuggingOptions {
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics.unwrap_or(true),
+            diagnostic_summary: self.diagnostic_summary,
         }
     }
 }
@@ -1109,6 +1166,7 @@ pub fn parse_error_format(
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
@@ -1192,6 +1250,9 @@ fn check_debug_option_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(ErrorOutputType::Sarif, "`--error-format=sarif` is unstable");
+        }
     }
 }
 
@@ -1638,6 +1699,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let mut debugging_opts = build_debugging_options(matches, error_format);
     check_debug_option_stability(&debugging_opts, error_format, json_rendered);
 
+    let lint_config = match &debugging_opts.lint_config {
+        Some(path) => LintConfig::load(path).unwrap_or_else(|e| {
+            early_error(
+                error_format,
+                &format!("failed to read lint configuration file `{}`: {}", path.display(), e),
+            )
+        }),
+        None => LintConfig::default(),
+    };
+
     let output_types = parse_output_types(&debugging_opts, matches, error_format);
 
     let mut cg = build_codegen_options(matches, error_format);
@@ -1666,6 +1737,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         );
     }
 
+    if cg.profile_sample_use.is_some()
+        && (cg.profile_generate.enabled() || cg.profile_use.is_some())
+    {
+        early_error(
+            error_format,
+            "option `-C profile-sample-use` cannot be combined with `-C profile-generate` or \
+             `-C profile-use`",
+        );
+    }
+
     let prints = collect_print_requests(&mut cg, &mut debugging_opts, matches, error_format);
 
     let cg = cg;
@@ -1708,6 +1789,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_config,
         describe_lints,
         output_types,
         search_paths,
@@ -1971,7 +2053,7 @@ impl PpMode {
 crate mod dep_tracking {
     use super::{
         CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, Sanitizer, SwitchWithOptPath, SymbolManglingVersion,
+        OutputTypes, Passes, Sanitizer, SplitDebuginfo, SwitchWithOptPath, SymbolManglingVersion,
     };
     use crate::lint;
     use crate::utils::NativeLibraryKind;
@@ -2019,6 +2101,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(String);
     impl_dep_tracking_hash_via_hash!(PathBuf);
     impl_dep_tracking_hash_via_hash!(lint::Level);
+    impl_dep_tracking_hash_via_hash!(lint::config_file::LintConfig);
     impl_dep_tracking_hash_via_hash!(Option<bool>);
     impl_dep_tracking_hash_via_hash!(Option<usize>);
     impl_dep_tracking_hash_via_hash!(Option<String>);
@@ -2044,6 +2127,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Sanitizer);
     impl_dep_tracking_hash_via_hash!(Option<Sanitizer>);
     impl_dep_tracking_hash_via_hash!(CFGuard);
+    impl_dep_tracking_hash_via_hash!(SplitDebuginfo);
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
@@ -7,6 +7,7 @@
 use crate::lint::FutureIncompatibleInfo;
 use crate::{declare_lint, declare_lint_pass};
 use rustc_span::edition::Edition;
+use rustc_span::symbol::sym;
 
 declare_lint! {
     pub ILL_FORMED_ATTRIBUTE_INPUT,
@@ -496,6 +497,27 @@ declare_lint! {
     "detects incompatible use of `#[inline(always)]` and `#[no_sanitize(...)]`",
 }
 
+// The lexer emits the two lints below before there are node ids for the items, so they are
+// buffered for the crate and only `#![allow]` at the crate root affects them.
+declare_lint! {
+    pub TEXT_DIRECTION_CODEPOINT_IN_COMMENT,
+    Warn,
+    "detects Unicode codepoints in comments that change the visual direction of text",
+}
+
+declare_lint! {
+    pub TEXT_DIRECTION_CODEPOINT_IN_LITERAL,
+    Warn,
+    "detects Unicode codepoints in literals that change the visual direction of text",
+}
+
+declare_lint! {
+    pub MUST_NOT_SUSPEND,
+    Allow,
+    "detects values of `#[must_not_suspend]` types held across suspension points",
+    @feature_gate = sym::must_not_suspend;
+}
+
 declare_lint_pass! {
     /// Does nothing as a lint pass, but registers some `Lint`s
     /// that are used by other parts of the compiler.
@@ -562,6 +584,9 @@ declare_lint_pass! {
         INDIRECT_STRUCTURAL_MATCH,
         SOFT_UNSTABLE,
         INLINE_NO_SANITIZE,
+        TEXT_DIRECTION_CODEPOINT_IN_COMMENT,
+        TEXT_DIRECTION_CODEPOINT_IN_LITERAL,
+        MUST_NOT_SUSPEND,
     ]
 }
 
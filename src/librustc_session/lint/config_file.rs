@@ -0,0 +1,166 @@
+//! Lint configuration files, read with `-Z lint-config`.
+//!
+//! A lint configuration file is a JSON object setting the levels of lints
+//! and lint groups, and declaring custom lint groups, so that the crates of a
+//! workspace can share one lint policy:
+//!
+//! ```json
+//! {
+//!     "groups": {
+//!         "our_style": ["unused_parens", "non_snake_case"]
+//!     },
+//!     "levels": {
+//!         "unused": "warn",
+//!         "unused_mut": "deny",
+//!         "our_style": "deny"
+//!     }
+//! }
+//! ```
+//!
+//! The levels apply as if they were passed on the command line before any
+//! `-A`, `-W`, `-D` or `-F` flag, so the flags override the file, and the
+//! levels of lint groups apply before the levels of single lints. Both apply
+//! in the order of the file, so a later group overrides an earlier one that
+//! overlaps it. A custom group can contain custom groups declared after it.
+
+use super::Level;
+use rustc_serialize::json::{JsonEvent, Parser, ParserError, StackElement};
+
+use std::fs;
+use std::path::Path;
+use std::str::Chars;
+
+/// The contents of a lint configuration file.
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct LintConfig {
+    /// The levels of lints and lint groups, in the order of the file.
+    pub levels: Vec<(String, Level)>,
+    /// The custom lint groups and the lints and lint groups they contain, in
+    /// the order of the file.
+    pub groups: Vec<(String, Vec<String>)>,
+}
+
+impl LintConfig {
+    /// Reads the lint configuration file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        LintConfig::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<LintConfig, String> {
+        let object = match Value::parse(contents)? {
+            Value::Object(object) => object,
+            _ => return Err("expected an object".to_string()),
+        };
+
+        let mut config = LintConfig::default();
+        for (key, value) in object {
+            match (&key[..], value) {
+                ("levels", Value::Object(levels)) => {
+                    for (name, level) in levels {
+                        let level = match level {
+                            Value::String(level) => Level::from_str(&level),
+                            _ => None,
+                        }
+                        .ok_or_else(|| format!("invalid level for `{}`", name))?;
+                        config.levels.push((name.replace("-", "_"), level));
+                    }
+                }
+                ("groups", Value::Object(groups)) => {
+                    for (name, lints) in groups {
+                        let lints = match lints {
+                            Value::Array(lints) => lints
+                                .into_iter()
+                                .map(|lint| match lint {
+                                    Value::String(lint) => Some(lint.replace("-", "_")),
+                                    _ => None,
+                                })
+                                .collect::<Option<Vec<_>>>(),
+                            _ => None,
+                        }
+                        .ok_or_else(|| format!("expected an array of lint names for `{}`", name))?;
+                        config.groups.push((name.replace("-", "_"), lints));
+                    }
+                }
+                ("levels", _) | ("groups", _) => {
+                    return Err(format!("expected an object for `{}`", key));
+                }
+                _ => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// A JSON value whose objects keep their members in the order of the file,
+/// unlike those of `Json`, which are sorted by key: the levels of overlapping
+/// lint groups apply in the order of the file.
+enum Value {
+    Object(Vec<(String, Value)>),
+    Array(Vec<Value>),
+    String(String),
+    /// Any other value, which a lint configuration file never expects.
+    Other,
+}
+
+impl Value {
+    fn parse(contents: &str) -> Result<Value, String> {
+        let mut parser = Parser::new(contents.chars());
+        let value = match parser.next() {
+            Some(event) => Value::build(&mut parser, event)?,
+            None => return Err("expected a value".to_string()),
+        };
+        match parser.next() {
+            None => Ok(value),
+            Some(JsonEvent::Error(e)) => Err(parser_error_message(e)),
+            Some(_) => Err("trailing characters".to_string()),
+        }
+    }
+
+    /// Builds the value that starts with `event`.
+    fn build(parser: &mut Parser<Chars<'_>>, event: JsonEvent) -> Result<Value, String> {
+        Ok(match event {
+            JsonEvent::ObjectStart => {
+                let mut members = Vec::new();
+                loop {
+                    let event = match parser.next() {
+                        Some(JsonEvent::ObjectEnd) => break,
+                        Some(JsonEvent::Error(e)) => return Err(parser_error_message(e)),
+                        Some(event) => event,
+                        None => return Err("unexpected end of file".to_string()),
+                    };
+                    // The parser pushes the key of a member before its value.
+                    let key = match parser.stack().top() {
+                        Some(StackElement::Key(key)) => key.to_string(),
+                        _ => return Err("expected a key".to_string()),
+                    };
+                    members.push((key, Value::build(parser, event)?));
+                }
+                Value::Object(members)
+            }
+            JsonEvent::ArrayStart => {
+                let mut elements = Vec::new();
+                loop {
+                    match parser.next() {
+                        Some(JsonEvent::ArrayEnd) => break,
+                        Some(event) => elements.push(Value::build(parser, event)?),
+                        None => return Err("unexpected end of file".to_string()),
+                    }
+                }
+                Value::Array(elements)
+            }
+            JsonEvent::StringValue(s) => Value::String(s),
+            JsonEvent::Error(e) => return Err(parser_error_message(e)),
+            _ => Value::Other,
+        })
+    }
+}
+
+fn parser_error_message(error: ParserError) -> String {
+    match error {
+        ParserError::SyntaxError(code, line, col) => {
+            format!("{} at line {}, column {}", code, line, col)
+        }
+        ParserError::IoError(_, msg) => msg,
+    }
+}
@@ -89,6 +89,9 @@ top_level_options!(
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED],
         lint_cap: Option<lint::Level> [TRACKED],
+        // The contents of the lint configuration file given with
+        // `-Z lint-config`, whose path is not tracked.
+        lint_config: lint::config_file::LintConfig [TRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -257,12 +260,17 @@ macro_rules! options {
         pub const parse_relro_level: Option<&str> =
             Some("one of: `full`, `partial`, or `off`");
         pub const parse_sanitizer: Option<&str> =
-            Some("one of: `address`, `leak`, `memory` or `thread`");
+            Some("one of: `address`, `cfi`, `hwaddress`, `leak`, `memory`, `shadow-call-stack` \
+                  or `thread`");
         pub const parse_sanitizer_list: Option<&str> =
             Some("comma separated list of sanitizers");
         pub const parse_sanitizer_memory_track_origins: Option<&str> = None;
         pub const parse_cfguard: Option<&str> =
             Some("either `disabled`, `nochecks`, or `checks`");
+        pub const parse_split_debuginfo: Option<&str> =
+            Some("one of: `off`, `unpacked`, or `packed`");
+        pub const parse_strip: Option<&str> =
+            Some("one of: `none`, `debuginfo`, or `symbols`");
         pub const parse_linker_flavor: Option<&str> =
             Some(::rustc_target::spec::LinkerFlavor::one_of());
         pub const parse_optimization_fuel: Option<&str> =
@@ -283,12 +291,14 @@ macro_rules! options {
             Some("one of: `disabled`, `trampolines`, or `aliases`");
         pub const parse_symbol_mangling_version: Option<&str> =
             Some("either `legacy` or `v0` (RFC 2603)");
+        pub const parse_incremental_explain: Option<&str> =
+            Some("either `text` or `json`, or omitted");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-            SymbolManglingVersion, CFGuard};
+            SymbolManglingVersion, CFGuard, SplitDebuginfo, Strip, IncrementalExplain};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -512,6 +522,26 @@ macro_rules! options {
             true
         }
 
+        fn parse_split_debuginfo(slot: &mut SplitDebuginfo, v: Option<&str>) -> bool {
+            match v {
+                Some("off") => *slot = SplitDebuginfo::Off,
+                Some("unpacked") => *slot = SplitDebuginfo::Unpacked,
+                Some("packed") => *slot = SplitDebuginfo::Packed,
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_strip(slot: &mut Strip, v: Option<&str>) -> bool {
+            match v {
+                Some("none") => *slot = Strip::None,
+                Some("debuginfo") => *slot = Strip::Debuginfo,
+                Some("symbols") => *slot = Strip::Symbols,
+                _ => return false,
+            }
+            true
+        }
+
         fn parse_linker_flavor(slote: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
             match v.and_then(LinkerFlavor::from_str) {
                 Some(lf) => *slote = Some(lf),
@@ -622,6 +652,18 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_incremental_explain(
+            slot: &mut Option<IncrementalExplain>,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = match v {
+                None | Some("text") => Some(IncrementalExplain::Text),
+                Some("json") => Some(IncrementalExplain::Json),
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -710,6 +752,15 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "compile the program with profiling instrumentation"),
     profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "use the given `.profdata` file for profile-guided optimization"),
+    split_debuginfo: SplitDebuginfo = (SplitDebuginfo::Off, parse_split_debuginfo, [TRACKED],
+        "write the debuginfo of each codegen unit to a separate `.dwo` file, and optionally \
+         package them into a `.dwp` file (Linux only)"),
+    strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
+        "tell the linker which information to strip from the output \
+         (`none` (default), `debuginfo` or `symbols`)"),
+    profile_sample_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "use the given sample profile file for profile-guided optimization \
+         (also known as AutoFDO)"),
 }
 
 options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
@@ -959,4 +1010,48 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "link the `.rlink` file generated by `-Z no-link`"),
     new_llvm_pass_manager: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "use new LLVM pass manager"),
+    validate_mir: bool = (false, parse_bool, [UNTRACKED],
+        "validate MIR after each transformation"),
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "enable MIR inlining (default: yes with `-Z mir-opt-level=2` and above)"),
+    inline_mir_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "a default MIR inlining threshold (default: 50)"),
+    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "inlining threshold for functions with inline hint (default: 100)"),
+    instrument_coverage: bool = (false, parse_bool, [TRACKED],
+        "instrument the generated code with code region counters and emit LLVM coverage \
+         mappings, for source-based code coverage reports with `llvm-cov`; links \
+         `libprofiler_builtins` (default: no)"),
+    type_layout_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the layout of each type encountered to the given file, as JSON"),
+    code_size_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the machine code size of each monomorphized function, aggregated by source \
+         function and by crate, to the given file, as JSON"),
+    cgu_cost_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the estimated cost of each codegen unit to the given file, as JSON"),
+    translate_lang: Option<String> = (None, parse_opt_string, [TRACKED],
+        "language of the diagnostic messages, like `fr` (default: `en-US`)"),
+    translate_additional_ftl: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "additional diagnostic messages in Fluent syntax, replacing those of the language"),
+    apply_suggestions: Option<Vec<String>> = (None, parse_opt_comma_list, [UNTRACKED],
+        "apply the machine-applicable suggestions of the given lints and error codes, or of \
+         all diagnostics with `all`, to the source files"),
+    apply_suggestions_output: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the files patched by `-Z apply-suggestions` to the given directory, rather than \
+         rewriting the source files"),
+    terminal_urls: Option<bool> = (None, parse_opt_bool, [UNTRACKED],
+        "make the locations and the error codes of diagnostics hyperlinks (default: if the \
+         terminal is known to support them)"),
+    terminal_url_template: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "the URL of the hyperlinks to locations, with `{path}`, `{line}` and `{col}` \
+         (default: `file://{path}`)"),
+    lint_config: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "read the levels of lints and lint groups, and custom lint groups, from the given \
+         JSON file"),
+    diagnostic_summary: bool = (false, parse_bool, [UNTRACKED],
+        "at the end of compilation, report the number of errors and warnings of each error \
+         code and lint, and where the first of them was reported (default: no)"),
+    incremental_explain: Option<IncrementalExplain> = (None, parse_incremental_explain,
+        [UNTRACKED], "print the chain of changed dependencies that made each query of the \
+         previous session execute again, as `text` or `json` (default: `text`)"),
 }
@@ -1,7 +1,9 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
-use crate::config::{self, OutputType, PrintRequest, Sanitizer, SwitchWithOptPath};
+pub use crate::code_stats::{DataTypeKind, DiscrInfo, DiscrKind, FieldInfo, NicheInfo};
+pub use crate::code_stats::{SizeKind, VariantInfo};
+use crate::config::SwitchWithOptPath;
+use crate::config::{self, DebugInfo, OutputType, PrintRequest, Sanitizer, SplitDebuginfo};
 use crate::filesearch;
 use crate::lint;
 use crate::parse::ParseSess;
@@ -16,8 +18,13 @@ use rustc_data_structures::sync::{
     self, AtomicU64, AtomicUsize, Lock, Lrc, Once, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
-use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::apply_suggestions::SuggestionApplier;
+use rustc_errors::emitter::{
+    terminal_supports_hyperlinks, ColorConfig, Emitter, EmitterWriter, HumanReadableErrorType,
+};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
+use rustc_errors::translation::Translator;
 use rustc_errors::{Applicability, DiagnosticBuilder, DiagnosticId, ErrorReported};
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{self, MultiSpan, Span};
@@ -586,6 +593,16 @@ impl Session {
         }
     }
 
+    /// Whether the debuginfo of each codegen unit is written to a split DWARF file, and whether
+    /// those files are packaged after linking. This is always `Off` without debuginfo.
+    pub fn split_debuginfo(&self) -> SplitDebuginfo {
+        if self.opts.debuginfo == DebugInfo::None {
+            SplitDebuginfo::Off
+        } else {
+            self.opts.cg.split_debuginfo
+        }
+    }
+
     /// Returns the symbol name for the registrar function,
     /// given the crate `Svh` and the function `DefIndex`.
     pub fn generate_plugin_registrar_symbol(&self, disambiguator: CrateDisambiguator) -> String {
@@ -893,6 +910,12 @@ fn default_emitter(
                         sopts.debugging_opts.teach,
                         sopts.debugging_opts.terminal_width,
                         macro_backtrace,
+                    )
+                    .terminal_urls(
+                        sopts.debugging_opts.terminal_urls.unwrap_or_else(|| {
+                            color_config != ColorConfig::Never && terminal_supports_hyperlinks()
+                        }),
+                        sopts.debugging_opts.terminal_url_template.clone(),
                     ),
                     Some(dst) => EmitterWriter::new(
                         dst,
@@ -928,6 +951,12 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing()),
         ),
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), source_map.clone()))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, Some(registry), source_map.clone()))
+        }
     }
 }
 
@@ -962,11 +991,29 @@ pub fn build_session_with_source_map(
         DiagnosticOutput::Raw(write) => Some(write),
     };
     let emitter = default_emitter(&sopts, registry, &source_map, write_dest);
+    let emitter: Box<dyn Emitter + sync::Send> = match &sopts.debugging_opts.apply_suggestions {
+        Some(codes) => Box::new(SuggestionApplier::new(
+            emitter,
+            source_map.clone(),
+            codes.clone(),
+            sopts.debugging_opts.apply_suggestions_output.clone(),
+        )),
+        None => emitter,
+    };
+
+    let sysroot = sopts.maybe_sysroot.clone().unwrap_or_else(filesearch::get_or_default_sysroot);
+    let translator = Translator::load(
+        &sysroot,
+        sopts.debugging_opts.translate_lang.as_deref(),
+        sopts.debugging_opts.translate_additional_ftl.as_deref(),
+    )
+    .unwrap_or_else(|e| early_error(sopts.error_format, &e.to_string()));
 
     let diagnostic_handler = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
         sopts.debugging_opts.diagnostic_handler_flags(can_emit_warnings),
-    );
+    )
+    .with_translator(translator);
 
     build_session_(sopts, local_crate_source_file, diagnostic_handler, source_map, lint_caps)
 }
@@ -1132,6 +1179,32 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    if let Some(ref path) = sess.opts.cg.profile_sample_use {
+        if !path.exists() {
+            sess.err(&format!(
+                "File `{}` passed to `-C profile-sample-use` does not exist.",
+                path.display()
+            ));
+        }
+
+        // Samples are attributed to source lines, so without debuginfo LLVM has nothing to match
+        // them against.
+        if sess.opts.debuginfo == DebugInfo::None {
+            sess.warn(
+                "`-C profile-sample-use` has no effect without debuginfo; \
+                 consider passing `-C debuginfo=1` as well",
+            );
+        }
+    }
+
+    // Split DWARF is only implemented for ELF targets, where `.dwo` and `.dwp` files are what the
+    // debuggers know to look for.
+    if sess.opts.cg.split_debuginfo != SplitDebuginfo::Off
+        && sess.target.target.target_os != "linux"
+    {
+        sess.err("`-C split-debuginfo` is only supported on Linux targets");
+    }
+
     // PGO does not work reliably with panic=unwind on Windows. Let's make it
     // an error to combine the two for now. It always runs into an assertions
     // if LLVM is built with assertions, but without assertions it sometimes
@@ -1164,12 +1237,22 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         const LSAN_SUPPORTED_TARGETS: &[&str] =
             &["x86_64-unknown-linux-gnu", "x86_64-apple-darwin"];
         const MSAN_SUPPORTED_TARGETS: &[&str] = &["x86_64-unknown-linux-gnu"];
+        const CFI_SUPPORTED_TARGETS: &[&str] =
+            &["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"];
+        const HWASAN_SUPPORTED_TARGETS: &[&str] = &["aarch64-unknown-linux-gnu"];
+        // The shadow call stack lives in `x18`, so it needs a platform that reserves that register
+        // for it and sets the shadow stack up.
+        const SHADOWCALLSTACK_SUPPORTED_TARGETS: &[&str] =
+            &["aarch64-linux-android", "aarch64-fuchsia"];
 
         let supported_targets = match *sanitizer {
             Sanitizer::Address => ASAN_SUPPORTED_TARGETS,
             Sanitizer::Thread => TSAN_SUPPORTED_TARGETS,
             Sanitizer::Leak => LSAN_SUPPORTED_TARGETS,
             Sanitizer::Memory => MSAN_SUPPORTED_TARGETS,
+            Sanitizer::Cfi => CFI_SUPPORTED_TARGETS,
+            Sanitizer::HwAddress => HWASAN_SUPPORTED_TARGETS,
+            Sanitizer::ShadowCallStack => SHADOWCALLSTACK_SUPPORTED_TARGETS,
         };
 
         if !supported_targets.contains(&&*sess.opts.target_triple.triple()) {
@@ -1179,6 +1262,12 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
                 supported_targets.join("` or `")
             ));
         }
+
+        // The valid targets of indirect calls are only known once every function that can be
+        // called is in the same module.
+        if *sanitizer == Sanitizer::Cfi && sess.lto() != config::Lto::Fat {
+            sess.err("`-Z sanitizer=cfi` requires `-C lto`");
+        }
     }
 }
 
@@ -1209,6 +1298,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1224,6 +1314,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
@@ -1234,6 +1234,16 @@ impl SourceFile {
 
         BytePos::from_u32(pos.0 - self.start_pos.0 + diff)
     }
+
+    /// Returns whether `src`, as read from the disk, is the source this file
+    /// was created from, by comparing their hashes once `src` is normalized.
+    pub fn is_source(&self, src: &str) -> bool {
+        let mut src = src.to_owned();
+        normalize_src(&mut src, self.start_pos);
+        let mut hasher: StableHasher = StableHasher::new();
+        hasher.write(src.as_bytes());
+        hasher.finish::<u128>() == self.src_hash
+    }
 }
 
 /// Normalizes the source code and records the normalizations.
@@ -156,6 +156,7 @@ symbols! {
         assume_init,
         async_await,
         async_closure,
+        att_syntax,
         attr,
         attributes,
         attr_literals,
@@ -191,6 +192,7 @@ symbols! {
         cfg_target_has_atomic,
         cfg_target_thread_local,
         cfg_target_vendor,
+        cfi,
         char,
         clippy,
         clone,
@@ -234,6 +236,7 @@ symbols! {
         copy_closures,
         core,
         core_intrinsics,
+        count_code_region,
         crate_id,
         crate_in_paths,
         crate_local,
@@ -248,6 +251,7 @@ symbols! {
         custom_attribute,
         custom_derive,
         custom_inner_attributes,
+        custom_mir,
         custom_test_frameworks,
         c_variadic,
         debug_trait,
@@ -363,6 +367,7 @@ symbols! {
         html_no_source,
         html_playground_url,
         html_root_url,
+        hwaddress,
         i128,
         i128_type,
         i16,
@@ -386,7 +391,9 @@ symbols! {
         inclusive_range_syntax,
         infer_outlives_requirements,
         infer_static_outlives_requirements,
+        inlateout,
         inline,
+        inout,
         intel,
         into_iter,
         IntoIterator,
@@ -408,6 +415,7 @@ symbols! {
         label_break_value,
         lang,
         lang_items,
+        lateout,
         let_chains,
         lhs,
         lib,
@@ -457,6 +465,18 @@ symbols! {
         min_const_unsafe_fn,
         min_specialization,
         mips_target_feature,
+        mir_basic_block,
+        mir_call,
+        mir_checked,
+        mir_discriminant,
+        mir_drop,
+        mir_goto,
+        mir_len,
+        mir_move,
+        mir_return,
+        mir_storage_dead,
+        mir_storage_live,
+        mir_unreachable,
         mmx_target_feature,
         module,
         module_path,
@@ -465,7 +485,9 @@ symbols! {
         move_val_init,
         movbe_target_feature,
         mul_with_overflow,
+        must_not_suspend,
         must_use,
+        MutexGuard,
         naked,
         naked_functions,
         name,
@@ -490,6 +512,7 @@ symbols! {
         no_link,
         no_main,
         no_mangle,
+        nomem,
         non_ascii_idents,
         None,
         non_exhaustive,
@@ -499,6 +522,7 @@ symbols! {
         no_stack_check,
         no_start,
         no_std,
+        nostack,
         not,
         note,
         object_safe_for_dispatch,
@@ -514,12 +538,15 @@ symbols! {
         option,
         Option,
         option_env,
+        option_type,
         opt_out_copy,
+        options,
         or,
         or_patterns,
         Ord,
         Ordering,
         Output,
+        out,
         overlapping_marker_traits,
         packed,
         panic,
@@ -551,6 +578,7 @@ symbols! {
         pref_align_of,
         prelude,
         prelude_import,
+        preserves_flags,
         primitive,
         proc_dash_macro: "proc-macro",
         proc_macro,
@@ -567,6 +595,7 @@ symbols! {
         profiler_runtime,
         ptr_offset_from,
         pub_restricted,
+        pure,
         pushpop_unsafe,
         quad_precision_float,
         question_mark,
@@ -581,6 +610,7 @@ symbols! {
         raw_identifiers,
         raw_ref_op,
         Ready,
+        readonly,
         reason,
         recursion_limit,
         reexport_test_harness_main,
@@ -599,6 +629,8 @@ symbols! {
         re_rebalance_coherence,
         result,
         Result,
+        result_type,
+        RET,
         Return,
         rhs,
         rlib,
@@ -671,6 +703,8 @@ symbols! {
         rust_eh_personality,
         rust_oom,
         rvalue_static_promotion,
+        RwLockReadGuard,
+        RwLockWriteGuard,
         sanitize,
         sanitizer_runtime,
         saturating_add,
@@ -679,6 +713,7 @@ symbols! {
         self_in_typedefs,
         self_struct_ctor,
         send_trait,
+        shadow_call_stack,
         should_panic,
         simd,
         simd_extract,
@@ -716,6 +751,7 @@ symbols! {
         sub_with_overflow,
         suggestion,
         sync_trait,
+        target_arch,
         target_feature,
         target_has_atomic,
         target_has_atomic_load_store,
@@ -0,0 +1,78 @@
+use super::parse_numbered;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum AArch64InlineAsmRegClass {
+    /// Any general purpose register.
+    Reg,
+    /// Any floating point or SIMD register.
+    Vreg,
+}
+
+impl AArch64InlineAsmRegClass {
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "reg" => Ok(AArch64InlineAsmRegClass::Reg),
+            "vreg" => Ok(AArch64InlineAsmRegClass::Vreg),
+            _ => Err("unknown register class"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AArch64InlineAsmRegClass::Reg => "reg",
+            AArch64InlineAsmRegClass::Vreg => "vreg",
+        }
+    }
+
+    pub fn llvm_constraint(self) -> &'static str {
+        match self {
+            AArch64InlineAsmRegClass::Reg => "r",
+            AArch64InlineAsmRegClass::Vreg => "w",
+        }
+    }
+
+    pub fn valid_modifiers(self) -> &'static [char] {
+        match self {
+            AArch64InlineAsmRegClass::Reg => &['w', 'x'],
+            AArch64InlineAsmRegClass::Vreg => &['b', 'h', 's', 'd', 'q', 'v'],
+        }
+    }
+
+    pub fn llvm_modifier(self, modifier: char) -> Option<char> {
+        match modifier {
+            // `v` names the whole vector register, which is what LLVM prints
+            // without a modifier.
+            'v' => None,
+            modifier => Some(modifier),
+        }
+    }
+}
+
+pub(super) fn parse_reg(name: &str) -> Result<String, &'static str> {
+    // General purpose registers are named `xN`, or `wN` for their 32-bit part.
+    let gpr = parse_numbered(name, "x", 31).or_else(|| parse_numbered(name, "w", 31));
+    // Vector registers are named `vN`, or by the size of the part used.
+    let vreg = ["v", "b", "h", "s", "d", "q"].iter().find_map(|p| parse_numbered(name, p, 32));
+    match (name, gpr, vreg) {
+        ("fp", ..) | (_, Some(29), _) => {
+            Err("the frame pointer cannot be used as an operand for inline asm")
+        }
+        (_, Some(18), _) => {
+            Err("x18 is a reserved register on some targets and cannot be used as an operand for \
+             inline asm")
+        }
+        (_, Some(19), _) => {
+            Err("x19 is used internally by LLVM and cannot be used as an operand for inline asm")
+        }
+        ("sp", ..) | ("wsp", ..) => {
+            Err("the stack pointer cannot be used as an operand for inline asm")
+        }
+        ("xzr", ..) | ("wzr", ..) => {
+            Err("the zero register cannot be used as an operand for inline asm")
+        }
+        ("lr", ..) => Ok("x30".to_string()),
+        (_, Some(index), _) => Ok(format!("x{}", index)),
+        (_, _, Some(index)) => Ok(format!("v{}", index)),
+        _ => Err("unknown register"),
+    }
+}
@@ -0,0 +1,65 @@
+use super::parse_numbered;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ArmInlineAsmRegClass {
+    /// Any general purpose register.
+    Reg,
+    /// A single-precision floating point register.
+    Sreg,
+    /// A double-precision floating point register.
+    Dreg,
+    /// A quad-word NEON register.
+    Qreg,
+}
+
+impl ArmInlineAsmRegClass {
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "reg" => Ok(ArmInlineAsmRegClass::Reg),
+            "sreg" => Ok(ArmInlineAsmRegClass::Sreg),
+            "dreg" => Ok(ArmInlineAsmRegClass::Dreg),
+            "qreg" => Ok(ArmInlineAsmRegClass::Qreg),
+            _ => Err("unknown register class"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ArmInlineAsmRegClass::Reg => "reg",
+            ArmInlineAsmRegClass::Sreg => "sreg",
+            ArmInlineAsmRegClass::Dreg => "dreg",
+            ArmInlineAsmRegClass::Qreg => "qreg",
+        }
+    }
+
+    pub fn llvm_constraint(self) -> &'static str {
+        match self {
+            ArmInlineAsmRegClass::Reg => "r",
+            ArmInlineAsmRegClass::Sreg => "t",
+            ArmInlineAsmRegClass::Dreg | ArmInlineAsmRegClass::Qreg => "w",
+        }
+    }
+}
+
+pub(super) fn parse_reg(name: &str) -> Result<String, &'static str> {
+    match name {
+        "r6" | "v3" => {
+            Err("r6 is used internally by LLVM and cannot be used as an operand for inline asm")
+        }
+        "r7" | "v4" | "r11" | "fp" => {
+            Err("the frame pointer cannot be used as an operand for inline asm")
+        }
+        "r13" | "sp" => Err("the stack pointer cannot be used as an operand for inline asm"),
+        "r15" | "pc" => Err("the program counter cannot be used as an operand for inline asm"),
+        "ip" => Ok("r12".to_string()),
+        "lr" => Ok("r14".to_string()),
+        _ if parse_numbered(name, "r", 15).is_some()
+            || parse_numbered(name, "s", 32).is_some()
+            || parse_numbered(name, "d", 32).is_some()
+            || parse_numbered(name, "q", 16).is_some() =>
+        {
+            Ok(name.to_string())
+        }
+        _ => Err("unknown register"),
+    }
+}
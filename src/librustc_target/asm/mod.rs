@@ -0,0 +1,178 @@
+//! The register classes and registers that operands of `asm!` can be allocated
+//! to, for each architecture that `asm!` supports.
+//!
+//! Register classes are named in `asm!` by their name here, like `reg` or
+//! `xmm_reg`, and let the register allocator pick any register of the class.
+//! Explicit registers are named by a string literal, like `"eax"`, and are
+//! accepted under any of their names: `InlineAsmReg::parse` maps them to a
+//! canonical name, so that two operands using the same register can be told
+//! apart from two operands using different registers.
+
+use std::fmt;
+use std::str::FromStr;
+
+mod aarch64;
+mod arm;
+mod riscv;
+mod x86;
+
+pub use aarch64::AArch64InlineAsmRegClass;
+pub use arm::ArmInlineAsmRegClass;
+pub use riscv::RiscVInlineAsmRegClass;
+pub use x86::X86InlineAsmRegClass;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum InlineAsmArch {
+    X86,
+    X86_64,
+    Arm,
+    AArch64,
+    RiscV32,
+    RiscV64,
+}
+
+impl FromStr for InlineAsmArch {
+    type Err = ();
+
+    /// Parses the `arch` of a target specification.
+    fn from_str(s: &str) -> Result<InlineAsmArch, ()> {
+        match s {
+            "x86" => Ok(InlineAsmArch::X86),
+            "x86_64" => Ok(InlineAsmArch::X86_64),
+            "arm" => Ok(InlineAsmArch::Arm),
+            "aarch64" => Ok(InlineAsmArch::AArch64),
+            "riscv32" => Ok(InlineAsmArch::RiscV32),
+            "riscv64" => Ok(InlineAsmArch::RiscV64),
+            _ => Err(()),
+        }
+    }
+}
+
+impl InlineAsmArch {
+    /// Whether the assembly of this architecture has an AT&T and an Intel
+    /// syntax, and so whether the `att_syntax` option of `asm!` applies.
+    pub fn has_att_syntax(self) -> bool {
+        match self {
+            InlineAsmArch::X86 | InlineAsmArch::X86_64 => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum InlineAsmRegClass {
+    X86(X86InlineAsmRegClass),
+    Arm(ArmInlineAsmRegClass),
+    AArch64(AArch64InlineAsmRegClass),
+    RiscV(RiscVInlineAsmRegClass),
+}
+
+impl InlineAsmRegClass {
+    pub fn parse(arch: InlineAsmArch, name: &str) -> Result<Self, &'static str> {
+        match arch {
+            InlineAsmArch::X86 | InlineAsmArch::X86_64 => {
+                X86InlineAsmRegClass::parse(name).map(InlineAsmRegClass::X86)
+            }
+            InlineAsmArch::Arm => ArmInlineAsmRegClass::parse(name).map(InlineAsmRegClass::Arm),
+            InlineAsmArch::AArch64 => {
+                AArch64InlineAsmRegClass::parse(name).map(InlineAsmRegClass::AArch64)
+            }
+            InlineAsmArch::RiscV32 | InlineAsmArch::RiscV64 => {
+                RiscVInlineAsmRegClass::parse(name).map(InlineAsmRegClass::RiscV)
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InlineAsmRegClass::X86(class) => class.name(),
+            InlineAsmRegClass::Arm(class) => class.name(),
+            InlineAsmRegClass::AArch64(class) => class.name(),
+            InlineAsmRegClass::RiscV(class) => class.name(),
+        }
+    }
+
+    /// The LLVM constraint code that allocates a register of this class.
+    pub fn llvm_constraint(self) -> &'static str {
+        match self {
+            InlineAsmRegClass::X86(class) => class.llvm_constraint(),
+            InlineAsmRegClass::Arm(class) => class.llvm_constraint(),
+            InlineAsmRegClass::AArch64(class) => class.llvm_constraint(),
+            InlineAsmRegClass::RiscV(class) => class.llvm_constraint(),
+        }
+    }
+
+    /// The modifiers that templates can apply to operands of this class,
+    /// like `e` in `{0:e}` to name the 32-bit part of an x86 register.
+    pub fn valid_modifiers(self, arch: InlineAsmArch) -> &'static [char] {
+        match self {
+            InlineAsmRegClass::X86(class) => class.valid_modifiers(arch),
+            InlineAsmRegClass::Arm(_) | InlineAsmRegClass::RiscV(_) => &[],
+            InlineAsmRegClass::AArch64(class) => class.valid_modifiers(),
+        }
+    }
+
+    /// The LLVM operand modifier for one of `valid_modifiers`, if LLVM needs
+    /// one to print the operand that way.
+    pub fn llvm_modifier(self, modifier: char) -> Option<char> {
+        match self {
+            InlineAsmRegClass::X86(class) => class.llvm_modifier(modifier),
+            InlineAsmRegClass::Arm(_) | InlineAsmRegClass::RiscV(_) => None,
+            InlineAsmRegClass::AArch64(class) => class.llvm_modifier(modifier),
+        }
+    }
+}
+
+impl fmt::Display for InlineAsmRegClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An explicit register, by its canonical name.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct InlineAsmReg {
+    name: String,
+}
+
+impl InlineAsmReg {
+    /// Parses a register name, returning why it cannot be used if it is not a
+    /// register of `arch` or is reserved.
+    pub fn parse(arch: InlineAsmArch, name: &str) -> Result<Self, &'static str> {
+        let name = match arch {
+            InlineAsmArch::X86 | InlineAsmArch::X86_64 => x86::parse_reg(arch, name)?,
+            InlineAsmArch::Arm => arm::parse_reg(name)?,
+            InlineAsmArch::AArch64 => aarch64::parse_reg(name)?,
+            InlineAsmArch::RiscV32 | InlineAsmArch::RiscV64 => riscv::parse_reg(name)?,
+        };
+        Ok(InlineAsmReg { name })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The LLVM constraint that allocates this register.
+    pub fn llvm_constraint(&self) -> String {
+        format!("{{{}}}", self.name)
+    }
+}
+
+impl fmt::Display for InlineAsmReg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Parses registers named by a prefix and a number below `count`, like `xmm3`.
+fn parse_numbered(name: &str, prefix: &str, count: u32) -> Option<u32> {
+    if !name.starts_with(prefix) {
+        return None;
+    }
+    let index = &name[prefix.len()..];
+    // Reject `xmm03` and the like, so that each register has one name.
+    if !index.bytes().all(|b| b.is_ascii_digit()) || index.len() > 1 && index.starts_with('0') {
+        return None;
+    }
+    index.parse().ok().filter(|&index| index < count)
+}
@@ -0,0 +1,56 @@
+use super::parse_numbered;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum RiscVInlineAsmRegClass {
+    /// Any general purpose register.
+    Reg,
+    /// Any floating point register.
+    Freg,
+}
+
+impl RiscVInlineAsmRegClass {
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "reg" => Ok(RiscVInlineAsmRegClass::Reg),
+            "freg" => Ok(RiscVInlineAsmRegClass::Freg),
+            _ => Err("unknown register class"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RiscVInlineAsmRegClass::Reg => "reg",
+            RiscVInlineAsmRegClass::Freg => "freg",
+        }
+    }
+
+    pub fn llvm_constraint(self) -> &'static str {
+        match self {
+            RiscVInlineAsmRegClass::Reg => "r",
+            RiscVInlineAsmRegClass::Freg => "f",
+        }
+    }
+}
+
+/// The ABI names of the general purpose registers, by register number.
+const GPR_ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+pub(super) fn parse_reg(name: &str) -> Result<String, &'static str> {
+    let gpr = parse_numbered(name, "x", 32)
+        .or_else(|| GPR_ABI_NAMES.iter().position(|&abi_name| abi_name == name).map(|i| i as u32))
+        .or_else(|| if name == "fp" { Some(8) } else { None });
+    match gpr {
+        Some(0) => Err("the zero register cannot be used as an operand for inline asm"),
+        Some(2) => Err("the stack pointer cannot be used as an operand for inline asm"),
+        Some(3) => Err("the global pointer cannot be used as an operand for inline asm"),
+        Some(4) => Err("the thread pointer cannot be used as an operand for inline asm"),
+        Some(8) => Err("the frame pointer cannot be used as an operand for inline asm"),
+        Some(index) => Ok(format!("x{}", index)),
+        None if parse_numbered(name, "f", 32).is_some() => Ok(name.to_string()),
+        None => Err("unknown register"),
+    }
+}
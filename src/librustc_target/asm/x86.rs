@@ -0,0 +1,150 @@
+use super::{parse_numbered, InlineAsmArch};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum X86InlineAsmRegClass {
+    /// Any general purpose register.
+    Reg,
+    /// `ax`, `bx`, `cx` or `dx`, whose second byte can be named.
+    RegAbcd,
+    /// A general purpose register with a nameable low byte.
+    RegByte,
+    XmmReg,
+    YmmReg,
+    ZmmReg,
+    /// An AVX-512 mask register.
+    KReg,
+}
+
+impl X86InlineAsmRegClass {
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "reg" => Ok(X86InlineAsmRegClass::Reg),
+            "reg_abcd" => Ok(X86InlineAsmRegClass::RegAbcd),
+            "reg_byte" => Ok(X86InlineAsmRegClass::RegByte),
+            "xmm_reg" => Ok(X86InlineAsmRegClass::XmmReg),
+            "ymm_reg" => Ok(X86InlineAsmRegClass::YmmReg),
+            "zmm_reg" => Ok(X86InlineAsmRegClass::ZmmReg),
+            "kreg" => Ok(X86InlineAsmRegClass::KReg),
+            _ => Err("unknown register class"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            X86InlineAsmRegClass::Reg => "reg",
+            X86InlineAsmRegClass::RegAbcd => "reg_abcd",
+            X86InlineAsmRegClass::RegByte => "reg_byte",
+            X86InlineAsmRegClass::XmmReg => "xmm_reg",
+            X86InlineAsmRegClass::YmmReg => "ymm_reg",
+            X86InlineAsmRegClass::ZmmReg => "zmm_reg",
+            X86InlineAsmRegClass::KReg => "kreg",
+        }
+    }
+
+    pub fn llvm_constraint(self) -> &'static str {
+        match self {
+            X86InlineAsmRegClass::Reg => "r",
+            X86InlineAsmRegClass::RegAbcd => "Q",
+            X86InlineAsmRegClass::RegByte => "q",
+            X86InlineAsmRegClass::XmmReg | X86InlineAsmRegClass::YmmReg => "x",
+            X86InlineAsmRegClass::ZmmReg => "v",
+            X86InlineAsmRegClass::KReg => "Yk",
+        }
+    }
+
+    pub fn valid_modifiers(self, arch: InlineAsmArch) -> &'static [char] {
+        match (self, arch) {
+            (X86InlineAsmRegClass::Reg, InlineAsmArch::X86_64) => &['l', 'x', 'e', 'r'],
+            (X86InlineAsmRegClass::Reg, _) => &['x', 'e'],
+            (X86InlineAsmRegClass::RegAbcd, InlineAsmArch::X86_64) => &['l', 'h', 'x', 'e', 'r'],
+            (X86InlineAsmRegClass::RegAbcd, _) => &['l', 'h', 'x', 'e'],
+            (X86InlineAsmRegClass::RegByte, _) | (X86InlineAsmRegClass::KReg, _) => &[],
+            (X86InlineAsmRegClass::XmmReg, _)
+            | (X86InlineAsmRegClass::YmmReg, _)
+            | (X86InlineAsmRegClass::ZmmReg, _) => &['x', 'y', 'z'],
+        }
+    }
+
+    pub fn llvm_modifier(self, modifier: char) -> Option<char> {
+        match modifier {
+            // The low byte, the second byte, and the 16, 32 and 64-bit parts
+            // of a general purpose register.
+            'l' => Some('b'),
+            'h' => Some('h'),
+            'x' if self == X86InlineAsmRegClass::Reg || self == X86InlineAsmRegClass::RegAbcd => {
+                Some('w')
+            }
+            'e' => Some('k'),
+            'r' => Some('q'),
+            // The `xmm`, `ymm` and `zmm` views of a vector register.
+            'x' => Some('x'),
+            'y' => Some('t'),
+            'z' => Some('g'),
+            _ => None,
+        }
+    }
+}
+
+/// The general purpose registers available on both x86 and x86_64, by their
+/// canonical name and by the names of their parts.
+const GPRS: &[(&str, &[&str])] = &[
+    ("ax", &["rax", "eax", "al"]),
+    ("bx", &["rbx", "ebx", "bl"]),
+    ("cx", &["rcx", "ecx", "cl"]),
+    ("dx", &["rdx", "edx", "dl"]),
+    ("si", &["rsi", "esi", "sil"]),
+    ("di", &["rdi", "edi", "dil"]),
+];
+
+const GPRS_64: &[(&str, &[&str])] = &[
+    ("r8", &["r8d", "r8w", "r8b"]),
+    ("r9", &["r9d", "r9w", "r9b"]),
+    ("r10", &["r10d", "r10w", "r10b"]),
+    ("r11", &["r11d", "r11w", "r11b"]),
+    ("r12", &["r12d", "r12w", "r12b"]),
+    ("r13", &["r13d", "r13w", "r13b"]),
+    ("r14", &["r14d", "r14w", "r14b"]),
+    ("r15", &["r15d", "r15w", "r15b"]),
+];
+
+pub(super) fn parse_reg(arch: InlineAsmArch, name: &str) -> Result<String, &'static str> {
+    let is_64 = arch == InlineAsmArch::X86_64;
+    let find = |regs: &[(&'static str, &[&str])]| {
+        regs.iter()
+            .find(|&&(canonical, aliases)| canonical == name || aliases.contains(&name))
+            .map(|&(canonical, _)| canonical.to_string())
+    };
+
+    if let Some(reg) = find(GPRS) {
+        return Ok(reg);
+    }
+    if let Some(reg) = find(GPRS_64) {
+        return if is_64 { Ok(reg) } else { Err("register is only available on x86_64") };
+    }
+    match name {
+        "bp" | "ebp" | "rbp" | "bpl" => {
+            return Err("the frame pointer cannot be used as an operand for inline asm");
+        }
+        "sp" | "esp" | "rsp" | "spl" => {
+            return Err("the stack pointer cannot be used as an operand for inline asm");
+        }
+        "ip" | "eip" | "rip" => {
+            return Err("the instruction pointer cannot be used as an operand for inline asm");
+        }
+        "k0" => return Err("the k0 AVX mask register cannot be used as an operand for inline asm"),
+        _ => {}
+    }
+
+    let (vector_regs, avx512_vector_regs) = if is_64 { (16, 32) } else { (8, 8) };
+    let numbered =
+        [("xmm", vector_regs), ("ymm", vector_regs), ("zmm", avx512_vector_regs), ("k", 8)];
+    for &(prefix, count) in &numbered {
+        if parse_numbered(name, prefix, count).is_some() {
+            return Ok(name.to_string());
+        }
+    }
+    if !is_64 && ["xmm", "ymm", "zmm"].iter().any(|p| parse_numbered(name, p, 16).is_some()) {
+        return Err("register is only available on x86_64");
+    }
+    Err("unknown register")
+}
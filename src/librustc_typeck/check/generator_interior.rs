@@ -5,14 +5,18 @@
 
 use super::FnCtxt;
 use rustc::middle::region::{self, YieldData};
-use rustc::ty::{self, Ty};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_ast::attr;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, DefKind, Res};
 use rustc_hir::def_id::DefId;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_hir::{Expr, ExprKind, Pat, PatKind};
+use rustc_session::lint::builtin::MUST_NOT_SUSPEND;
+use rustc_span::symbol::sym;
 use rustc_span::Span;
+use std::collections::hash_map::Entry;
 
 struct InteriorVisitor<'a, 'tcx> {
     fcx: &'a FnCtxt<'a, 'tcx>,
@@ -27,6 +31,7 @@ impl<'a, 'tcx> InteriorVisitor<'a, 'tcx> {
     fn record(
         &mut self,
         ty: Ty<'tcx>,
+        hir_id: hir::HirId,
         scope: Option<region::Scope>,
         expr: Option<&'tcx Expr<'tcx>>,
         source_span: Span,
@@ -91,14 +96,28 @@ impl<'a, 'tcx> InteriorVisitor<'a, 'tcx> {
                 // Map the type to the number of types added before it
                 let entries = self.types.len();
                 let scope_span = scope.map(|s| s.span(self.fcx.tcx, self.region_scope_tree));
-                self.types
-                    .entry(ty::GeneratorInteriorTypeCause {
-                        span: source_span,
-                        ty: &ty,
-                        scope_span,
-                        expr: expr.map(|e| e.hir_id),
-                    })
-                    .or_insert(entries);
+                let cause = ty::GeneratorInteriorTypeCause {
+                    span: source_span,
+                    ty: &ty,
+                    scope_span,
+                    expr: expr.map(|e| e.hir_id),
+                };
+                if let Entry::Vacant(entry) = self.types.entry(cause) {
+                    entry.insert(entries);
+                    // Values without a scope are assumed to live across a yield
+                    // even in bodies that never suspend, so only values held
+                    // across a known suspension point are linted.
+                    if !yield_data.span.is_dummy() {
+                        let data = SuspendCheckData {
+                            hir_id,
+                            source_span,
+                            yield_data,
+                            descr_pre: "",
+                            descr_post: "",
+                        };
+                        check_must_not_suspend_ty(self.fcx, ty, data);
+                    }
+                }
             }
         } else {
             debug!(
@@ -222,7 +241,7 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         if let PatKind::Binding(..) = pat.kind {
             let scope = self.region_scope_tree.var_scope(pat.hir_id.local_id);
             let ty = self.fcx.tables.borrow().pat_ty(pat);
-            self.record(ty, Some(scope), None, pat.span);
+            self.record(ty, pat.hir_id, Some(scope), None, pat.span);
         }
     }
 
@@ -262,7 +281,7 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         // If there are adjustments, then record the final type --
         // this is the actual value that is being produced.
         if let Some(adjusted_ty) = self.fcx.tables.borrow().expr_ty_adjusted_opt(expr) {
-            self.record(adjusted_ty, scope, Some(expr), expr.span);
+            self.record(adjusted_ty, expr.hir_id, scope, Some(expr), expr.span);
         }
 
         // Also record the unadjusted type (which is the only type if
@@ -290,9 +309,77 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         // The type table might not have information for this expression
         // if it is in a malformed scope. (#66387)
         if let Some(ty) = self.fcx.tables.borrow().expr_ty_opt(expr) {
-            self.record(ty, scope, Some(expr), expr.span);
+            self.record(ty, expr.hir_id, scope, Some(expr), expr.span);
         } else {
             self.fcx.tcx.sess.delay_span_bug(expr.span, "no type for node");
         }
     }
 }
+
+/// A value held across a suspension point, and where the type being checked occurs in
+/// the type of the value, for the messages of `MUST_NOT_SUSPEND`.
+#[derive(Clone, Copy)]
+struct SuspendCheckData<'a> {
+    hir_id: hir::HirId,
+    source_span: Span,
+    yield_data: YieldData,
+    descr_pre: &'a str,
+    descr_post: &'a str,
+}
+
+/// Lints the `#[must_not_suspend]` types in `ty`, the type of a value held across a
+/// suspension point. Like `#[must_use]`, this looks through boxes, tuples and arrays,
+/// but not into the fields of other types.
+fn check_must_not_suspend_ty<'tcx>(
+    fcx: &FnCtxt<'_, 'tcx>,
+    ty: Ty<'tcx>,
+    data: SuspendCheckData<'_>,
+) {
+    match ty.kind {
+        ty::Adt(..) if ty.is_box() => {
+            let descr_pre = &format!("{}boxed ", data.descr_pre);
+            check_must_not_suspend_ty(fcx, ty.boxed_ty(), SuspendCheckData { descr_pre, ..data })
+        }
+        ty::Adt(def, _) => check_must_not_suspend_def(fcx.tcx, def.did, data),
+        ty::Tuple(_) => {
+            for (i, ty) in ty.tuple_fields().enumerate() {
+                let descr_post = &format!(" in tuple element {}", i);
+                check_must_not_suspend_ty(fcx, ty, SuspendCheckData { descr_post, ..data })
+            }
+        }
+        ty::Array(ty, _) => {
+            let descr_pre = &format!("{}array of ", data.descr_pre);
+            check_must_not_suspend_ty(fcx, ty, SuspendCheckData { descr_pre, ..data })
+        }
+        _ => {}
+    }
+}
+
+fn check_must_not_suspend_def(tcx: TyCtxt<'_>, def_id: DefId, data: SuspendCheckData<'_>) {
+    let attrs = tcx.get_attrs(def_id);
+    let attr = match attr::find_by_name(&attrs, sym::must_not_suspend) {
+        Some(attr) => attr,
+        None => return,
+    };
+    tcx.struct_span_lint_hir(MUST_NOT_SUSPEND, data.hir_id, data.source_span, |lint| {
+        let msg = format!(
+            "{}`{}`{} held across a suspend point, but should not be",
+            data.descr_pre,
+            tcx.def_path_str(def_id),
+            data.descr_post,
+        );
+        let mut err = lint.build(&msg);
+        err.span_label(
+            data.yield_data.span,
+            format!("the value is held across this {}", data.yield_data.source),
+        );
+        if let Some(reason) = attr.value_str() {
+            err.note(&reason.as_str());
+        }
+        err.help(
+            "consider using a block (`{ ... }`) to shrink the value's scope, ending before the \
+             suspend point",
+        );
+        err.emit();
+    });
+}
@@ -333,6 +333,12 @@ pub fn check_intrinsic_type(tcx: TyCtxt<'_>, it: &hir::ForeignItem<'_>) {
 
             "nontemporal_store" => (1, vec![tcx.mk_mut_ptr(param(0)), param(0)], tcx.mk_unit()),
 
+            "count_code_region" => (
+                0,
+                vec![tcx.types.u64, tcx.types.u32, tcx.types.u32, tcx.types.u32],
+                tcx.mk_unit(),
+            ),
+
             "miri_start_panic" => {
                 // FIXME - the relevant types aren't lang items,
                 // so it's not trivial to check this
@@ -2439,10 +2439,20 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, id: DefId) -> CodegenFnAttrs {
                         codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_SANITIZE_MEMORY;
                     } else if item.check_name(sym::thread) {
                         codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_SANITIZE_THREAD;
+                    } else if item.check_name(sym::cfi) {
+                        codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_SANITIZE_CFI;
+                    } else if item.check_name(sym::hwaddress) {
+                        codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_SANITIZE_HWADDRESS;
+                    } else if item.check_name(sym::shadow_call_stack) {
+                        codegen_fn_attrs.flags |=
+                            CodegenFnAttrFlags::NO_SANITIZE_SHADOW_CALL_STACK;
                     } else {
                         tcx.sess
                             .struct_span_err(item.span(), "invalid argument for `no_sanitize`")
-                            .note("expected one of: `address`, `memory` or `thread`")
+                            .note(
+                                "expected one of: `address`, `cfi`, `hwaddress`, `memory`, \
+                                 `shadow_call_stack` or `thread`",
+                            )
                             .emit();
                     }
                 }
@@ -7,6 +7,7 @@ use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_driver::abort_on_err;
 use rustc_errors::emitter::{Emitter, EmitterWriter};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::Namespace::TypeNS;
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LOCAL_CRATE};
@@ -163,8 +164,8 @@ impl<'tcx> DocContext<'tcx> {
 
 /// Creates a new diagnostic `Handler` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the handler.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the handler.
 pub fn new_handler(
     error_format: ErrorOutputType,
     source_map: Option<Lrc<source_map::SourceMap>>,
@@ -194,6 +195,12 @@ pub fn new_handler(
                     .ui_testing(debugging_opts.ui_testing()),
             )
         }
+        ErrorOutputType::Sarif => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Lrc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            Box::new(SarifEmitter::stderr(None, source_map))
+        }
     };
 
     rustc_errors::Handler::with_emitter_and_flags(
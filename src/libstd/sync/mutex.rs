@@ -141,6 +141,12 @@ unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
 /// [`try_lock`]: struct.Mutex.html#method.try_lock
 /// [`Mutex`]: struct.Mutex.html
 #[must_use = "if unused the Mutex will immediately unlock"]
+#[cfg_attr(
+    not(bootstrap),
+    must_not_suspend = "holding a MutexGuard across suspend points can cause deadlocks, delays, \
+                        and cause futures to not implement `Send`"
+)]
+#[rustc_diagnostic_item = "MutexGuard"]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct MutexGuard<'a, T: ?Sized + 'a> {
     lock: &'a Mutex<T>,
@@ -203,6 +203,12 @@ static Attribute::AttrKind fromRust(LLVMRustAttribute Kind) {
     return Attribute::OptimizeNone;
   case ReturnsTwice:
     return Attribute::ReturnsTwice;
+  case ReadNone:
+    return Attribute::ReadNone;
+  case SanitizeHWAddress:
+    return Attribute::SanitizeHWAddress;
+  case ShadowCallStack:
+    return Attribute::ShadowCallStack;
   }
   report_fatal_error("bad AttributeKind");
 }
@@ -674,13 +680,13 @@ extern "C" LLVMMetadataRef LLVMRustDIBuilderCreateCompileUnit(
     const char *Producer, size_t ProducerLen, bool isOptimized,
     const char *Flags, unsigned RuntimeVer,
     const char *SplitName, size_t SplitNameLen,
-    LLVMRustDebugEmissionKind Kind) {
+    LLVMRustDebugEmissionKind Kind, bool DebugInfoForProfiling) {
   auto *File = unwrapDI<DIFile>(FileRef);
 
   return wrap(Builder->createCompileUnit(Lang, File, StringRef(Producer, ProducerLen),
                                          isOptimized, Flags, RuntimeVer,
                                          StringRef(SplitName, SplitNameLen),
-                                         fromRust(Kind)));
+                                         fromRust(Kind), 0, true, DebugInfoForProfiling));
 }
 
 extern "C" LLVMMetadataRef LLVMRustDIBuilderCreateFile(
@@ -1517,6 +1523,13 @@ extern "C" void LLVMRustSetVisibility(LLVMValueRef V,
   LLVMSetVisibility(V, fromRust(RustVisibility));
 }
 
+extern "C" void LLVMRustAddTypeMetadata(LLVMValueRef Global, uint64_t Offset,
+                                        const char *TypeId, size_t TypeIdLen) {
+  GlobalObject *GO = unwrap<GlobalObject>(Global);
+  GO->addTypeMetadata(Offset, MDString::get(GO->getContext(),
+                                            StringRef(TypeId, TypeIdLen)));
+}
+
 struct LLVMRustModuleBuffer {
   std::string data;
 };
@@ -82,6 +82,9 @@ enum LLVMRustAttribute {
   NonLazyBind = 23,
   OptimizeNone = 24,
   ReturnsTwice = 25,
+  ReadNone = 26,
+  SanitizeHWAddress = 27,
+  ShadowCallStack = 28,
 };
 
 typedef struct OpaqueRustString *RustStringRef;
@@ -0,0 +1,46 @@
+// ignore-tidy-linelength
+// only-x86_64
+// compile-flags: -O
+
+#![feature(asm)]
+#![crate_type = "lib"]
+
+// Check how the register operands and options of `asm!` are lowered to LLVM
+// constraints, operand references and attributes.
+
+// CHECK-LABEL: @add_one
+// CHECK: call i32 asm sideeffect alignstack inteldialect "lea ${0:k}, [${1:k} + 1]", "=&r,r,~{dirflag},~{fpsr},~{flags}"(i32 %{{.*}})
+#[no_mangle]
+pub unsafe fn add_one(x: i32) -> i32 {
+    let y: i32;
+    asm!("lea {0:e}, [{1:e} + 1]", out(reg) y, in(reg) x);
+    y
+}
+
+// CHECK-LABEL: @double
+// CHECK: call i64 asm inteldialect "lea ${0}, [${0} + ${0}]", "=r,0"(i64 %{{.*}}) #[[NOMEM:[0-9]+]]
+#[no_mangle]
+pub unsafe fn double(mut x: u64) -> u64 {
+    asm!("lea {0}, [{0} + {0}]", inlateout(reg) x, options(pure, nomem, nostack, preserves_flags));
+    x
+}
+
+// CHECK-LABEL: @explicit
+// CHECK: call i32 asm sideeffect alignstack "movl $$1, %eax", "={ax},~{cx},~{dirflag},~{fpsr},~{flags}"()
+#[no_mangle]
+pub unsafe fn explicit() -> i32 {
+    let x: i32;
+    asm!("movl $1, %eax", lateout("eax") x, out("ecx") _, options(att_syntax));
+    x
+}
+
+// CHECK-LABEL: @split
+// CHECK: call i64 asm sideeffect alignstack inteldialect "add ${0}, ${2}\0Aadd ${0}, ${3}", "=&r,0,r,r,~{dirflag},~{fpsr},~{flags}"(i64 %{{.*}}, i64 %{{.*}}, i64 %{{.*}})
+#[no_mangle]
+pub unsafe fn split(a: u64, b: u64, c: u64) -> u64 {
+    let sum: u64;
+    asm!("add {0}, {1}", "add {0}, {c}", inout(reg) a => sum, in(reg) b, c = in(reg) c);
+    sum
+}
+
+// CHECK: attributes #[[NOMEM]] = { {{.*}}readnone{{.*}} }
@@ -0,0 +1,67 @@
+// ignore-tidy-linelength
+// Verifies that `-Zsanitizer=cfi` adds type metadata to functions and checks
+// the targets of indirect calls against it.
+//
+// needs-sanitizer-support
+// only-linux
+// only-x86_64
+// compile-flags: -Zsanitizer=cfi -Clto -Cno-prepopulate-passes
+
+#![crate_type="lib"]
+#![feature(no_sanitize)]
+
+pub trait Trait {
+    fn method(&self, x: i32) -> i32;
+}
+
+pub struct Struct;
+
+impl Trait for Struct {
+    // CHECK: define {{.*}}@{{.*}}$LT$sanitizer_cfi..Struct$u20$as$u20$sanitizer_cfi..Trait$GT$6method{{.*}} !type ![[METHOD:[0-9]+]]
+    fn method(&self, x: i32) -> i32 {
+        x
+    }
+}
+
+// CHECK-LABEL: define {{.*}}@{{.*}}sanitizer_cfi13function_call{{.*}} !type
+// CHECK:         [[TEST:%.+]] = call i1 @llvm.type.test(i8* {{%.+}}, metadata !"typeid.Rust.i32.i32")
+// CHECK-NEXT:    br i1 [[TEST]], label %cfi_valid, label %cfi_invalid
+// CHECK:       cfi_valid:
+// CHECK:         call i32 %f(i32 %x)
+// CHECK:       cfi_invalid:
+// CHECK-NEXT:    call void @llvm.trap()
+// CHECK-NEXT:    unreachable
+pub fn function_call(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+// Calls through vtables are not checked.
+// CHECK-LABEL: define {{.*}}@{{.*}}sanitizer_cfi11method_call
+// CHECK-NOT:     @llvm.type.test
+// CHECK:         call i32 %{{.+}}(
+// CHECK:       }
+pub fn method_call(t: &dyn Trait, x: i32) -> i32 {
+    t.method(x)
+}
+
+extern "C" {
+    fn abs(x: i32) -> i32;
+}
+
+// Pointers to foreign functions point to a shim, which has type metadata.
+// CHECK-LABEL: define {{.*}}@{{.*}}sanitizer_cfi16foreign_function
+// CHECK:         ret {{.*}}@{{.*}}3abs{{.*}}reify.shim
+pub fn foreign_function() -> unsafe extern "C" fn(i32) -> i32 {
+    abs
+}
+
+// CHECK-LABEL: define {{.*}}@{{.*}}sanitizer_cfi14unchecked_call
+// CHECK-NOT:     @llvm.type.test
+// CHECK:         call i32 %f(i32 %x)
+// CHECK:       }
+#[no_sanitize(cfi)]
+pub fn unchecked_call(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+// CHECK: ![[METHOD]] = !{i64 0, !"typeid.Rust.i32.p.i32"}
@@ -0,0 +1,32 @@
+// Verifies that HWAddressSanitizer instruments functions, unless they opt out
+// with `#[no_sanitize(hwaddress)]`, and that globals are tagged.
+//
+// needs-sanitizer-support
+// only-aarch64
+// only-linux
+// compile-flags: -Zsanitizer=hwaddress
+
+#![crate_type="lib"]
+#![feature(no_sanitize)]
+
+// CHECK-LABEL: ; sanitizer_hwaddress::unsanitized
+// CHECK-NEXT:  ; Function Attrs:
+// CHECK-NOT:   sanitize_hwaddress
+// CHECK:       start:
+// CHECK-NOT:   __hwasan
+// CHECK:       }
+#[no_sanitize(hwaddress)]
+pub fn unsanitized(b: &mut u8) -> u8 {
+    *b
+}
+
+// CHECK-LABEL: ; sanitizer_hwaddress::sanitized
+// CHECK-NEXT:  ; Function Attrs:
+// CHECK:       sanitize_hwaddress
+// CHECK:       start:
+// CHECK:       }
+pub fn sanitized(b: &mut u8) -> u8 {
+    *b
+}
+
+// CHECK: attributes #{{[0-9]+}} = {{.*}}"target-features"="{{.*}}+tagged-globals
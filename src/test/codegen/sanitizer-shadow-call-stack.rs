@@ -0,0 +1,24 @@
+// Verifies that `-Zsanitizer=shadow-call-stack` enables the shadow call stack,
+// unless a function opts out with `#[no_sanitize(shadow_call_stack)]`, and
+// reserves the register it lives in.
+//
+// compile-flags: --target aarch64-linux-android -Zsanitizer=shadow-call-stack
+
+#![crate_type = "lib"]
+#![feature(no_core, lang_items, no_sanitize)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+
+// CHECK: define void @with_shadow_call_stack() {{.*}}#[[WITH:[0-9]+]]
+#[no_mangle]
+pub fn with_shadow_call_stack() {}
+
+// CHECK: define void @without_shadow_call_stack() {{.*}}#[[WITHOUT:[0-9]+]]
+#[no_mangle]
+#[no_sanitize(shadow_call_stack)]
+pub fn without_shadow_call_stack() {}
+
+// CHECK: attributes #[[WITH]] = {{.*}}shadowcallstack{{.*}}"target-features"="{{.*}}+reserve-x18
+// CHECK-NOT: attributes #[[WITHOUT]] = {{.*}}shadowcallstack
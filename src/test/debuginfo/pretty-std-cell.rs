@@ -0,0 +1,58 @@
+// ignore-windows failing on win32 bot
+// ignore-freebsd: gdb package too new
+// ignore-android: FIXME(#10381)
+// compile-flags:-g
+// min-gdb-version 7.7
+// min-lldb-version: 310
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command: run
+
+// gdb-command: print cell
+// gdb-check:$1 = Cell<i32> = {value = 7}
+
+// gdb-command: print refcell
+// gdb-check:$2 = RefCell<i32>(borrow: 0) = {value = 8}
+
+// gdb-command: print shared_refcell
+// gdb-check:$3 = RefCell<i32>(borrow: 2) = {value = 9}
+
+// gdb-command: print mut_refcell
+// gdb-check:$4 = RefCell<i32>(borrow: -1) = {value = 10}
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command: run
+
+// lldb-command: print cell
+// lldb-check:[...]$0 = Cell<i32> { value: 7 }
+
+// lldb-command: print refcell
+// lldb-check:[...]$1 = RefCell<i32> { borrow: 0, value: 8 }
+
+// lldb-command: print shared_refcell
+// lldb-check:[...]$2 = RefCell<i32> { borrow: 2, value: 9 }
+
+// lldb-command: print mut_refcell
+// lldb-check:[...]$3 = RefCell<i32> { borrow: -1, value: 10 }
+
+#![allow(unused_variables)]
+use std::cell::{Cell, RefCell};
+
+fn main() {
+    let cell = Cell::new(7);
+
+    let refcell = RefCell::new(8);
+
+    let shared_refcell = RefCell::new(9);
+    let first_borrow = shared_refcell.borrow();
+    let second_borrow = shared_refcell.borrow();
+
+    let mut_refcell = RefCell::new(10);
+    let mut_borrow = mut_refcell.borrow_mut();
+
+    zzz(); // #break
+}
+
+fn zzz() { () }
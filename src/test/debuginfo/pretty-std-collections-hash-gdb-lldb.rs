@@ -0,0 +1,87 @@
+// ignore-tidy-linelength
+// ignore-windows failing on win32 bot
+// ignore-freebsd: gdb package too new
+// ignore-android: FIXME(#10381)
+// compile-flags:-g
+// min-gdb-version 7.7
+// min-lldb-version: 310
+
+// The CDB checks of hash maps and sets are in `pretty-std-collections-hash.rs`. The maps below
+// use a hasher that returns the keys themselves, so that their elements are in a known order.
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command: run
+
+// gdb-command: print hash_set
+// gdb-check:$1 = HashSet<u64, [...]>(len: 3) = {1, 2, 3}
+
+// gdb-command: print hash_map
+// gdb-check:$2 = HashMap<u64, &str, [...]>(len: 3) = {[1] = "one", [2] = "two", [3] = "three"}
+
+// gdb-command: print single_hash_map
+// gdb-check:$3 = HashMap<u64, &str, [...]>(len: 1) = {[7] = "seven"}
+
+// gdb-command: print empty_hash_map
+// gdb-check:$4 = HashMap<u64, &str, [...]>(len: 0)
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command: run
+
+// lldb-command: print hash_set
+// lldb-check:[...]$0 = {1, 2, 3}
+
+// lldb-command: print hash_map
+// lldb-check:[...]$1 = {1: "one", 2: "two", 3: "three"}
+
+// lldb-command: print single_hash_map
+// lldb-check:[...]$2 = {7: "seven"}
+
+// lldb-command: print empty_hash_map
+// lldb-check:[...]$3 = {}
+
+#![allow(unused_variables)]
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::{BuildHasherDefault, Hasher};
+
+#[derive(Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        unreachable!("only `u64` keys are hashed")
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n;
+    }
+}
+
+type Identity = BuildHasherDefault<IdentityHasher>;
+
+fn main() {
+    let mut hash_set: HashSet<u64, Identity> = HashSet::default();
+    hash_set.insert(3);
+    hash_set.insert(1);
+    hash_set.insert(2);
+
+    let mut hash_map: HashMap<u64, &str, Identity> = HashMap::default();
+    hash_map.insert(3, "three");
+    hash_map.insert(1, "one");
+    hash_map.insert(2, "two");
+
+    let mut single_hash_map: HashMap<u64, &str, Identity> = HashMap::default();
+    single_hash_map.insert(7, "seven");
+
+    let empty_hash_map: HashMap<u64, &str, Identity> = HashMap::default();
+
+    zzz(); // #break
+}
+
+fn zzz() { () }
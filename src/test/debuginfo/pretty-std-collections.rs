@@ -35,6 +35,31 @@
 // gdb-command: print vec_deque2
 // gdb-check:$7 = VecDeque<i32>(len: 7, cap: 8) = {2, 3, 4, 5, 6, 7, 8}
 
+// === LLDB TESTS ==================================================================================
+
+// lldb-command:run
+
+// lldb-command: print btree_set
+// lldb-check:[...]$0 = {0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14}
+
+// lldb-command: print empty_btree_set
+// lldb-check:[...]$1 = {}
+
+// lldb-command: print btree_map
+// lldb-check:[...]$2 = {0: 0, 1: 1, 2: 2, 3: 3, 4: 4, 5: 5, 6: 6, 7: 7, 8: 8, 9: 9, 10: 10, 11: 11, 12: 12, 13: 13, 14: 14}
+
+// lldb-command: print empty_btree_map
+// lldb-check:[...]$3 = {}
+
+// lldb-command: print nasty_btree_map
+// lldb-check:[...]$4 = {1: MyLeafNode(11)}
+
+// lldb-command: print vec_deque
+// lldb-check:[...]$5 = [5, 3, 7]
+
+// lldb-command: print vec_deque2
+// lldb-check:[...]$6 = [2, 3, 4, 5, 6, 7, 8]
+
 #![allow(unused_variables)]
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
@@ -0,0 +1,96 @@
+// ignore-tidy-linelength
+// ignore-windows failing on win32 bot
+// ignore-freebsd: gdb package too new
+// ignore-android: FIXME(#10381)
+// compile-flags:-g
+// min-gdb-version 7.7
+// min-lldb-version: 310
+
+// Checks that `None` and `Err` are recognized when they are stored in a niche
+// whose invalid value is not zero.
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command: run
+
+// gdb-command: print some_bool
+// gdbg-check:$1 = Some = {true}
+// gdbr-check:$1 = core::option::Option<bool>::Some(true)
+
+// gdb-command: print none_bool
+// gdbg-check:$2 = None
+// gdbr-check:$2 = core::option::Option<bool>::None
+
+// gdb-command: print some_char
+// gdbg-check:$3 = Some = {97 'a'}
+// gdbr-check:$3 = core::option::Option<char>::Some(97 'a')
+
+// gdb-command: print none_char
+// gdbg-check:$4 = None
+// gdbr-check:$4 = core::option::Option<char>::None
+
+// gdb-command: print some_ordering
+// gdbg-check:$5 = Some = {Less}
+// gdbr-check:$5 = core::option::Option<core::cmp::Ordering>::Some(core::cmp::Ordering::Less)
+
+// gdb-command: print none_ordering
+// gdbg-check:$6 = None
+// gdbr-check:$6 = core::option::Option<core::cmp::Ordering>::None
+
+// gdb-command: print ok_ref
+// gdbg-check:$7 = Ok = {[...]}
+// gdbr-check:$7 = core::result::Result<&u32, ()>::Ok([...])
+
+// gdb-command: print err_unit
+// gdbg-check:$8 = Err
+// gdbr-check:$8 = core::result::Result<&u32, ()>::Err(())
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command: run
+
+// lldb-command: print some_bool
+// lldb-check:[...]$0 = Some(true)
+
+// lldb-command: print none_bool
+// lldb-check:[...]$1 = None
+
+// lldb-command: print some_char
+// lldb-check:[...]$2 = Some('a')
+
+// lldb-command: print none_char
+// lldb-check:[...]$3 = None
+
+// lldb-command: print some_ordering
+// lldb-check:[...]$4 = Some(Less)
+
+// lldb-command: print none_ordering
+// lldb-check:[...]$5 = None
+
+// lldb-command: print ok_ref
+// lldb-check:[...]$6 = Ok(&[...])
+
+// lldb-command: print err_unit
+// lldb-check:[...]$7 = Err
+
+#![allow(unused_variables)]
+use std::cmp::Ordering;
+
+fn main() {
+    let some_bool = Some(true);
+    let none_bool: Option<bool> = None;
+
+    let some_char = Some('a');
+    let none_char: Option<char> = None;
+
+    let some_ordering = Some(Ordering::Less);
+    let none_ordering: Option<Ordering> = None;
+
+    let value = 11u32;
+    let ok_ref: Result<&u32, ()> = Ok(&value);
+    let err_unit: Result<&u32, ()> = Err(());
+
+    zzz(); // #break
+}
+
+fn zzz() { () }
@@ -0,0 +1,51 @@
+// ignore-tidy-linelength
+// ignore-windows failing on win32 bot
+// ignore-freebsd: gdb package too new
+// ignore-android: FIXME(#10381)
+// compile-flags:-g
+// min-gdb-version 7.7
+// min-lldb-version: 310
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command: run
+
+// gdb-command: print rc
+// gdb-check:$1 = Rc<i32>(strong: 2, weak: 1) = {value = 5}
+
+// gdb-command: print arc
+// gdb-check:$2 = Arc<i32>(strong: 1, weak: 0) = {value = 6}
+
+// gdb-command: print rc_vec
+// gdb-check:$3 = Rc<alloc::vec::Vec<u8>>(strong: 1, weak: 0) = {value = Vec<u8>(len: 2, cap: [...]) = {1, 2}}
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command: run
+
+// lldb-command: print rc
+// lldb-check:[...]$0 = Rc<i32> { strong: 2, weak: 1, value: 5 }
+
+// lldb-command: print arc
+// lldb-check:[...]$1 = Arc<i32> { strong: 1, weak: 0, value: 6 }
+
+// lldb-command: print rc_vec
+// lldb-check:[...]$2 = Rc<alloc::vec::Vec<u8>> { strong: 1, weak: 0, value: vec![1, 2] }
+
+#![allow(unused_variables)]
+use std::rc::Rc;
+use std::sync::Arc;
+
+fn main() {
+    let rc = Rc::new(5);
+    let rc_clone = rc.clone();
+    let rc_weak = Rc::downgrade(&rc);
+
+    let arc = Arc::new(6);
+
+    let rc_vec = Rc::new(vec![1u8, 2]);
+
+    zzz(); // #break
+}
+
+fn zzz() { () }
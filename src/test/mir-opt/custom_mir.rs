@@ -0,0 +1,54 @@
+// Test that a body written with `#[custom_mir]` is built exactly as written, and that it then goes
+// through the usual optimization pipeline.
+
+#![feature(custom_mir, core_intrinsics)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+#[custom_mir]
+fn chain(x: i32) -> i32 {
+    mir!(
+        let y: i32;
+
+        {
+            Goto(bb1)
+        }
+
+        bb1 = {
+            y = x;
+            Goto(bb2)
+        }
+
+        bb2 = {
+            RET = Move(y);
+            Return()
+        }
+    )
+}
+
+fn main() {
+    chain(5);
+}
+
+// END RUST SOURCE
+// START rustc.chain.SimplifyCfg-initial.before.mir
+//     bb0: {
+//         goto -> bb1;
+//     }
+//     bb1: {
+//         _2 = _1;
+//         goto -> bb2;
+//     }
+//     bb2: {
+//         _0 = move _2;
+//         return;
+//     }
+// END rustc.chain.SimplifyCfg-initial.before.mir
+// START rustc.chain.SimplifyCfg-initial.after.mir
+//     bb0: {
+//         _2 = _1;
+//         _0 = move _2;
+//         return;
+//     }
+// END rustc.chain.SimplifyCfg-initial.after.mir
@@ -0,0 +1,37 @@
+// compile-flags: -C overflow-checks=on
+
+fn add_twice(x: u32, y: u32) -> (u32, u32) {
+    (x + y, x + y)
+}
+
+fn main() {
+    add_twice(1, 2);
+}
+
+// END RUST SOURCE
+// START rustc.add_twice.GlobalValueNumbering.before.mir
+//  bb0: {
+//      ...
+//      _6 = CheckedAdd(move _4, move _5);
+//      assert(!move (_6.1: bool), "attempt to add with overflow") -> bb1;
+//  }
+//  bb1: {
+//      _3 = move (_6.0: u32);
+//      ...
+//      _10 = CheckedAdd(move _8, move _9);
+//      assert(!move (_10.1: bool), "attempt to add with overflow") -> bb2;
+//  }
+// END rustc.add_twice.GlobalValueNumbering.before.mir
+// START rustc.add_twice.GlobalValueNumbering.after.mir
+//  bb0: {
+//      ...
+//      _6 = CheckedAdd(move _4, move _5);
+//      assert(!(_6.1: bool), "attempt to add with overflow") -> bb1;
+//  }
+//  bb1: {
+//      _3 = (_6.0: u32);
+//      ...
+//      _10 = _6;
+//      assert(!move (_10.1: bool), "attempt to add with overflow") -> bb2;
+//  }
+// END rustc.add_twice.GlobalValueNumbering.after.mir
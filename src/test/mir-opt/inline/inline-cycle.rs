@@ -0,0 +1,26 @@
+// Check that inlining stops at cycles between local functions: `f` is inlined into `main`, but
+// `g` calls back into `f` and `main`, so it is not inlined into either of them.
+
+fn main() {
+    f(g);
+}
+
+#[inline(always)]
+fn f(g: impl Fn()) {
+    g();
+}
+
+#[inline(always)]
+fn g() {
+    f(main);
+}
+
+// END RUST SOURCE
+// START rustc.main.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _2 = const g() -> bb1;
+// }
+// ...
+// END rustc.main.Inline.after.mir
@@ -0,0 +1,42 @@
+// Check that the inliner does not inline across functions with different target features, even
+// for a feature that is always available, like `sse2` on x86_64.
+
+// only-x86_64
+
+fn main() {
+    unsafe {
+        sse2();
+        calls_plain();
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn sse2() {}
+
+#[target_feature(enable = "sse2")]
+unsafe fn calls_plain() -> u32 {
+    plain()
+}
+
+#[inline]
+fn plain() -> u32 {
+    7
+}
+
+// END RUST SOURCE
+// START rustc.main.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _1 = const sse2() -> bb1;
+// }
+// ...
+// END rustc.main.Inline.after.mir
+// START rustc.calls_plain.Inline.after.mir
+// ...
+// bb0: {
+//     _0 = const plain() -> bb1;
+// }
+// ...
+// END rustc.calls_plain.Inline.after.mir
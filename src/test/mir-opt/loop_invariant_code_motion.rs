@@ -0,0 +1,76 @@
+// Test that the length read of an indexed slice is hoisted out of the loop, while the bounds
+// check, which depends on the loop counter, stays in it.
+
+#![feature(custom_mir, core_intrinsics)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+#[custom_mir]
+fn sum(xs: &[u32], n: usize) -> u32 {
+    mir!(
+        let i: usize;
+        let len: usize;
+        let in_bounds: bool;
+        let cond: bool;
+
+        {
+            RET = 0;
+            i = 0;
+            Goto(header)
+        }
+
+        header = {
+            cond = i < n;
+            match cond {
+                false => exit,
+                _ => body,
+            }
+        }
+
+        body = {
+            len = Len(&*xs);
+            in_bounds = i < len;
+            RET = RET + xs[i];
+            i = i + 1;
+            Goto(header)
+        }
+
+        exit = {
+            Return()
+        }
+    )
+}
+
+fn main() {
+    sum(&[1, 2, 3], 3);
+}
+
+// END RUST SOURCE
+// START rustc.sum.LoopInvariantCodeMotion.before.mir
+//     bb0: {
+//         ...
+//         goto -> bb1;
+//     }
+//     ...
+//     bb2: {
+//         _4 = Len((*_1));
+//         _5 = Lt(_3, _4);
+//         ...
+//         goto -> bb1;
+//     }
+// END rustc.sum.LoopInvariantCodeMotion.before.mir
+// START rustc.sum.LoopInvariantCodeMotion.after.mir
+//     bb0: {
+//         ...
+//         _4 = Len((*_1));
+//         goto -> bb1;
+//     }
+//     ...
+//     bb2: {
+//         nop;
+//         _5 = Lt(_3, _4);
+//         ...
+//         goto -> bb1;
+//     }
+// END rustc.sum.LoopInvariantCodeMotion.after.mir
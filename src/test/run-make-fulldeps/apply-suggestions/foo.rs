@@ -0,0 +1,6 @@
+fn main() {
+    let mut x = (1);
+    let mut y = 2;
+    y += x;
+    println!("{}", y);
+}
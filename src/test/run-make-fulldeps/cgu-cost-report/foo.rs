@@ -0,0 +1,28 @@
+pub mod a {
+    pub fn large(x: u64) -> u64 {
+        let mut y = x;
+        for i in 0..x {
+            y = y.wrapping_mul(31).wrapping_add(i);
+            if y % 7 == 0 {
+                y /= 3;
+            }
+        }
+        y
+    }
+}
+
+pub mod b {
+    pub fn small(x: u64) -> u64 {
+        x + 1
+    }
+}
+
+pub mod c {
+    pub fn smaller() -> u64 {
+        1
+    }
+}
+
+fn main() {
+    println!("{}", a::large(b::small(c::smaller())));
+}
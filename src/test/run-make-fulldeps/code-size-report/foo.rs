@@ -0,0 +1,9 @@
+#[inline(never)]
+fn generic<T: std::fmt::Debug>(value: T) {
+    println!("{:?}", value);
+}
+
+fn main() {
+    generic(1u8);
+    generic(2u64);
+}
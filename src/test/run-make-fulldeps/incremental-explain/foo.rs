@@ -0,0 +1,15 @@
+#![crate_type = "rlib"]
+
+#[cfg(first)]
+pub fn answer() -> u32 {
+    42
+}
+
+#[cfg(not(first))]
+pub fn answer() -> u64 {
+    42
+}
+
+pub fn uses_answer() -> u64 {
+    answer() as u64 + 1
+}
@@ -0,0 +1,7 @@
+extern crate used;
+
+fn main() {
+    assert_eq!(used::generic_max(3, 7), 7);
+    assert_eq!(used::generic_max(2, 5), 5);
+    assert_eq!(used::double(4), 8);
+}
@@ -0,0 +1,11 @@
+// Uses the instrumented functions of `used.rs` without `-Z instrument-coverage`.
+
+extern crate used;
+
+pub fn max_byte(a: u8, b: u8) -> u8 {
+    used::generic_max(a, b)
+}
+
+pub fn quadruple(n: u32) -> u32 {
+    used::double(used::double(n))
+}
@@ -0,0 +1,12 @@
+pub fn generic_max<T: PartialOrd>(a: T, b: T) -> T {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+#[inline]
+pub fn double(n: u32) -> u32 {
+    n * 2
+}
@@ -0,0 +1,14 @@
+fn main() {
+    let mut countdown = 0;
+    if std::env::args().len() == 1 {
+        countdown = 10;
+    }
+
+    if countdown > 5 {
+        countdown -= 5;
+    } else {
+        countdown = 0;
+    }
+
+    assert_eq!(countdown, 5);
+}
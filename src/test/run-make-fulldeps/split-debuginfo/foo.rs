@@ -0,0 +1,4 @@
+fn main() {
+    let x = vec![1, 2, 3];
+    println!("{:?}", x);
+}
@@ -0,0 +1,36 @@
+#![crate_type = "lib"]
+
+pub struct Pair {
+    pub a: u8,
+    pub b: u32,
+}
+
+pub enum Tagged {
+    A(u32),
+    B(u16),
+    C,
+}
+
+pub enum Niched {
+    None,
+    Some(bool),
+}
+
+pub fn swap(pair: Pair) -> Pair {
+    Pair { a: pair.a, b: pair.b }
+}
+
+pub fn tagged(t: Tagged) -> u32 {
+    match t {
+        Tagged::A(a) => a,
+        Tagged::B(b) => b.into(),
+        Tagged::C => 0,
+    }
+}
+
+pub fn niched(n: Niched) -> bool {
+    match n {
+        Niched::None => false,
+        Niched::Some(b) => b,
+    }
+}
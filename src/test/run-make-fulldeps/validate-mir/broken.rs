@@ -0,0 +1,54 @@
+// Hand-written bodies that copy a `String` instead of moving it, use a local
+// after its storage is dead, and make the storage of a local live twice.
+
+#![feature(custom_mir, core_intrinsics)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+#[custom_mir]
+fn copy_string(s: String) -> String {
+    mir!(
+        {
+            RET = s;
+            Return()
+        }
+    )
+}
+
+#[custom_mir]
+fn use_after_storage_dead(x: i32) -> i32 {
+    mir!(
+        let y: i32;
+
+        {
+            StorageLive(y);
+            y = x;
+            StorageDead(y);
+            RET = y;
+            Return()
+        }
+    )
+}
+
+#[custom_mir]
+fn storage_live_twice(x: i32) -> i32 {
+    mir!(
+        let y: i32;
+
+        {
+            StorageLive(y);
+            y = x;
+            StorageLive(y);
+            RET = y;
+            StorageDead(y);
+            Return()
+        }
+    )
+}
+
+fn main() {
+    copy_string(String::new());
+    use_after_storage_dead(1);
+    storage_live_twice(1);
+}
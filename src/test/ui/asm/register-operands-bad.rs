@@ -0,0 +1,28 @@
+// only-x86_64
+
+#![feature(asm)]
+
+fn main() {
+    let foo = 0u64;
+    unsafe {
+        asm!("{}", in(foo) foo);
+        //~^ ERROR invalid register class `foo`: unknown register class
+        asm!("", in("rsp") foo);
+        //~^ ERROR invalid register `rsp`: the stack pointer cannot be used
+        asm!("{}", out(reg) _);
+        //~^ ERROR discarded outputs need an explicit register
+        asm!("{} {}", in(reg) foo);
+        //~^ ERROR invalid reference to argument at index 1
+        asm!("{}", in(reg) foo, in(reg) foo);
+        //~^ ERROR argument never used
+        asm!("{:q}", in(reg) foo);
+        //~^ ERROR invalid asm template modifier for this register class
+        asm!("", options(pure, nomem));
+        //~^ ERROR asm with the `pure` option must have at least one output
+        asm!("{}", in("eax") foo);
+        //~^ ERROR explicit register arguments cannot be used in the asm template
+        asm!("", in("eax") foo, in("al") foo);
+        //~^ ERROR register `ax` is used by more than one input
+    }
+    let _ = foo;
+}
@@ -0,0 +1,67 @@
+// run-pass
+// only-x86_64
+
+#![feature(asm)]
+
+fn add_one(x: u64) -> u64 {
+    let y;
+    unsafe {
+        asm!("lea {}, [{} + 1]", out(reg) y, in(reg) x);
+    }
+    y
+}
+
+fn mul_add(a: u64, b: u64, c: u64) -> u64 {
+    let result;
+    unsafe {
+        asm!(
+            "imul {0}, {1}",
+            "add {0}, {c}",
+            inout(reg) a => result,
+            in(reg) b,
+            c = in(reg) c,
+            options(pure, nomem, nostack),
+        );
+    }
+    result
+}
+
+fn low_byte(x: u32) -> u32 {
+    let mut y = x;
+    unsafe {
+        asm!("movzx {0:e}, {0:l}", inout(reg_abcd) y, options(pure, nomem, nostack));
+    }
+    y
+}
+
+fn explicit_registers(x: u32) -> u32 {
+    let y;
+    unsafe {
+        asm!("mul {}", in(reg) 2u32, inlateout("eax") x => y, lateout("edx") _);
+    }
+    y
+}
+
+fn att_syntax(x: u64) -> u64 {
+    let y;
+    unsafe {
+        asm!("movq {1}, {0}", "addq $3, {0}", out(reg) y, in(reg) x, options(att_syntax));
+    }
+    y
+}
+
+fn discarded_inout(x: u64) -> u64 {
+    unsafe {
+        asm!("add {}, 1", inout(reg) x => _, options(nomem, nostack));
+    }
+    x
+}
+
+fn main() {
+    assert_eq!(add_one(41), 42);
+    assert_eq!(mul_add(3, 4, 5), 17);
+    assert_eq!(low_byte(0x1234), 0x34);
+    assert_eq!(explicit_registers(21), 42);
+    assert_eq!(att_syntax(39), 42);
+    assert_eq!(discarded_inout(42), 42);
+}
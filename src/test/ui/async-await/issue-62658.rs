@@ -4,6 +4,8 @@
 // build-pass
 // edition:2018
 
+#![allow(let_underscore_future)]
+
 async fn noop() {}
 
 async fn foo() {
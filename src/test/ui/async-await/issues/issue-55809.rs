@@ -1,6 +1,8 @@
 // edition:2018
 // run-pass
 
+#![allow(let_underscore_future)]
+
 trait Foo { }
 
 impl Foo for () { }
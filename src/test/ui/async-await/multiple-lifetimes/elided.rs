@@ -3,6 +3,8 @@
 
 // Test that we can use async fns with multiple arbitrary lifetimes.
 
+#![allow(let_underscore_future)]
+
 async fn multiple_elided_lifetimes(_: &u8, _: &u8) {}
 
 fn main() {
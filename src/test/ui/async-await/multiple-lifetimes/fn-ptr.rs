@@ -3,6 +3,8 @@
 
 // Test that we can use async fns with multiple arbitrary lifetimes.
 
+#![allow(let_underscore_future)]
+
 async fn multiple_named_lifetimes<'a, 'b>(_: &'a u8, _: &'b u8, _: fn(&u8)) {}
 
 fn gimme(_: &u8) { }
@@ -1,6 +1,8 @@
 // edition:2018
 // run-pass
 
+#![allow(let_underscore_future)]
+
 async fn lotsa_lifetimes<'a, 'b, 'c>(a: &'a u32, b: &'b u32, c: &'c u32) -> (&'a u32, &'b u32)
     where 'b: 'a
 {
@@ -5,6 +5,7 @@
 // return type of an async.
 
 #![feature(member_constraints)]
+#![allow(let_underscore_future)]
 
 trait Trait<'a, 'b> { }
 impl<T> Trait<'_, '_> for T { }
@@ -4,6 +4,8 @@
 // Test for async fn where the parameters have distinct lifetime
 // parameters that appear in all possible variances.
 
+#![allow(let_underscore_future)]
+
 async fn lotsa_lifetimes<'a, 'b, 'c>(_: fn(&'a u8), _: fn(&'b u8) -> &'b u8, _: fn() -> &'c u8) { }
 
 fn take_any(_: &u8) { }
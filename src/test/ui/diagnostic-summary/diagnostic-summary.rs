@@ -0,0 +1,10 @@
+// Checks that `-Z diagnostic-summary` reports the number of errors and warnings of each error
+// code and lint, with the errors first.
+
+// compile-flags: -Z diagnostic-summary
+
+fn main() {
+    let _a: i32 = "foo"; //~ ERROR mismatched types
+    let _b = (1); //~ WARN unnecessary parentheses
+    let _c: i32 = "bar"; //~ ERROR mismatched types
+}
@@ -0,0 +1,2 @@
+#[custom_mir] //~ ERROR the `#[custom_mir]` attribute is just used for the Rust test suite
+fn main() {}
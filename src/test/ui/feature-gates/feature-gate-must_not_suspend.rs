@@ -0,0 +1,6 @@
+#![deny(must_not_suspend)] //~ ERROR the `must_not_suspend` lint is unstable
+
+#[must_not_suspend = "reason"] //~ ERROR the `#[must_not_suspend]` attribute is an experimental feature
+struct Guard;
+
+fn main() {}
@@ -0,0 +1,41 @@
+// check-pass
+// edition:2018
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+async fn foo() {}
+
+#[must_use]
+struct MustPoll;
+
+impl Future for MustPoll {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+// Like the handle of a spawned task, which detaches the task when dropped.
+struct Detach;
+
+impl Future for Detach {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+fn main() {
+    let _ = foo(); //~ WARNING non-binding let on a future
+    let _ = MustPoll; //~ WARNING non-binding let on a future
+
+    // Bound futures can still be polled later.
+    let fut = foo();
+    // A place is not dropped by a non-binding let.
+    let _ = fut;
+
+    // Dropping futures that are not `#[must_use]` can be intended.
+    let _ = Detach;
+}
@@ -0,0 +1,27 @@
+// check-pass
+
+use std::sync::{Mutex, RwLock};
+
+fn main() {
+    let data = Mutex::new(0);
+    let _ = data.lock(); //~ WARNING non-binding let on a synchronization lock
+
+    let lock = RwLock::new(0);
+    let _ = lock.read().unwrap(); //~ WARNING non-binding let on a synchronization lock
+
+    // Bound guards are held until the end of their scope.
+    let _guard = lock.write().unwrap();
+
+    not_dropped(&Mutex::new(0));
+}
+
+fn not_dropped(data: &Mutex<i32>) {
+    // A place is neither moved nor dropped by a non-binding let.
+    let guard = data.lock().unwrap();
+    let _ = guard;
+
+    // Neither are the guards the value only borrows.
+    let _ = &guard;
+    let guards = vec![guard];
+    let _ = guards.iter();
+}
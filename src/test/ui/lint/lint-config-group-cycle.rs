@@ -0,0 +1,6 @@
+// Checks that a custom lint group of `-Z lint-config` cannot contain itself.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config-group-cycle.json
+// error-pattern: lint group `first` of the lint configuration file
+
+fn main() {}
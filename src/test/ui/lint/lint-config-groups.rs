@@ -0,0 +1,12 @@
+// Checks that the custom lint groups of `-Z lint-config` can contain groups defined after them,
+// and that the levels of overlapping lint groups apply in the order of the file, not in the
+// alphabetical order of their names.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config-groups.json
+
+#![allow(unused_variables)]
+
+fn main() {
+    let x = (1); //~ WARN unnecessary parentheses
+    let Unused = x; //~ ERROR should have a snake case name
+}
@@ -0,0 +1,10 @@
+// Checks that the levels of `-Z lint-config` apply to its custom lint groups, that the levels of
+// single lints override those of lint groups, and that the flags override the file.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config.json -W unused-mut
+
+fn main() {
+    let mut x = (1); //~ ERROR unnecessary parentheses
+    //~^ WARN does not need to be mutable
+    let Unused = x; //~ WARN should have a snake case name
+}
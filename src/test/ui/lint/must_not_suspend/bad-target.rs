@@ -0,0 +1,6 @@
+#![feature(must_not_suspend)]
+
+#[must_not_suspend] //~ ERROR attribute can only be applied to a struct, enum or union
+fn foo() {}
+
+fn main() {}
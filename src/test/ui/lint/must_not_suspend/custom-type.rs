@@ -0,0 +1,33 @@
+// edition:2018
+#![feature(must_not_suspend)]
+#![deny(must_not_suspend)]
+
+#[must_not_suspend = "an `Umm` must be dropped before suspending"]
+struct Umm {
+    _i: i64,
+}
+
+fn bar() -> Umm {
+    Umm { _i: 1 }
+}
+
+async fn other() {}
+
+pub async fn uhoh() {
+    let _guard = bar(); //~ ERROR `Umm` held across a suspend point, but should not be
+    other().await;
+}
+
+pub async fn boxed_in_tuple() {
+    let _guard = (Box::new(bar()), 1); //~ ERROR boxed `Umm` in tuple element 0 held across
+    other().await;
+}
+
+pub async fn dropped_before_await() {
+    {
+        let _guard = bar();
+    }
+    other().await;
+}
+
+fn main() {}
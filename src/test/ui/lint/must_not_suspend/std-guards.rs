@@ -0,0 +1,20 @@
+// edition:2018
+#![feature(must_not_suspend)]
+#![deny(must_not_suspend)]
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+async fn other() {}
+
+pub async fn mutex(m: &Mutex<u32>) {
+    let _guard = m.lock().unwrap(); //~ ERROR `std::sync::MutexGuard` held across
+    other().await;
+}
+
+pub async fn ref_cell(c: &RefCell<u32>) {
+    let _borrow = c.borrow_mut(); //~ ERROR `std::cell::RefMut` held across
+    other().await;
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![feature(non_ascii_idents)]
+#![deny(confusable_idents)]
+#![allow(uncommon_codepoints, non_upper_case_globals)]
+
+const l: usize = 42;
+const ǀ: usize = 42; //~ ERROR identifier pair considered confusable
+
+fn main() {
+    let _ = ǀ + l;
+}
@@ -0,0 +1,11 @@
+#![feature(non_ascii_idents)]
+#![deny(mixed_script_confusables)]
+#![allow(mixed_script_idents)]
+
+fn rustc() {}
+fn rustс() {} //~ ERROR identifier pair considered confusable
+
+fn main() {
+    rustc();
+    rustс();
+}
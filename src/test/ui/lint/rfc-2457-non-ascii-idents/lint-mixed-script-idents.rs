@@ -0,0 +1,13 @@
+#![feature(non_ascii_idents)]
+#![deny(mixed_script_idents)]
+
+fn rustс() {} //~ ERROR identifier contains characters of several scripts
+
+const ЗНАЧЕНИЕ: u32 = 1;
+
+fn main() {
+    let パスワード = ЗНАЧЕНИЕ;
+    let 日本語テキスト = パスワード;
+    let _ = 日本語テキスト;
+    rustс(); //~ ERROR identifier contains characters of several scripts
+}
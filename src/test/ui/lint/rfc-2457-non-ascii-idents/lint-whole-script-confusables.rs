@@ -0,0 +1,12 @@
+#![feature(non_ascii_idents)]
+#![deny(whole_script_confusables)]
+#![allow(uncommon_codepoints, non_upper_case_globals)]
+
+const s: usize = 42;
+const ѕ: usize = 42; //~ ERROR identifier pair considered confusable
+
+fn main() {
+    let рое = ѕ + s;
+    let poe = рое; //~ ERROR identifier pair considered confusable
+    let _ = poe;
+}
@@ -0,0 +1,41 @@
+// Checks that `Discriminant` in a `#[custom_mir]` body only reads the discriminants that are
+// `isize`s, like the one `Discriminant` returns.
+
+#![feature(custom_mir, core_intrinsics)]
+#![allow(dead_code)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+enum Plain {
+    A,
+    B,
+}
+
+#[repr(u8)]
+enum Byte {
+    A,
+    B,
+}
+
+#[custom_mir]
+fn plain(e: Plain) -> isize {
+    mir!(
+        {
+            RET = Discriminant(e);
+            Return()
+        }
+    )
+}
+
+#[custom_mir]
+fn byte(e: Byte) -> isize {
+    mir!(
+        {
+            RET = Discriminant(e); //~ ERROR could not parse discriminant
+            Return()
+        }
+    )
+}
+
+fn main() {}
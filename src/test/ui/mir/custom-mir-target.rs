@@ -0,0 +1,22 @@
+// Checks that `#[custom_mir]` can only be applied to fn items.
+
+#![feature(custom_mir, stmt_expr_attributes)]
+#![allow(dead_code)]
+
+#[custom_mir] //~ ERROR attribute should be applied to a function item
+const CONST: i32 = 1;
+
+#[custom_mir] //~ ERROR attribute should be applied to a function item
+static STATIC: i32 = 1;
+
+struct Struct;
+
+impl Struct {
+    #[custom_mir] //~ ERROR attribute should be applied to a function item
+    fn method(&self) {}
+}
+
+fn main() {
+    let _closure = #[custom_mir] || 1;
+    //~^ ERROR attribute should be applied to a function item
+}
@@ -0,0 +1,25 @@
+// run-pass
+// compile-flags: -O -Z inline-mir
+// Check that the inliner terminates and does not run into query cycles on mutually recursive
+// functions.
+
+fn main() {
+    assert_eq!(even(10), true);
+    assert_eq!(odd(7), true);
+    assert_eq!(apply(&|n| n + 1, 3), 3);
+}
+
+#[inline]
+fn even(n: u32) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+#[inline]
+fn odd(n: u32) -> bool {
+    if n == 0 { false } else { even(n - 1) }
+}
+
+#[inline]
+fn apply(f: &dyn Fn(u32) -> u32, n: u32) -> u32 {
+    if n == 0 { 0 } else { f(apply(f, n - 1)) }
+}
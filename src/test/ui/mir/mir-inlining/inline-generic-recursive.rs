@@ -0,0 +1,57 @@
+// run-pass
+// compile-flags: -O -Z inline-mir
+// Check the inliner on recursive functions, generic functions, trait methods of type parameters
+// and functions with target features.
+
+use std::fmt::Debug;
+
+fn main() {
+    assert_eq!(countdown(5), 0);
+    assert_eq!(twice(&|n| n * 3, 2), 18);
+    assert_eq!(describe(&[1, 2]), "[1, 2]");
+    assert_eq!(describe(&Some("x")), "Some(\"x\")");
+    assert_eq!(with_features(20), 42);
+}
+
+#[inline]
+fn countdown(n: u32) -> u32 {
+    if n == 0 { 0 } else { countdown(n - 1) }
+}
+
+#[inline]
+fn twice<F: Fn(u32) -> u32>(f: &F, n: u32) -> u32 {
+    f(f(n))
+}
+
+#[inline]
+fn describe<T: Debug + ?Sized>(value: &T) -> String {
+    format(value)
+}
+
+#[inline]
+fn format<T: Debug + ?Sized>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn with_features(n: u32) -> u32 {
+    // `sse2` is always available on x86_64.
+    unsafe { add_two_sse2(n * 2) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn add_two_sse2(n: u32) -> u32 {
+    add_two(n)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn with_features(n: u32) -> u32 {
+    add_two(n * 2)
+}
+
+#[inline]
+fn add_two(n: u32) -> u32 {
+    n + 2
+}
@@ -0,0 +1,43 @@
+// run-pass
+// compile-flags: -Z validate-mir -Z mir-opt-level=2
+// Checks that the MIR produced for common constructs passes validation after every pass.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::Generator;
+use std::pin::Pin;
+
+fn sum(xs: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..xs.len() {
+        total += xs[i];
+    }
+    total
+}
+
+fn describe(x: Option<Box<u32>>) -> String {
+    match x {
+        Some(b) if *b > 10 => format!("big {}", b),
+        Some(b) => format!("small {}", b),
+        None => String::from("none"),
+    }
+}
+
+fn apply(f: for<'a> fn(&'a u32) -> &'a u32, x: &u32) -> u32 {
+    *f(x)
+}
+
+fn main() {
+    assert_eq!(sum(&[1, 2, 3]), 6);
+    assert_eq!(describe(Some(Box::new(11))), "big 11");
+    assert_eq!(describe(None), "none");
+    assert_eq!(apply(|x| x, &4), 4);
+
+    let v = vec![1, 2];
+    let mut gen = move || {
+        yield v.len();
+        v[0]
+    };
+    let _ = Pin::new(&mut gen).resume(());
+    let _ = Pin::new(&mut gen).resume(());
+}
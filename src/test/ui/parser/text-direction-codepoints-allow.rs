@@ -0,0 +1,10 @@
+// check-pass
+// The text direction lints are emitted before the attributes of items are
+// known, and are silenced by allowing them at the crate root.
+
+#![allow(text_direction_codepoint_in_comment, text_direction_codepoint_in_literal)]
+
+fn main() {
+    // if access_level != "user" { ‮ ⁦// Check if admin⁩ ⁦
+    let _ = "user‮ ⁦// Check if admin⁩ ⁦";
+}
@@ -0,0 +1,9 @@
+// check-pass
+// Codepoints changing the direction of text make code display differently from
+// how it is compiled, unlike their escapes.
+
+fn main() {
+    // if access_level != "user" { ‮ ⁦// Check if admin⁩ ⁦
+    let _ = "user‮ ⁦// Check if admin⁩ ⁦";
+    let _ = "\u{202E}";
+}
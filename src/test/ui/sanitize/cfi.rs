@@ -0,0 +1,44 @@
+// Verifies that calls through trait objects and through pointers to functions
+// of the standard library, which is compiled without the sanitizer, pass.
+//
+// needs-sanitizer-support
+// only-linux
+// only-x86_64
+//
+// compile-flags: -Z sanitizer=cfi -C lto -O
+//
+// run-pass
+
+#![feature(test)]
+
+use std::hint::black_box;
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn main() {
+    let shape: Box<dyn Shape> = black_box(Box::new(Square(3)));
+    assert_eq!(shape.area(), 9);
+    drop(shape);
+
+    let len: fn(&str) -> usize = black_box(str::len);
+    assert_eq!(len("four"), 4);
+    let max: fn(u32, u32) -> u32 = black_box(std::cmp::max);
+    assert_eq!(max(1, 2), 2);
+
+    let double: fn(u32) -> u32 = black_box(double);
+    assert_eq!(double(4), 8);
+}
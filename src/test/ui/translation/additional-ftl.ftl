@@ -0,0 +1 @@
+parser-invalid-digit = chiffre invalide pour un littéral en base { $base }
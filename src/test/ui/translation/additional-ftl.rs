@@ -0,0 +1,9 @@
+// Checks that the messages of `-Z translate-additional-ftl` replace the built-in ones, and that
+// the built-in messages are used for the messages it does not have.
+
+// compile-flags: -Z translate-additional-ftl={{src-base}}/translation/additional-ftl.ftl
+
+fn main() {
+    0o18; //~ ERROR chiffre invalide pour un littéral en base 8
+    0b; //~ ERROR no valid digits found for number
+}